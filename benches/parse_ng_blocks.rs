@@ -3,7 +3,7 @@
 // Generate test with criterion
 use criterion::{Criterion, criterion_group, criterion_main};
 use rusty_pcap::pcap_ng::blocks::Block;
-use std::hint::black_box;
+use std::{borrow::Cow, hint::black_box};
 
 fn parse_enhanced_packets(c: &mut Criterion) {
     let block_content = {
@@ -15,12 +15,15 @@ fn parse_enhanced_packets(c: &mut Criterion) {
             timestamp_low: 6000,
             captured_length: packet_content.len() as u32,
             original_length: packet_content.len() as u32,
-            content: &packet_content,
+            content: Cow::Borrowed(&packet_content),
             options: None,
         };
         let mut buffer = Vec::new();
         packet
-            .write(&mut buffer, rusty_pcap::byte_order::LittleEndian)
+            .write(
+                &mut buffer,
+                rusty_pcap::byte_order::Endianness::LittleEndian,
+            )
             .unwrap();
         buffer
     };
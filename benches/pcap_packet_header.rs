@@ -14,6 +14,7 @@ fn parse_packet_header_little_endian(c: &mut Criterion) {
             timestamp: PacketTimestamp::try_from(SystemTime::now()).unwrap(),
             orig_len: 100,
             include_len: 100,
+            extended: None,
         };
         let mut buf = Vec::new();
         packet_header
@@ -44,6 +45,7 @@ fn parse_packet_header_big_endian(c: &mut Criterion) {
             timestamp: PacketTimestamp::try_from(SystemTime::now()).unwrap(),
             orig_len: 100,
             include_len: 100,
+            extended: None,
         };
         let mut buf = Vec::new();
         packet_header
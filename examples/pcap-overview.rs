@@ -12,39 +12,24 @@ struct PcapOverview {
 fn main() -> anyhow::Result<()> {
     let cli = PcapOverview::parse();
     let file = File::open(cli.file)?;
-    let packet_reader = SyncAnyPcapReader::new(BufReader::new(file))?;
-
-    let mut packet_count = 0;
-    let mut average_number_of_bytes: usize = 0;
-    let mut highest_packet_size: usize = 0;
-    let mut lowest_packet_size: usize = 0;
-    let mut packet_reader = packet_reader;
-    while let Some((_header, data)) = packet_reader.next_packet()? {
-        packet_count += 1;
-        let packet_size = data.len();
-        average_number_of_bytes += packet_size;
-        if packet_size > highest_packet_size {
-            highest_packet_size = packet_size;
-        }
-        if lowest_packet_size == 0 || packet_size < lowest_packet_size {
-            lowest_packet_size = packet_size;
-        }
-    }
-    average_number_of_bytes = average_number_of_bytes
-        .checked_div(packet_count)
-        .unwrap_or(0);
+    let stats = SyncAnyPcapReader::new(BufReader::new(file))?.stats()?;
 
     println!("-- PCAP File Overview --");
-    println!("Total packets in pcap file: {}", packet_count);
+    println!("Total packets in pcap file: {}", stats.packet_count);
     println!(
         "Pcap file version: {}.{}",
-        packet_reader.version().major,
-        packet_reader.version().minor
+        stats.version.major, stats.version.minor
+    );
+    println!("Pcap file type: {}", stats.file_type);
+    println!("Average packet size: {} bytes", stats.average_packet_size());
+    println!(
+        "Highest packet size: {} bytes",
+        stats.largest_packet.unwrap_or(0)
+    );
+    println!(
+        "Lowest packet size: {} bytes",
+        stats.smallest_packet.unwrap_or(0)
     );
-    println!("Pcap file type: {}", packet_reader.file_type());
-    println!("Average packet size: {} bytes", average_number_of_bytes);
-    println!("Highest packet size: {} bytes", highest_packet_size);
-    println!("Lowest packet size: {} bytes", lowest_packet_size);
     println!("-- End of Overview --");
     Ok(())
 }
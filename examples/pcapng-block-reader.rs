@@ -15,6 +15,13 @@ struct BlockReaderCLI {
         help = "If set, will fail on generic blocks. This is useful for debugging."
     )]
     fail_on_generic: bool,
+    #[clap(
+        long,
+        default_value = "false",
+        action = clap::ArgAction::SetTrue,
+        help = "If set, packet blocks will not be printed."
+    )]
+    skip_packets: bool,
 
     // Path to the pcapng file to read
     file: PathBuf,
@@ -31,6 +38,9 @@ fn main() -> anyhow::Result<()> {
                 "Encountered a generic block, which is not expected."
             ));
         }
+        if cli.skip_packets && block.is_packet() {
+            continue;
+        }
         debug_print_block(&block);
     }
     Ok(())
@@ -118,6 +128,15 @@ fn debug_print_block(block: &PcapNgBlock) {
             }
             println!("--- End of Decryption Secrets Block ---");
         }
+        PcapNgBlock::LegacyPacket(packet) => {
+            println!("--- Packet Block (obsolete) ---");
+            println!("Block Length: {}", packet.block_length);
+            println!("Interface ID: {}", packet.interface_id);
+            println!("Drops Count: {}", packet.drops_count);
+            println!("Original Length: {}", packet.original_length);
+            println!("Content Length: {}", packet.content.len());
+            println!("--- End of Packet Block ---");
+        }
     }
 }
 
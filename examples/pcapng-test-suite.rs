@@ -299,6 +299,7 @@ fn probe(path: &Path) -> Result<Counts, String> {
                 PcapNgBlock::NameResolution(_) => counts.bump_abbr("NRB"),
                 PcapNgBlock::InterfaceStatistics(_) => counts.bump_abbr("ISB"),
                 PcapNgBlock::DecryptionSecrets(_) => counts.bump_abbr("DSB"),
+                PcapNgBlock::LegacyPacket(_) => counts.bump_abbr("PB"),
                 PcapNgBlock::Custom(cb) => {
                     // The test corpus distinguishes "CB" (may-copy) from "DCB"
                     // (do-not-copy) in its descriptors, so mirror that split
@@ -318,7 +319,9 @@ fn probe(path: &Path) -> Result<Counts, String> {
 }
 
 // Abbreviations that map onto real PcapNgBlock variants (not Generic).
-const NATIVE_ABBRS: &[&str] = &["SHB", "IDB", "EPB", "SPB", "NRB", "ISB", "CB", "DCB", "DSB"];
+const NATIVE_ABBRS: &[&str] = &[
+    "SHB", "IDB", "EPB", "SPB", "NRB", "ISB", "CB", "DCB", "DSB", "PB",
+];
 
 fn is_native(abbr: &str) -> bool {
     NATIVE_ABBRS.contains(&abbr)
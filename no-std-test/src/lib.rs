@@ -0,0 +1,42 @@
+//! Tests for rusty-pcap built without the `std` feature
+//!
+//! The main crate's dev-dependency on itself turns `std` back on for its own tests, so the `no_std` build is
+//! tested from this separate crate. Run with `cargo test --manifest-path no-std-test/Cargo.toml`.
+#![no_std]
+
+#[cfg(test)]
+mod tests {
+    use rusty_pcap::{
+        byte_order::Endianness,
+        link_type::LinkType,
+        pcap::file_header::{MagicNumber, PcapFileHeader},
+        pcap_ng::blocks::{EnhancedPacket, InterfaceDescriptionBlock, SectionHeaderBlock},
+    };
+
+    static PCAP: &[u8] = include_bytes!("../../test_data/test.pcap");
+    static PCAP_NG: &[u8] = include_bytes!("../../test_data/test.pcapng");
+
+    #[test]
+    fn parse_pcap_file_header() {
+        let header = PcapFileHeader::try_from(PCAP).unwrap();
+        assert_eq!(header.resolution(), MagicNumber::Microsecond);
+        assert_eq!(header.endianness(), Endianness::LittleEndian);
+        assert_eq!(header.link_type, LinkType::Ethernet);
+    }
+
+    #[test]
+    fn parse_pcap_ng_blocks() {
+        let (section, section_length) = SectionHeaderBlock::parse_from_slice(PCAP_NG).unwrap();
+        let byte_order = section.byte_order;
+        let rest = &PCAP_NG[section_length..];
+
+        let (interface, interface_length) =
+            InterfaceDescriptionBlock::parse_from_slice(rest, byte_order).unwrap();
+        assert_eq!(interface.link_type, LinkType::Ethernet);
+        let rest = &rest[interface_length..];
+
+        let (packet, _) = EnhancedPacket::parse_from_slice(rest, byte_order).unwrap();
+        assert_eq!(packet.interface_id, 0);
+        assert_eq!(packet.content.len(), packet.captured_length as usize);
+    }
+}
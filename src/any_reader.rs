@@ -5,12 +5,21 @@ use thiserror::Error;
 
 use crate::{
     PcapFileType, Version,
+    link_type::LinkType,
     pcap::{PcapParseError, SyncPcapReader, file_header::PcapFileHeader},
     pcap_ng::{PcapNgParseError, SyncPcapNgReader, blocks::SectionHeaderBlock},
     utils::PeakableReader,
 };
 mod header;
+mod metadata;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod stats;
 pub use header::*;
+pub use metadata::{InterfaceMetadata, PcapMetadata, read_metadata};
+#[cfg(feature = "rayon")]
+pub use parallel::scan_files_parallel;
+pub use stats::PcapStats;
 #[cfg(feature = "tokio-async")]
 mod tokio_impl;
 #[cfg(feature = "tokio-async")]
@@ -33,6 +42,46 @@ pub enum AnyPcapReaderError {
     #[error(transparent)]
     IOError(#[from] std::io::Error),
 }
+impl AnyPcapReaderError {
+    /// Returns true if the file ended in the middle of a header or packet
+    ///
+    /// Files that are still being written often end this way, so reading can be retried once more data is available
+    pub fn is_eof(&self) -> bool {
+        matches!(
+            self,
+            AnyPcapReaderError::PcapNgError(PcapNgParseError::TruncatedBlock { .. })
+        ) || self
+            .io_error()
+            .is_some_and(|err| err.kind() == std::io::ErrorKind::UnexpectedEof)
+    }
+    /// Returns true if the file is not a pcap or pcap-ng file or its contents are malformed
+    ///
+    /// I/O errors, including [`Self::is_eof`], are not format errors. Neither is
+    /// [`PcapParseError::PacketExceedsMaxBuffer`] as it depends on the reader configuration
+    pub fn is_format_error(&self) -> bool {
+        match self {
+            AnyPcapReaderError::InvalidPcapFormat => true,
+            AnyPcapReaderError::PcapError(err) => !matches!(
+                err,
+                PcapParseError::IO(_) | PcapParseError::PacketExceedsMaxBuffer { .. }
+            ),
+            AnyPcapReaderError::PcapNgError(err) => !matches!(
+                err,
+                PcapNgParseError::IO(_) | PcapNgParseError::TruncatedBlock { .. }
+            ),
+            AnyPcapReaderError::IOError(_) => false,
+        }
+    }
+    /// The wrapped I/O error, if any
+    fn io_error(&self) -> Option<&std::io::Error> {
+        match self {
+            AnyPcapReaderError::PcapError(PcapParseError::IO(err))
+            | AnyPcapReaderError::PcapNgError(PcapNgParseError::IO(err))
+            | AnyPcapReaderError::IOError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
 #[derive(Debug)]
 enum SyncAnyPcapReaderInner<R: std::io::Read> {
     Pcap(SyncPcapReader<R>),
@@ -66,6 +115,7 @@ impl<R: Read> SyncAnyPcapReaderInner<R> {
         match file_type {
             PcapFileType::Pcap => {
                 let header = PcapFileHeader::read(&mut peakable)?;
+                header.validate_snap_length()?;
                 drop(peakable);
                 Ok(SyncAnyPcapReaderInner::Pcap(
                     SyncPcapReader::new_with_header(reader, header),
@@ -111,9 +161,8 @@ impl<R: Read> SyncAnyPcapReader<R> {
     }
     /// Reads the next packet from the pcap or pcapng file
     ///
-    /// # Why Cow?
-    ///
-    /// The PcapNg packets do not have a fixed size buffer and each packet is read into a newly allocated `Vec<u8>`
+    /// For both file types the packet data borrows the underlying reader's reusable packet buffer,
+    /// so no allocation is made per packet. Copy the data to keep it past the next read.
     pub fn next_packet(&mut self) -> Result<Option<AnyPcapPacket<'_>>, AnyPcapReaderError> {
         match &mut self.inner {
             SyncAnyPcapReaderInner::Pcap(pcap_reader) => match pcap_reader.next_packet()? {
@@ -142,6 +191,31 @@ impl<R: Read> SyncAnyPcapReader<R> {
             SyncAnyPcapReaderInner::PcapNg(pcapng_reader) => pcapng_reader.version(),
         }
     }
+    /// Reads every remaining packet and returns statistics about them
+    ///
+    /// The version is the version when this is called. Only packets not read yet are counted
+    pub fn stats(mut self) -> Result<PcapStats, AnyPcapReaderError> {
+        let mut stats = PcapStats::new(self.file_type(), *self.version());
+        while let Some((_, data)) = self.next_packet()? {
+            stats.record(data.len());
+        }
+        Ok(stats)
+    }
+    /// Returns the link type of the interface with `interface_id`
+    ///
+    /// pcap files only have interface 0, which uses the link type of the file header.
+    /// For pcapng files only interfaces of the current section are known
+    pub fn link_type(&self, interface_id: u32) -> Option<LinkType> {
+        match &self.inner {
+            SyncAnyPcapReaderInner::Pcap(pcap_reader) => {
+                (interface_id == 0).then_some(pcap_reader.file_header().link_type)
+            }
+            SyncAnyPcapReaderInner::PcapNg(pcapng_reader) => pcapng_reader
+                .interfaces()
+                .get(interface_id as usize)
+                .map(|interface| interface.link_type),
+        }
+    }
 }
 #[cfg(test)]
 mod tests {
@@ -205,4 +279,100 @@ mod tests {
             }
         }
     }
+    #[test]
+    fn next_packet_reuses_buffer() -> anyhow::Result<()> {
+        use std::io::Cursor;
+
+        use crate::{
+            byte_order::Endianness,
+            link_type::LinkType,
+            pcap::{
+                file_header::PcapFileHeader,
+                writer::{NewPacketHeader, SyncPcapWriter},
+            },
+            pcap_ng::{
+                blocks::{InterfaceDescriptionBlock, SectionHeaderBlock},
+                writer::SyncPcapNgWriter,
+            },
+        };
+
+        let mut pcap = SyncPcapWriter::new(Cursor::new(Vec::new()), PcapFileHeader::default())?;
+        let mut pcap_ng = SyncPcapNgWriter::new(
+            Vec::new(),
+            SectionHeaderBlock::new(Endianness::LittleEndian),
+        )?;
+        pcap_ng.add_interface(InterfaceDescriptionBlock::new(LinkType::Ethernet, 0))?;
+        for packet in [[1u8; 8], [2u8; 8]] {
+            pcap.write_header(NewPacketHeader::default(), &packet)?;
+            pcap_ng.write_packet(0, 0, &packet, None)?;
+        }
+        for file in [pcap.into_inner()?.into_inner(), pcap_ng.into_inner()] {
+            let mut reader = SyncAnyPcapReader::new(file.as_slice())?;
+            let (_, first) = reader.next_packet()?.expect("first packet");
+            assert_eq!(first, &[1; 8]);
+            let first = first.as_ptr();
+            let (_, second) = reader.next_packet()?.expect("second packet");
+            assert_eq!(second, &[2; 8]);
+            assert_eq!(
+                first,
+                second.as_ptr(),
+                "{} packets must be read into the same buffer",
+                reader.file_type()
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn error_classification() -> anyhow::Result<()> {
+        use std::io::{Error, ErrorKind};
+
+        use super::*;
+
+        let eof = || Error::from(ErrorKind::UnexpectedEof);
+        let cases = [
+            (AnyPcapReaderError::InvalidPcapFormat, false, true),
+            (AnyPcapReaderError::IOError(eof()), true, false),
+            (
+                AnyPcapReaderError::IOError(Error::from(ErrorKind::PermissionDenied)),
+                false,
+                false,
+            ),
+            (PcapParseError::IO(eof()).into(), true, false),
+            (PcapParseError::InvalidVersion.into(), false, true),
+            (
+                PcapParseError::PacketExceedsMaxBuffer {
+                    max_buffer: 16,
+                    incl_len: 32,
+                }
+                .into(),
+                false,
+                false,
+            ),
+            (PcapNgParseError::IO(eof()).into(), true, false),
+            (
+                PcapNgParseError::InvalidEndianness { got: [0; 4] }.into(),
+                false,
+                true,
+            ),
+        ];
+        for (err, is_eof, is_format_error) in cases {
+            assert_eq!(err.is_eof(), is_eof, "{err:?}");
+            assert_eq!(err.is_format_error(), is_format_error, "{err:?}");
+        }
+
+        // A packet cut off part way through
+        let file = std::fs::read("test_data/test.pcap")?;
+        let mut reader = SyncAnyPcapReader::new(&file[..file.len() - 1])?;
+        let err = loop {
+            match reader.next_packet() {
+                Ok(Some(_)) => {}
+                Ok(None) => panic!("Truncated file read to the end"),
+                Err(err) => break err,
+            }
+        };
+        assert!(err.is_eof());
+        assert!(!err.is_format_error());
+        Ok(())
+    }
 }
@@ -1,4 +1,10 @@
-use crate::{pcap::packet_header::PacketHeader, pcap_ng::options::BlockOptions};
+use crate::{
+    pcap::packet_header::{PacketHeader, PacketTimestamp},
+    pcap_ng::{
+        blocks::{EnhancedPacket, SimplePacket, TsResolution, timestamp_from_raw},
+        options::BlockOptions,
+    },
+};
 
 /// A unified packet header enum that can represent both pcap and pcapng packet headers
 ///
@@ -48,6 +54,53 @@ impl AnyPacketHeader {
             } => *original_length,
         }
     }
+    /// Returns the number of packet bytes stored in the file, regardless of which
+    /// underlying header variant is in use.
+    ///
+    /// Simple packets do not record a captured length so their original length is returned.
+    /// Their data is shorter if it was truncated to the interface snap length
+    pub fn captured_length(&self) -> u32 {
+        match self {
+            AnyPacketHeader::Pcap(header) => header.include_len,
+            AnyPacketHeader::PcapNgSimple {
+                original_length, ..
+            } => *original_length,
+            AnyPacketHeader::PcapNgEnhanced {
+                captured_length, ..
+            } => *captured_length,
+        }
+    }
+    /// Returns the id of the interface the packet was captured on
+    ///
+    /// Pcap files have a single interface and simple packets always belong to the first interface
+    /// of their section, so both return `0`
+    pub fn interface_id(&self) -> u32 {
+        match self {
+            AnyPacketHeader::PcapNgEnhanced { interface_id, .. } => *interface_id,
+            AnyPacketHeader::Pcap(_) | AnyPacketHeader::PcapNgSimple { .. } => 0,
+        }
+    }
+    /// Returns the capture timestamp of the packet
+    ///
+    /// Pcap timestamps are returned as is and `resolution_hint` is ignored.
+    /// Enhanced packet timestamps are converted with `resolution_hint`, which should be the
+    /// [`TsResolution`] of the interface the packet was captured on. See [`EnhancedPacket::timestamp`].
+    ///
+    /// Returns `None` for simple packets as they have no timestamp
+    pub fn timestamp(&self, resolution_hint: TsResolution) -> Option<PacketTimestamp> {
+        match self {
+            AnyPacketHeader::Pcap(header) => Some(header.timestamp),
+            AnyPacketHeader::PcapNgSimple { .. } => None,
+            AnyPacketHeader::PcapNgEnhanced {
+                timestamp_high,
+                timestamp_low,
+                ..
+            } => Some(timestamp_from_raw(
+                ((*timestamp_high as u64) << 32) | *timestamp_low as u64,
+                resolution_hint,
+            )),
+        }
+    }
 }
 
 impl From<PacketHeader> for AnyPacketHeader {
@@ -55,3 +108,114 @@ impl From<PacketHeader> for AnyPacketHeader {
         AnyPacketHeader::Pcap(value)
     }
 }
+impl From<EnhancedPacket<'_>> for AnyPacketHeader {
+    fn from(value: EnhancedPacket<'_>) -> Self {
+        AnyPacketHeader::PcapNgEnhanced {
+            block_length: value.block_length,
+            interface_id: value.interface_id,
+            timestamp_high: value.timestamp_high,
+            timestamp_low: value.timestamp_low,
+            captured_length: value.captured_length,
+            original_length: value.original_length,
+            options: value.options,
+        }
+    }
+}
+impl From<SimplePacket<'_>> for AnyPacketHeader {
+    fn from(value: SimplePacket<'_>) -> Self {
+        AnyPacketHeader::PcapNgSimple {
+            block_length: value.block_length,
+            original_length: value.original_length,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captured_length() {
+        let pcap = AnyPacketHeader::from(PacketHeader::new(PacketTimestamp::default(), 60, 1500));
+        assert_eq!(pcap.captured_length(), 60);
+        assert_eq!(pcap.original_length(), 1500);
+
+        let simple = AnyPacketHeader::PcapNgSimple {
+            block_length: 80,
+            original_length: 64,
+        };
+        assert_eq!(simple.captured_length(), 64);
+        assert_eq!(simple.original_length(), 64);
+
+        let enhanced = AnyPacketHeader::PcapNgEnhanced {
+            block_length: 96,
+            interface_id: 0,
+            timestamp_high: 0,
+            timestamp_low: 0,
+            captured_length: 64,
+            original_length: 1514,
+            options: None,
+        };
+        assert_eq!(enhanced.captured_length(), 64);
+        assert_eq!(enhanced.original_length(), 1514);
+    }
+
+    #[test]
+    fn timestamp() {
+        let timestamp = PacketTimestamp {
+            seconds: 1_700_000_000,
+            usec: 250_000,
+        };
+        let pcap = AnyPacketHeader::from(PacketHeader::new(timestamp, 0, 0));
+        assert_eq!(pcap.timestamp(TsResolution::NANOSECONDS), Some(timestamp));
+
+        let simple = AnyPacketHeader::PcapNgSimple {
+            block_length: 16,
+            original_length: 0,
+        };
+        assert_eq!(simple.timestamp(TsResolution::MICROSECONDS), None);
+
+        let raw = 1_700_000_000_250_000u64;
+        let enhanced = AnyPacketHeader::PcapNgEnhanced {
+            block_length: 32,
+            interface_id: 0,
+            timestamp_high: (raw >> 32) as u32,
+            timestamp_low: raw as u32,
+            captured_length: 0,
+            original_length: 0,
+            options: None,
+        };
+        assert_eq!(
+            enhanced.timestamp(TsResolution::MICROSECONDS),
+            Some(timestamp)
+        );
+        assert_eq!(
+            enhanced.timestamp(TsResolution::NANOSECONDS),
+            Some(PacketTimestamp {
+                seconds: 1_700_000,
+                usec: 250_000,
+            })
+        );
+    }
+
+    #[test]
+    fn interface_id() {
+        let pcap = AnyPacketHeader::from(PacketHeader::new(PacketTimestamp::default(), 0, 0));
+        assert_eq!(pcap.interface_id(), 0);
+        let simple = AnyPacketHeader::PcapNgSimple {
+            block_length: 16,
+            original_length: 0,
+        };
+        assert_eq!(simple.interface_id(), 0);
+        let enhanced = AnyPacketHeader::PcapNgEnhanced {
+            block_length: 32,
+            interface_id: 3,
+            timestamp_high: 0,
+            timestamp_low: 0,
+            captured_length: 0,
+            original_length: 0,
+            options: None,
+        };
+        assert_eq!(enhanced.interface_id(), 3);
+    }
+}
@@ -0,0 +1,176 @@
+//! Reading the header information of a file without reading its packets
+use std::{borrow::Cow, io::Read};
+
+use super::AnyPcapReaderError;
+use crate::{
+    PcapFileType, Version,
+    byte_order::Endianness,
+    link_type::LinkType,
+    pcap::file_header::PcapFileHeader,
+    pcap_ng::{
+        PcapNgParseError,
+        blocks::{
+            Block, BlockHeader, InterfaceDescriptionBlock, SHBOptionCodes, SectionHeaderBlock,
+        },
+    },
+};
+/// The link type and snap length of an interface described by a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InterfaceMetadata {
+    /// The link type of the interface
+    pub link_type: LinkType,
+    /// The snap length of the interface. 0 means unlimited in pcap-ng files
+    pub snap_length: u32,
+}
+/// Header information of a pcap or pcap-ng file returned by [`read_metadata`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PcapMetadata {
+    /// The format of the file
+    pub file_type: PcapFileType,
+    /// The version of the pcap file or of the first pcap-ng section
+    pub version: Version,
+    /// The byte order of the pcap file or of the first pcap-ng section
+    pub endianness: Endianness,
+    /// The interfaces described before the first non interface block
+    ///
+    /// pcap files always have exactly one interface taken from the file header
+    pub interfaces: Vec<InterfaceMetadata>,
+    /// The `shb_hardware` option of the first section. Always `None` for pcap files
+    pub hardware: Option<String>,
+    /// The `shb_os` option of the first section. Always `None` for pcap files
+    pub os: Option<String>,
+    /// The `shb_userappl` option of the first section. Always `None` for pcap files
+    pub user_application: Option<String>,
+}
+impl PcapMetadata {
+    /// The link type of the first interface
+    pub fn link_type(&self) -> Option<LinkType> {
+        self.interfaces.first().map(|interface| interface.link_type)
+    }
+    /// The snap length of the first interface
+    pub fn snap_length(&self) -> Option<u32> {
+        self.interfaces
+            .first()
+            .map(|interface| interface.snap_length)
+    }
+}
+impl From<&PcapFileHeader> for PcapMetadata {
+    fn from(header: &PcapFileHeader) -> Self {
+        Self {
+            file_type: PcapFileType::Pcap,
+            version: header.version,
+            endianness: header.endianness(),
+            interfaces: vec![InterfaceMetadata {
+                link_type: header.link_type,
+                snap_length: header.snap_length,
+            }],
+            hardware: None,
+            os: None,
+            user_application: None,
+        }
+    }
+}
+/// Reads the header information of a pcap or pcap-ng file
+///
+/// For pcap files only the file header is read. For pcap-ng files the first section header is read along with
+/// the interface description blocks directly following it. Reading stops at the first block that is not an
+/// interface description block, so no packets are read. Interfaces declared later in the file are not included.
+pub fn read_metadata<R: Read>(mut reader: R) -> Result<PcapMetadata, AnyPcapReaderError> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    let Some(file_type) = PcapFileType::from_magic(magic) else {
+        return Err(AnyPcapReaderError::InvalidPcapFormat);
+    };
+    let mut reader = magic.chain(reader);
+    match file_type {
+        PcapFileType::Pcap => Ok(PcapMetadata::from(&PcapFileHeader::read(&mut reader)?)),
+        PcapFileType::PcapNg => {
+            let section = SectionHeaderBlock::read_from_reader(&mut reader, &mut Vec::new())?;
+            let interfaces = read_interfaces(&mut reader, section.byte_order)?;
+            let option_string = |code: SHBOptionCodes| {
+                section
+                    .options
+                    .as_ref()?
+                    .get_utf8(code as u16)
+                    .map(Cow::into_owned)
+            };
+            Ok(PcapMetadata {
+                file_type,
+                version: section.version,
+                endianness: section.byte_order,
+                interfaces,
+                hardware: option_string(SHBOptionCodes::Hardware),
+                os: option_string(SHBOptionCodes::OS),
+                user_application: option_string(SHBOptionCodes::UserApplication),
+            })
+        }
+    }
+}
+/// Reads interface description blocks until another block or the end of the file
+fn read_interfaces<R: Read>(
+    reader: &mut R,
+    byte_order: Endianness,
+) -> Result<Vec<InterfaceMetadata>, PcapNgParseError> {
+    let mut interfaces = Vec::new();
+    let mut header_bytes = [0u8; 8];
+    loop {
+        match reader.read_exact(&mut header_bytes) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(err) => return Err(err.into()),
+        }
+        let header = BlockHeader::parse_from_bytes(&header_bytes)?;
+        if header.block_id_as_u32(byte_order) != InterfaceDescriptionBlock::block_id() {
+            break;
+        }
+        let interface = InterfaceDescriptionBlock::read_with_header(
+            reader,
+            &header,
+            Some(byte_order),
+            &mut Vec::new(),
+        )?;
+        interfaces.push(InterfaceMetadata {
+            link_type: interface.link_type,
+            snap_length: interface.snap_length,
+        });
+    }
+    Ok(interfaces)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pcap_metadata() -> anyhow::Result<()> {
+        let file = std::fs::File::open("test_data/test.pcap")?;
+        let metadata = read_metadata(file)?;
+        assert_eq!(metadata.file_type, PcapFileType::Pcap);
+        assert_eq!(metadata.version, Version { major: 2, minor: 4 });
+        assert_eq!(metadata.link_type(), Some(LinkType::Ethernet));
+        assert_eq!(metadata.interfaces.len(), 1);
+        assert_eq!(metadata.os, None);
+        Ok(())
+    }
+
+    #[test]
+    fn pcap_ng_metadata() -> anyhow::Result<()> {
+        for (file_name, endianness) in [
+            ("test_data/ng/test001_le.pcapng", Endianness::LittleEndian),
+            ("test_data/ng/test001_be.pcapng", Endianness::BigEndian),
+        ] {
+            let metadata = read_metadata(std::fs::File::open(file_name)?)?;
+            assert_eq!(metadata.file_type, PcapFileType::PcapNg);
+            assert_eq!(metadata.endianness, endianness);
+            assert_eq!(metadata.version, Version { major: 1, minor: 0 });
+            assert_eq!(metadata.link_type(), Some(LinkType::Ethernet));
+            assert!(metadata.hardware.is_some() || metadata.os.is_some());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_magic() {
+        let result = read_metadata(&[0u8; 24][..]);
+        assert!(matches!(result, Err(AnyPcapReaderError::InvalidPcapFormat)));
+    }
+}
@@ -0,0 +1,87 @@
+//! Scanning many files in parallel
+//!
+//! The readers hold no shared state, so a reader over a `Send` source is `Send` and each file can be read on its own thread.
+//! A single reader must still only be used from one thread at a time.
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use rayon::prelude::*;
+
+use super::{AnyPcapReaderError, PcapStats, SyncAnyPcapReader};
+
+/// Reads the [`PcapStats`] of every file in `paths` on the rayon thread pool
+///
+/// `f` is called with the path and stats of each file as soon as it has been read, in no particular order.
+/// Files that could not be opened or read are returned along with their error.
+///
+/// Requires the `rayon` feature.
+pub fn scan_files_parallel(
+    paths: &[PathBuf],
+    f: impl Fn(&Path, PcapStats) + Sync,
+) -> Vec<(PathBuf, AnyPcapReaderError)> {
+    paths
+        .par_iter()
+        .filter_map(|path| match scan_file(path) {
+            Ok(stats) => {
+                f(path, stats);
+                None
+            }
+            Err(err) => Some((path.clone(), err)),
+        })
+        .collect()
+}
+fn scan_file(path: &Path) -> Result<PcapStats, AnyPcapReaderError> {
+    let file = BufReader::new(File::open(path)?);
+    SyncAnyPcapReader::new(file)?.stats()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::{pcap::SyncPcapReader, pcap_ng::SyncPcapNgReader};
+
+    fn assert_send<T: Send>() {}
+
+    #[test]
+    fn readers_are_send() {
+        assert_send::<SyncAnyPcapReader<BufReader<File>>>();
+        assert_send::<SyncPcapReader<BufReader<File>>>();
+        assert_send::<SyncPcapNgReader<BufReader<File>>>();
+        assert_send::<PcapStats>();
+        assert_send::<AnyPcapReaderError>();
+    }
+
+    #[test]
+    fn scan_two_files() -> anyhow::Result<()> {
+        let paths = [
+            PathBuf::from("test_data/test.pcap"),
+            PathBuf::from("test_data/test.pcapng"),
+            PathBuf::from("test_data/does_not_exist.pcap"),
+        ];
+        let scanned = Mutex::new(Vec::new());
+        let errors = scan_files_parallel(&paths, |path, stats| {
+            if let Ok(mut scanned) = scanned.lock() {
+                scanned.push((path.to_path_buf(), stats));
+            }
+        });
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, paths[2]);
+
+        let mut scanned = scanned
+            .into_inner()
+            .map_err(|err| anyhow::anyhow!("{err}"))?;
+        scanned.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(scanned.len(), 2);
+        for (path, stats) in scanned {
+            let expected = SyncAnyPcapReader::new(File::open(&path)?)?.stats()?;
+            assert_eq!(stats, expected);
+            assert!(stats.packet_count > 0);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+//! Summary statistics of the packets in a file
+use crate::{PcapFileType, Version};
+
+/// Packet statistics returned by [`SyncAnyPcapReader::stats`](super::SyncAnyPcapReader::stats)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PcapStats {
+    /// The format of the file
+    pub file_type: PcapFileType,
+    /// The version of the pcap file or of the first pcap-ng section
+    pub version: Version,
+    /// Number of packets read
+    pub packet_count: u64,
+    /// Sum of the captured length of every packet
+    pub total_bytes: u64,
+    /// Captured length of the smallest packet. `None` if the file has no packets
+    pub smallest_packet: Option<usize>,
+    /// Captured length of the largest packet. `None` if the file has no packets
+    pub largest_packet: Option<usize>,
+}
+impl PcapStats {
+    pub(crate) fn new(file_type: PcapFileType, version: Version) -> Self {
+        Self {
+            file_type,
+            version,
+            packet_count: 0,
+            total_bytes: 0,
+            smallest_packet: None,
+            largest_packet: None,
+        }
+    }
+    pub(crate) fn record(&mut self, packet_size: usize) {
+        self.packet_count += 1;
+        self.total_bytes += packet_size as u64;
+        self.smallest_packet = Some(
+            self.smallest_packet
+                .map_or(packet_size, |s| s.min(packet_size)),
+        );
+        self.largest_packet = Some(
+            self.largest_packet
+                .map_or(packet_size, |l| l.max(packet_size)),
+        );
+    }
+    /// The mean captured length of the packets, rounded down. 0 if the file has no packets
+    pub fn average_packet_size(&self) -> u64 {
+        self.total_bytes.checked_div(self.packet_count).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{any_reader::SyncAnyPcapReader, pcap::SyncPcapReader};
+
+    #[test]
+    fn sync_stats() -> anyhow::Result<()> {
+        let file = std::fs::read("test_data/test.pcap")?;
+        let stats = SyncAnyPcapReader::new(file.as_slice())?.stats()?;
+
+        let mut reader = SyncPcapReader::new(file.as_slice())?;
+        let mut sizes = Vec::new();
+        while let Some((_, data)) = reader.next_packet()? {
+            sizes.push(data.len());
+        }
+        assert_eq!(stats.packet_count, sizes.len() as u64);
+        assert_eq!(stats.total_bytes, sizes.iter().sum::<usize>() as u64);
+        assert_eq!(stats.smallest_packet, sizes.iter().min().copied());
+        assert_eq!(stats.largest_packet, sizes.iter().max().copied());
+        assert_eq!(
+            stats.average_packet_size(),
+            stats.total_bytes / stats.packet_count
+        );
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@ use tokio::io::AsyncRead;
 
 use crate::{
     PcapFileType, Version,
-    any_reader::{AnyPacketHeader, AnyPcapPacket, AnyPcapReaderError},
+    any_reader::{AnyPacketHeader, AnyPcapPacket, AnyPcapReaderError, PcapStats},
     byte_order::tokio_async::AsyncReadExt,
     pcap::{AsyncPcapReader, file_header::PcapFileHeader},
     pcap_ng::{
@@ -46,6 +46,7 @@ impl<R: AsyncRead + Unpin> AsyncAnyPcapReaderInner<R> {
             PcapFileType::Pcap => {
                 let first_24_bytes = peakable.read_bytes::<24>().await?;
                 let header = PcapFileHeader::try_from(&first_24_bytes)?;
+                header.validate_snap_length()?;
                 drop(peakable);
                 Ok(AsyncAnyPcapReaderInner::Pcap(
                     AsyncPcapReader::new_with_header(reader, header),
@@ -97,9 +98,8 @@ impl<R: AsyncRead + Unpin> AsyncAnyPcapReader<R> {
     }
     /// Reads the next packet from the pcap or pcapng file
     ///
-    /// # Why Cow?
-    ///
-    /// The PcapNg packets do not have a fixed size buffer and each packet is read into a newly allocated `Vec<u8>`
+    /// For both file types the packet data borrows the underlying reader's reusable packet buffer.
+    /// Copy the data to keep it past the next read.
     pub async fn next_packet(&mut self) -> Result<Option<AnyPcapPacket<'_>>, AnyPcapReaderError> {
         match &mut self.inner {
             AsyncAnyPcapReaderInner::Pcap(pcap_reader) => match pcap_reader.next_packet().await? {
@@ -130,6 +130,16 @@ impl<R: AsyncRead + Unpin> AsyncAnyPcapReader<R> {
             AsyncAnyPcapReaderInner::PcapNg(pcapng_reader) => pcapng_reader.version(),
         }
     }
+    /// Reads every remaining packet and returns statistics about them
+    ///
+    /// The async counterpart to [`SyncAnyPcapReader::stats`](crate::any_reader::SyncAnyPcapReader::stats)
+    pub async fn stats(mut self) -> Result<PcapStats, AnyPcapReaderError> {
+        let mut stats = PcapStats::new(self.file_type(), *self.version());
+        while let Some((_, data)) = self.next_packet().await? {
+            stats.record(data.len());
+        }
+        Ok(stats)
+    }
 }
 #[cfg(test)]
 mod tests {
@@ -200,4 +210,23 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn stats_match_sync() -> anyhow::Result<()> {
+        for file_name in [
+            "test_data/test.pcap",
+            "test_data/ng/test001_le.pcapng",
+            "test_data/ng/test001_be.pcapng",
+        ] {
+            let file = std::fs::read(file_name)?;
+            let stats = AsyncAnyPcapReader::new(file.as_slice())
+                .await?
+                .stats()
+                .await?;
+            let expected = crate::any_reader::SyncAnyPcapReader::new(file.as_slice())?.stats()?;
+            assert_eq!(stats, expected);
+            assert!(stats.packet_count > 0);
+        }
+        Ok(())
+    }
 }
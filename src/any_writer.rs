@@ -0,0 +1,291 @@
+//! Writing either pcap or pcapng files
+use std::{
+    borrow::Cow,
+    io::{self, Seek, Write},
+};
+
+use crate::{
+    PcapFileType,
+    any_reader::AnyPacketHeader,
+    byte_order::Endianness,
+    link_type::LinkType,
+    pcap::{
+        file_header::PcapFileHeader,
+        packet_header::PacketTimestamp,
+        writer::{NewPacketHeader, SyncPcapWriter},
+    },
+    pcap_ng::{
+        blocks::{
+            EnhancedPacket, InterfaceDescriptionBlock, SectionHeaderBlock, SimplePacket,
+            TsResolution, timestamp_from_raw,
+        },
+        writer::SyncPcapNgWriter,
+    },
+};
+#[cfg(feature = "tokio-async")]
+mod tokio_impl;
+#[cfg(feature = "tokio-async")]
+pub use tokio_impl::AsyncAnyPcapWriter;
+
+/// A writer that can write either pcap or pcapng files
+///
+/// # Timestamps
+///
+/// Both formats are written with microsecond timestamps. Pcap-ng headers carry no
+/// interface information so their timestamps are assumed to be in microseconds
+/// (the pcap-ng default) and pcap headers are assumed to come from a microsecond file.
+///
+/// # Converting headers
+///
+/// - Pcap output: enhanced and simple packet headers are collapsed to their timestamp and lengths.
+///   Simple packets have no timestamp and are written with a zero timestamp.
+/// - Pcap-ng output: pcap headers become enhanced packets on interface 0.
+///   Enhanced packets keep their interface id and options so any interface past the first
+///   must be added through the inner [`SyncPcapNgWriter`].
+pub enum AnyPcapWriter<W: Write + Seek> {
+    /// Writing a pcap file
+    Pcap(SyncPcapWriter<W>),
+    /// Writing a pcap-ng file with a single section
+    PcapNg(SyncPcapNgWriter<W>),
+}
+impl<W: Write + Seek> AnyPcapWriter<W> {
+    /// Creates a new writer for `file_type` and writes the file header
+    ///
+    /// For pcap-ng a little endian section header and a single interface with `link_type` are written.
+    pub fn new(target: W, file_type: PcapFileType, link_type: LinkType) -> Result<Self, io::Error> {
+        match file_type {
+            PcapFileType::Pcap => {
+                let header = PcapFileHeader::builder().link_type(link_type).build();
+                Ok(Self::Pcap(SyncPcapWriter::new(target, header)?))
+            }
+            PcapFileType::PcapNg => {
+                let mut writer = SyncPcapNgWriter::new(
+                    target,
+                    SectionHeaderBlock::new(Endianness::LittleEndian),
+                )?;
+                writer.add_interface(InterfaceDescriptionBlock::new(link_type, 0))?;
+                Ok(Self::PcapNg(writer))
+            }
+        }
+    }
+    /// Returns the type of file being written
+    pub fn file_type(&self) -> PcapFileType {
+        match self {
+            AnyPcapWriter::Pcap(_) => PcapFileType::Pcap,
+            AnyPcapWriter::PcapNg(_) => PcapFileType::PcapNg,
+        }
+    }
+    /// Writes a packet, converting `header` to the output format
+    ///
+    /// The captured length is always taken from `data`.
+    pub fn write_packet(&mut self, header: AnyPacketHeader, data: &[u8]) -> Result<(), io::Error> {
+        match self {
+            AnyPcapWriter::Pcap(writer) => writer.write_header(pcap_packet_header(&header), data),
+            AnyPcapWriter::PcapNg(writer) => match pcap_ng_packet_block(header, data) {
+                PcapNgPacket::Enhanced(packet) => writer.write_enhanced_packet(&packet),
+                PcapNgPacket::Simple(packet) => writer.write_simple_packet(&packet),
+            },
+        }
+    }
+    /// Number of packets written to the target
+    pub fn packets_written(&self) -> u64 {
+        match self {
+            AnyPcapWriter::Pcap(writer) => writer.packets_written(),
+            AnyPcapWriter::PcapNg(writer) => writer.packets_written(),
+        }
+    }
+    /// Flushes the target, rewriting the pcap file header if required
+    ///
+    /// See [`SyncPcapWriter::finish`] and [`SyncPcapNgWriter::finish`]
+    pub fn finish(self) -> Result<(), io::Error> {
+        match self {
+            AnyPcapWriter::Pcap(writer) => writer.finish(),
+            AnyPcapWriter::PcapNg(writer) => writer.finish(),
+        }
+    }
+}
+/// A packet block converted from an [`AnyPacketHeader`]
+pub(crate) enum PcapNgPacket<'a> {
+    Enhanced(EnhancedPacket<'a>),
+    Simple(SimplePacket<'a>),
+}
+/// Collapses `header` to the timestamp and original length of a pcap packet header
+///
+/// See [`AnyPcapWriter`] for the conversion rules
+pub(crate) fn pcap_packet_header(header: &AnyPacketHeader) -> NewPacketHeader {
+    let timestamp = match header {
+        AnyPacketHeader::Pcap(header) => header.timestamp,
+        AnyPacketHeader::PcapNgSimple { .. } => PacketTimestamp::default(),
+        AnyPacketHeader::PcapNgEnhanced {
+            timestamp_high,
+            timestamp_low,
+            ..
+        } => timestamp_from_raw(
+            ((*timestamp_high as u64) << 32) | *timestamp_low as u64,
+            TsResolution::MICROSECONDS,
+        ),
+    };
+    NewPacketHeader {
+        timestamp,
+        orig_len: Some(header.original_length()),
+    }
+}
+/// Converts `header` to the pcap-ng packet block holding `data`
+///
+/// See [`AnyPcapWriter`] for the conversion rules
+pub(crate) fn pcap_ng_packet_block(header: AnyPacketHeader, data: &[u8]) -> PcapNgPacket<'_> {
+    match header {
+        AnyPacketHeader::Pcap(header) => {
+            let timestamp =
+                header.timestamp.seconds as u64 * 1_000_000 + header.timestamp.usec as u64;
+            PcapNgPacket::Enhanced(EnhancedPacket {
+                block_length: 0,
+                interface_id: 0,
+                timestamp_high: (timestamp >> 32) as u32,
+                timestamp_low: timestamp as u32,
+                captured_length: data.len() as u32,
+                original_length: header.orig_len,
+                content: Cow::Borrowed(data),
+                options: None,
+            })
+        }
+        AnyPacketHeader::PcapNgSimple {
+            original_length, ..
+        } => PcapNgPacket::Simple(SimplePacket {
+            block_length: 0,
+            original_length,
+            content: Cow::Borrowed(data),
+        }),
+        AnyPacketHeader::PcapNgEnhanced {
+            interface_id,
+            timestamp_high,
+            timestamp_low,
+            original_length,
+            options,
+            ..
+        } => PcapNgPacket::Enhanced(EnhancedPacket {
+            block_length: 0,
+            interface_id,
+            timestamp_high,
+            timestamp_low,
+            captured_length: data.len() as u32,
+            original_length,
+            content: Cow::Borrowed(data),
+            options,
+        }),
+    }
+}
+impl<W: Write + Seek> From<SyncPcapWriter<W>> for AnyPcapWriter<W> {
+    fn from(value: SyncPcapWriter<W>) -> Self {
+        AnyPcapWriter::Pcap(value)
+    }
+}
+impl<W: Write + Seek> From<SyncPcapNgWriter<W>> for AnyPcapWriter<W> {
+    fn from(value: SyncPcapNgWriter<W>) -> Self {
+        AnyPcapWriter::PcapNg(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{any_reader::SyncAnyPcapReader, pcap::packet_header::PacketHeader};
+
+    #[test]
+    fn write_both_formats() -> anyhow::Result<()> {
+        let packets: [(AnyPacketHeader, &[u8]); 3] = [
+            (
+                PacketHeader::new(
+                    PacketTimestamp {
+                        seconds: 10,
+                        usec: 250,
+                    },
+                    3,
+                    60,
+                )
+                .into(),
+                &[1, 2, 3],
+            ),
+            (
+                AnyPacketHeader::PcapNgEnhanced {
+                    block_length: 0,
+                    interface_id: 0,
+                    timestamp_high: 0,
+                    timestamp_low: 11_000_500,
+                    captured_length: 2,
+                    original_length: 2,
+                    options: None,
+                },
+                &[4, 5],
+            ),
+            (
+                AnyPacketHeader::PcapNgSimple {
+                    block_length: 0,
+                    original_length: 4,
+                },
+                &[6, 7, 8, 9],
+            ),
+        ];
+        for file_type in [PcapFileType::Pcap, PcapFileType::PcapNg] {
+            let mut writer = AnyPcapWriter::new(Cursor::new(Vec::new()), file_type, LinkType::Raw)?;
+            assert_eq!(writer.file_type(), file_type);
+            for (header, data) in &packets {
+                writer.write_packet(header.clone(), data)?;
+            }
+            assert_eq!(writer.packets_written(), 3);
+            let file = match writer {
+                AnyPcapWriter::Pcap(writer) => writer.into_inner()?,
+                AnyPcapWriter::PcapNg(writer) => writer.into_inner(),
+            }
+            .into_inner();
+
+            let mut reader = SyncAnyPcapReader::new(file.as_slice())?;
+            assert_eq!(reader.file_type(), file_type);
+            let mut read = Vec::new();
+            while let Some((header, data)) = reader.next_packet()? {
+                read.push((header, data.to_vec()));
+            }
+            assert_eq!(read.len(), 3);
+            for ((expected, expected_data), (header, data)) in packets.iter().zip(&read) {
+                assert_eq!(expected_data, data);
+                assert_eq!(expected.original_length(), header.original_length());
+            }
+            match file_type {
+                PcapFileType::Pcap => {
+                    let timestamps: Vec<_> = read
+                        .iter()
+                        .map(|(header, _)| match header {
+                            AnyPacketHeader::Pcap(header) => header.timestamp,
+                            other => panic!("expected a pcap header, got {other:?}"),
+                        })
+                        .collect();
+                    assert_eq!(
+                        timestamps,
+                        [
+                            PacketTimestamp {
+                                seconds: 10,
+                                usec: 250
+                            },
+                            PacketTimestamp {
+                                seconds: 11,
+                                usec: 500
+                            },
+                            PacketTimestamp::default(),
+                        ]
+                    );
+                }
+                PcapFileType::PcapNg => {
+                    let AnyPacketHeader::PcapNgEnhanced { timestamp_low, .. } = &read[0].0 else {
+                        panic!("expected an enhanced packet, got {:?}", read[0].0);
+                    };
+                    assert_eq!(*timestamp_low, 10_000_250);
+                    assert!(matches!(read[1].0, AnyPacketHeader::PcapNgEnhanced { .. }));
+                    assert!(matches!(read[2].0, AnyPacketHeader::PcapNgSimple { .. }));
+                }
+            }
+        }
+        Ok(())
+    }
+}
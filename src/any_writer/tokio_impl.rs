@@ -0,0 +1,164 @@
+use std::io;
+
+use tokio::io::AsyncWrite;
+
+use crate::{
+    PcapFileType,
+    any_reader::AnyPacketHeader,
+    any_writer::{PcapNgPacket, pcap_ng_packet_block, pcap_packet_header},
+    byte_order::Endianness,
+    link_type::LinkType,
+    pcap::{AsyncPcapWriter, file_header::PcapFileHeader},
+    pcap_ng::{
+        AsyncPcapNgWriter,
+        blocks::{InterfaceDescriptionBlock, SectionHeaderBlock},
+    },
+};
+
+/// An async writer that can write either pcap or pcapng files
+///
+/// Headers are converted the same way as [`AnyPcapWriter`](crate::any_writer::AnyPcapWriter).
+/// The pcap file header is never rewritten so packets larger than the snap length return an error.
+pub enum AsyncAnyPcapWriter<W: AsyncWrite + Unpin> {
+    /// Writing a pcap file
+    Pcap(AsyncPcapWriter<W>),
+    /// Writing a pcap-ng file with a single section
+    PcapNg(AsyncPcapNgWriter<W>),
+}
+impl<W: AsyncWrite + Unpin> AsyncAnyPcapWriter<W> {
+    /// Creates a new writer for `file_type` and writes the file header
+    ///
+    /// For pcap-ng a little endian section header and a single interface with `link_type` are written.
+    pub async fn new(
+        target: W,
+        file_type: PcapFileType,
+        link_type: LinkType,
+    ) -> Result<Self, io::Error> {
+        match file_type {
+            PcapFileType::Pcap => {
+                let header = PcapFileHeader::builder().link_type(link_type).build();
+                Ok(Self::Pcap(AsyncPcapWriter::new(target, header).await?))
+            }
+            PcapFileType::PcapNg => {
+                let mut writer = AsyncPcapNgWriter::new(
+                    target,
+                    SectionHeaderBlock::new(Endianness::LittleEndian),
+                )
+                .await?;
+                writer
+                    .add_interface(InterfaceDescriptionBlock::new(link_type, 0))
+                    .await?;
+                Ok(Self::PcapNg(writer))
+            }
+        }
+    }
+    /// Returns the type of file being written
+    pub fn file_type(&self) -> PcapFileType {
+        match self {
+            AsyncAnyPcapWriter::Pcap(_) => PcapFileType::Pcap,
+            AsyncAnyPcapWriter::PcapNg(_) => PcapFileType::PcapNg,
+        }
+    }
+    /// Writes a packet, converting `header` to the output format
+    ///
+    /// The captured length is always taken from `data`.
+    pub async fn write_packet(
+        &mut self,
+        header: AnyPacketHeader,
+        data: &[u8],
+    ) -> Result<(), io::Error> {
+        match self {
+            AsyncAnyPcapWriter::Pcap(writer) => {
+                writer.write_header(pcap_packet_header(&header), data).await
+            }
+            AsyncAnyPcapWriter::PcapNg(writer) => match pcap_ng_packet_block(header, data) {
+                PcapNgPacket::Enhanced(packet) => writer.write_enhanced_packet(&packet).await,
+                PcapNgPacket::Simple(packet) => writer.write_simple_packet(&packet).await,
+            },
+        }
+    }
+    /// Number of packets written to the target
+    pub fn packets_written(&self) -> u64 {
+        match self {
+            AsyncAnyPcapWriter::Pcap(writer) => writer.packets_written(),
+            AsyncAnyPcapWriter::PcapNg(writer) => writer.packets_written(),
+        }
+    }
+    /// Flushes the target
+    pub async fn finish(self) -> Result<(), io::Error> {
+        match self {
+            AsyncAnyPcapWriter::Pcap(writer) => writer.finish().await,
+            AsyncAnyPcapWriter::PcapNg(writer) => writer.finish().await,
+        }
+    }
+    /// Consumes the writer and returns the underlying target
+    pub fn into_inner(self) -> W {
+        match self {
+            AsyncAnyPcapWriter::Pcap(writer) => writer.into_inner(),
+            AsyncAnyPcapWriter::PcapNg(writer) => writer.into_inner(),
+        }
+    }
+}
+impl<W: AsyncWrite + Unpin> From<AsyncPcapWriter<W>> for AsyncAnyPcapWriter<W> {
+    fn from(value: AsyncPcapWriter<W>) -> Self {
+        AsyncAnyPcapWriter::Pcap(value)
+    }
+}
+impl<W: AsyncWrite + Unpin> From<AsyncPcapNgWriter<W>> for AsyncAnyPcapWriter<W> {
+    fn from(value: AsyncPcapNgWriter<W>) -> Self {
+        AsyncAnyPcapWriter::PcapNg(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        any_reader::AsyncAnyPcapReader,
+        pcap::packet_header::{PacketHeader, PacketTimestamp},
+    };
+
+    #[tokio::test]
+    async fn write_both_formats() -> anyhow::Result<()> {
+        let packets: [(AnyPacketHeader, &[u8]); 2] = [
+            (
+                PacketHeader::new(
+                    PacketTimestamp {
+                        seconds: 10,
+                        usec: 250,
+                    },
+                    3,
+                    60,
+                )
+                .into(),
+                &[1, 2, 3],
+            ),
+            (
+                AnyPacketHeader::PcapNgSimple {
+                    block_length: 0,
+                    original_length: 4,
+                },
+                &[6, 7, 8, 9],
+            ),
+        ];
+        for file_type in [PcapFileType::Pcap, PcapFileType::PcapNg] {
+            let mut writer = AsyncAnyPcapWriter::new(Vec::new(), file_type, LinkType::Raw).await?;
+            assert_eq!(writer.file_type(), file_type);
+            for (header, data) in &packets {
+                writer.write_packet(header.clone(), data).await?;
+            }
+            assert_eq!(writer.packets_written(), 2);
+            let file = writer.into_inner();
+
+            let mut reader = AsyncAnyPcapReader::new(file.as_slice()).await?;
+            assert_eq!(reader.file_type(), file_type);
+            for (expected, expected_data) in &packets {
+                let (header, data) = reader.next_packet().await?.expect("packet");
+                assert_eq!(data, *expected_data);
+                assert_eq!(header.original_length(), expected.original_length());
+            }
+            assert!(reader.next_packet().await?.is_none());
+        }
+        Ok(())
+    }
+}
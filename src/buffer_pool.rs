@@ -428,6 +428,7 @@ mod tests {
             },
             include_len: 10,
             orig_len: 10,
+            extended: None,
         }
     }
 
@@ -1,6 +1,7 @@
 //! Byte Order handling for pcap and pcap-ng files
 
-use std::io::{Read, Write};
+#[cfg(feature = "alloc")]
+use crate::io::{self, Read, Write};
 
 use thiserror::Error;
 /// Returned when the byte order of a pcap or pcap-ng file cannot be determined
@@ -20,6 +21,8 @@ pub trait ByteOrder: Clone + Copy {
     fn u32_to_bytes(self, value: u32) -> [u8; 4];
     /// Converts a byte array to a u64
     fn u64_from_bytes(self, bytes: [u8; 8]) -> u64;
+    /// Converts a u64 to a byte array
+    fn u64_to_bytes(self, value: u64) -> [u8; 8];
 }
 
 /// Big-endian byte order
@@ -46,6 +49,10 @@ impl ByteOrder for BigEndian {
     fn u64_from_bytes(self, bytes: [u8; 8]) -> u64 {
         u64::from_be_bytes(bytes)
     }
+    #[inline(always)]
+    fn u64_to_bytes(self, value: u64) -> [u8; 8] {
+        value.to_be_bytes()
+    }
 }
 /// Little-endian byte order
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -71,6 +78,10 @@ impl ByteOrder for LittleEndian {
     fn u64_from_bytes(self, bytes: [u8; 8]) -> u64 {
         u64::from_le_bytes(bytes)
     }
+    #[inline(always)]
+    fn u64_to_bytes(self, value: u64) -> [u8; 8] {
+        value.to_le_bytes()
+    }
 }
 
 /// Represents the endianness of the byte order
@@ -79,7 +90,7 @@ impl ByteOrder for LittleEndian {
 ///
 /// `Default` is based on the host architecture, so it should not be relied on
 /// in tests — specify [`BigEndian`] or [`LittleEndian`] explicitly.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Endianness {
     /// Little-endian byte order
     LittleEndian,
@@ -137,6 +148,13 @@ impl ByteOrder for Endianness {
             Endianness::LittleEndian => LittleEndian.u64_from_bytes(bytes),
         }
     }
+    #[inline(always)]
+    fn u64_to_bytes(self, value: u64) -> [u8; 8] {
+        match self {
+            Endianness::BigEndian => BigEndian.u64_to_bytes(value),
+            Endianness::LittleEndian => LittleEndian.u64_to_bytes(value),
+        }
+    }
 }
 
 /// Returned when a slice of bytes does not match the expected size for the
@@ -183,56 +201,84 @@ impl<B: ByteOrder> ExtendedByteOrder for B {
         Ok(self.u32_from_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
     }
 }
+#[cfg(feature = "alloc")]
 /// Extension trait for [`Read`] adding byte-order-aware integer reads.
 pub trait ReadExt {
     /// Reads a u16 from the reader
-    fn read_u16<B: ByteOrder>(&mut self, byte_order: B) -> Result<u16, std::io::Error>;
+    fn read_u16<B: ByteOrder>(&mut self, byte_order: B) -> Result<u16, io::Error>;
 
     /// Reads a u32 from the reader
-    fn read_u32<B: ByteOrder>(&mut self, byte_order: B) -> Result<u32, std::io::Error>;
+    fn read_u32<B: ByteOrder>(&mut self, byte_order: B) -> Result<u32, io::Error>;
     /// Has nothing to do with byte order, just reads a fixed number of bytes
     ///
     /// But exists for simplicity
-    fn read_bytes<const SIZE: usize>(&mut self) -> Result<[u8; SIZE], std::io::Error>;
+    fn read_bytes<const SIZE: usize>(&mut self) -> Result<[u8; SIZE], io::Error>;
+    /// Reads and discards exactly `length` bytes
+    ///
+    /// Returns an [`io::ErrorKind::UnexpectedEof`] error if the reader ends first
+    #[cfg(feature = "std")]
+    fn skip_exact(&mut self, length: u64) -> Result<(), io::Error>;
 }
+#[cfg(feature = "alloc")]
 impl<R: Read> ReadExt for R {
-    fn read_u16<B: ByteOrder>(&mut self, byte_order: B) -> Result<u16, std::io::Error> {
+    fn read_u16<B: ByteOrder>(&mut self, byte_order: B) -> Result<u16, io::Error> {
         let mut buffer = [0u8; 2];
         self.read_exact(&mut buffer)?;
         Ok(byte_order.u16_from_bytes(buffer))
     }
-    fn read_u32<B: ByteOrder>(&mut self, byte_order: B) -> Result<u32, std::io::Error> {
+    fn read_u32<B: ByteOrder>(&mut self, byte_order: B) -> Result<u32, io::Error> {
         let mut buffer = [0u8; 4];
         self.read_exact(&mut buffer)?;
         Ok(byte_order.u32_from_bytes(buffer))
     }
     #[inline(always)]
-    fn read_bytes<const SIZE: usize>(&mut self) -> Result<[u8; SIZE], std::io::Error> {
+    fn read_bytes<const SIZE: usize>(&mut self) -> Result<[u8; SIZE], io::Error> {
         let mut buffer = [0u8; SIZE];
         self.read_exact(&mut buffer)?;
         Ok(buffer)
     }
+    #[cfg(feature = "std")]
+    fn skip_exact(&mut self, length: u64) -> Result<(), io::Error> {
+        let skipped = std::io::copy(&mut self.take(length), &mut std::io::sink())?;
+        if skipped != length {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Data extends past the end of the file",
+            ));
+        }
+        Ok(())
+    }
 }
 
+#[cfg(feature = "alloc")]
 /// Extension trait for [`Write`] adding byte-order-aware integer writes.
 pub trait WriteExt {
     /// Writes a u16 to the writer
-    fn write_u16<B: ByteOrder>(&mut self, value: u16, byte_order: B) -> Result<(), std::io::Error>;
+    fn write_u16<B: ByteOrder>(&mut self, value: u16, byte_order: B) -> Result<(), io::Error>;
 
     /// Writes a u32 to the writer
-    fn write_u32<B: ByteOrder>(&mut self, value: u32, byte_order: B) -> Result<(), std::io::Error>;
+    fn write_u32<B: ByteOrder>(&mut self, value: u32, byte_order: B) -> Result<(), io::Error>;
+
+    /// Writes a u64 to the writer
+    fn write_u64<B: ByteOrder>(&mut self, value: u64, byte_order: B) -> Result<(), io::Error>;
 }
+#[cfg(feature = "alloc")]
 impl<R: Write> WriteExt for R {
-    fn write_u16<B: ByteOrder>(&mut self, value: u16, byte_order: B) -> Result<(), std::io::Error> {
+    fn write_u16<B: ByteOrder>(&mut self, value: u16, byte_order: B) -> Result<(), io::Error> {
         let value = byte_order.u16_to_bytes(value);
         self.write_all(&value)?;
         Ok(())
     }
-    fn write_u32<B: ByteOrder>(&mut self, value: u32, byte_order: B) -> Result<(), std::io::Error> {
+    fn write_u32<B: ByteOrder>(&mut self, value: u32, byte_order: B) -> Result<(), io::Error> {
         let value = byte_order.u32_to_bytes(value);
         self.write_all(&value)?;
         Ok(())
     }
+    fn write_u64<B: ByteOrder>(&mut self, value: u64, byte_order: B) -> Result<(), io::Error> {
+        let value = byte_order.u64_to_bytes(value);
+        self.write_all(&value)?;
+        Ok(())
+    }
 }
 
 /// Async byte-order utilities, gated on the `tokio-async` feature.
@@ -0,0 +1,648 @@
+//! Converting between pcap and pcapng files
+//!
+//! # pcap to pcapng
+//!
+//! A single little endian section is written with one interface using the link type and snap length of the pcap file.
+//! Nanosecond pcap files set `if_tsresol` on the interface.
+//!
+//! # pcapng to pcap
+//!
+//! The pcap file header is taken from the interface of the first packet.
+//! Every packet must be captured on an interface with the same link type.
+//! Timestamps are converted to the resolution of the pcap file and include the interface's `if_tsoffset`.
+//! Simple packets are written with a zero timestamp.
+//!
+//! # Changing the snap length
+//!
+//! [`resnap`] rewrites a pcap file with a new snap length, truncating larger packets.
+//!
+//! # Splitting by interface
+//!
+//! [`split_by_interface`] writes the packets of each pcapng interface to their own file.
+use std::{
+    fs::File,
+    io::{self, BufWriter, Read, Seek, Write},
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+
+use crate::{
+    byte_order::Endianness,
+    link_type::LinkType,
+    pcap::{
+        PcapParseError, SyncPcapReader,
+        file_header::{MagicNumber, MagicNumberAndEndianness, PcapFileHeader},
+        packet_header::{PacketHeader, PacketTimestamp},
+        writer::{NewPacketHeader, SyncPcapWriter, TruncationPolicy},
+    },
+    pcap_ng::{
+        PcapNgParseError, SyncPcapNgReader,
+        blocks::{
+            InterfaceDescriptionBlock, InterfaceOptionCodes, PcapNgBlock, SectionHeaderBlock,
+            TsResolution, apply_ts_offset, timestamp_from_raw,
+        },
+        options::{BlockOption, BlockOptions},
+        writer::SyncPcapNgWriter,
+    },
+};
+#[cfg(feature = "tokio-async")]
+mod tokio_impl;
+#[cfg(feature = "tokio-async")]
+pub use tokio_impl::{convert_pcap_to_pcapng_async, convert_pcapng_to_pcap_async};
+
+/// The snap length used for pcap files converted from interfaces without a snap length
+///
+/// Matches `MAXIMUM_SNAPLEN` in libpcap
+const MAXIMUM_SNAP_LENGTH: u32 = 262144;
+
+/// Errors returned when converting between pcap and pcapng files
+#[derive(Debug, Error)]
+pub enum ConvertError {
+    /// An error occurred while reading or validating the pcap file.
+    #[error(transparent)]
+    Pcap(#[from] PcapParseError),
+    /// An error occurred while reading the pcap-ng file.
+    #[error(transparent)]
+    PcapNg(#[from] PcapNgParseError),
+    /// An underlying I/O error occurred.
+    #[error(transparent)]
+    IO(#[from] io::Error),
+    /// A pcap file can only hold packets of a single link type.
+    #[error("Packet captured on a {got:?} interface but the pcap file has link type {expected:?}")]
+    MixedLinkTypes {
+        /// Link type of the pcap file.
+        expected: LinkType,
+        /// Link type of the interface the packet was captured on.
+        got: LinkType,
+    },
+}
+
+/// Converts a pcap file to a pcapng file
+///
+/// Returns the number of packets converted
+pub fn convert_pcap_to_pcapng<R: Read, W: Write>(
+    reader: R,
+    writer: W,
+) -> Result<u64, ConvertError> {
+    let mut reader = SyncPcapReader::new(reader)?;
+    let file_header = *reader.file_header();
+    let mut writer =
+        SyncPcapNgWriter::new(writer, SectionHeaderBlock::new(Endianness::LittleEndian))?;
+    writer.add_interface(interface_for_pcap(&file_header))?;
+    while let Some((header, data)) = reader.next_packet()? {
+        writer.write_packet(
+            0,
+            pcap_ng_timestamp(&file_header, header.timestamp),
+            data,
+            Some(header.orig_len),
+        )?;
+    }
+    let packets = writer.packets_written();
+    writer.finish()?;
+    Ok(packets)
+}
+/// Copies a pcap file with its snap length set to `new_snap`
+///
+/// Packets larger than `new_snap` are truncated to `new_snap` bytes and keep their original length.
+/// The rest of the file header is kept as is, including a nonzero timezone and sig_figs from legacy files.
+/// Packet timestamps are kept.
+///
+/// Returns the number of packets copied.
+/// Returns [`PcapParseError::InvalidSnapLength`] without writing anything if `new_snap` is 0
+pub fn resnap<R: Read, W: Write + Seek>(
+    reader: R,
+    writer: W,
+    new_snap: u32,
+) -> Result<u64, ConvertError> {
+    if new_snap == 0 {
+        return Err(PcapParseError::InvalidSnapLength.into());
+    }
+    let mut reader = SyncPcapReader::new(reader)?;
+    let mut file_header = *reader.file_header();
+    file_header.snap_length = new_snap;
+    let mut writer = SyncPcapWriter::new(writer, file_header)?
+        .with_truncation_policy(TruncationPolicy::TruncateToSnap);
+    while let Some((header, data)) = reader.next_packet()? {
+        writer.write_header(
+            NewPacketHeader {
+                timestamp: header.timestamp,
+                orig_len: Some(header.orig_len),
+            },
+            data,
+        )?;
+    }
+    let packets = writer.packets_written();
+    writer.finish()?;
+    Ok(packets)
+}
+/// Writes the packets of every interface of a pcapng file to their own pcapng file in `out_dir`
+///
+/// Each output holds a copy of the section header without its section length, the interface as interface 0 and the enhanced
+/// and simple packets captured on it. Other blocks are not copied.
+/// Outputs are named `interface_{n}.pcapng` where `n` counts interfaces in the order they are declared,
+/// so for single section files `n` is the interface id.
+///
+/// Returns the paths of the written files in the same order
+pub fn split_by_interface<R: Read>(
+    reader: R,
+    out_dir: impl AsRef<Path>,
+) -> Result<Vec<PathBuf>, ConvertError> {
+    let out_dir = out_dir.as_ref();
+    let mut reader = SyncPcapNgReader::new(reader)?;
+    let mut section = reader.current_section().clone();
+    // Each output holds only part of the section, so its declared length no longer applies
+    section.section_length = None;
+    let mut paths = Vec::new();
+    let mut writers: Vec<SyncPcapNgWriter<BufWriter<File>>> = Vec::new();
+    // Index into `writers` of interface 0 of the current section
+    let mut section_start = 0;
+    while let Some(block) = reader.next_block()? {
+        match block {
+            PcapNgBlock::SectionHeader(new_section) => {
+                section = new_section;
+                section.section_length = None;
+                section_start = writers.len();
+            }
+            PcapNgBlock::InterfaceDescription(interface) => {
+                let path = out_dir.join(format!("interface_{}.pcapng", writers.len()));
+                let mut writer =
+                    SyncPcapNgWriter::new(BufWriter::new(File::create(&path)?), section.clone())?;
+                writer.add_interface(interface)?;
+                writers.push(writer);
+                paths.push(path);
+            }
+            PcapNgBlock::EnhancedPacket(mut packet) => {
+                let declared = writers.len() - section_start;
+                let Some(writer) = writers.get_mut(section_start + packet.interface_id as usize)
+                else {
+                    return Err(PcapNgParseError::UndeclaredInterface {
+                        interface_id: packet.interface_id,
+                        declared,
+                    }
+                    .into());
+                };
+                packet.interface_id = 0;
+                writer.write_enhanced_packet(&packet)?;
+            }
+            PcapNgBlock::SimplePacket(packet) => {
+                if let Some(writer) = writers.get_mut(section_start) {
+                    writer.write_simple_packet(&packet)?;
+                }
+            }
+            _ => {}
+        }
+    }
+    for writer in writers {
+        writer.finish()?;
+    }
+    Ok(paths)
+}
+/// Converts a pcapng file to a pcap file
+///
+/// Returns the number of packets converted
+pub fn convert_pcapng_to_pcap<R: Read, W: Write>(
+    reader: R,
+    mut writer: W,
+) -> Result<u64, ConvertError> {
+    let mut reader = SyncPcapNgReader::new(reader)?;
+    let mut converter = PcapNgToPcap::new(reader.current_section().byte_order);
+    let mut packets = 0;
+    while let Some(block) = reader.next_block()? {
+        let Some(packet) = converter.convert(&block)? else {
+            continue;
+        };
+        if let Some(file_header) = packet.file_header {
+            file_header.write(&mut writer)?;
+        }
+        packet
+            .header
+            .write(&mut writer, converter.endianness(), &converter.version())?;
+        writer.write_all(packet.content)?;
+        packets += 1;
+    }
+    if let Some(file_header) = converter.finish() {
+        file_header.write(&mut writer)?;
+    }
+    writer.flush()?;
+    Ok(packets)
+}
+/// The interface written for the packets of a pcap file
+pub(crate) fn interface_for_pcap(file_header: &PcapFileHeader) -> InterfaceDescriptionBlock {
+    let mut interface =
+        InterfaceDescriptionBlock::new(file_header.link_type, file_header.snap_length);
+    if file_header.resolution() == MagicNumber::Nanosecond {
+        interface.options = Some(BlockOptions(vec![BlockOption {
+            code: InterfaceOptionCodes::IfTimestampResolution as u16,
+            length: 1,
+            pen: None,
+            value: vec![TsResolution::NANOSECONDS.to_byte()],
+        }]));
+    }
+    interface
+}
+/// Converts a pcap timestamp to the units of [`interface_for_pcap`]
+pub(crate) fn pcap_ng_timestamp(file_header: &PcapFileHeader, timestamp: PacketTimestamp) -> u64 {
+    let units_per_second = match file_header.resolution() {
+        MagicNumber::Nanosecond => 1_000_000_000,
+        _ => 1_000_000,
+    };
+    timestamp.seconds as u64 * units_per_second + timestamp.usec as u64
+}
+/// A pcap packet converted from a pcapng block
+pub(crate) struct ConvertedPacket<'b> {
+    /// The pcap file header if it must be written before this packet
+    pub file_header: Option<PcapFileHeader>,
+    pub header: PacketHeader,
+    pub content: &'b [u8],
+}
+/// Tracks the interfaces of a pcapng file while it is converted to pcap
+#[derive(Debug)]
+pub(crate) struct PcapNgToPcap {
+    /// Byte order of the current section
+    byte_order: Endianness,
+    interfaces: Vec<InterfaceDescriptionBlock>,
+    file_header: Option<PcapFileHeader>,
+}
+impl PcapNgToPcap {
+    /// `byte_order` is the byte order of the first section
+    pub fn new(byte_order: Endianness) -> Self {
+        Self {
+            byte_order,
+            interfaces: Vec::new(),
+            file_header: None,
+        }
+    }
+    /// Converts a packet block to a pcap packet
+    ///
+    /// Returns `Ok(None)` for blocks that are not packets
+    pub fn convert<'b>(
+        &mut self,
+        block: &'b PcapNgBlock<'_>,
+    ) -> Result<Option<ConvertedPacket<'b>>, ConvertError> {
+        let (interface_id, raw_timestamp, original_length, content) = match block {
+            PcapNgBlock::SectionHeader(section) => {
+                self.byte_order = section.byte_order;
+                self.interfaces.clear();
+                return Ok(None);
+            }
+            PcapNgBlock::InterfaceDescription(interface) => {
+                self.interfaces.push(interface.clone());
+                return Ok(None);
+            }
+            PcapNgBlock::EnhancedPacket(packet) => (
+                packet.interface_id,
+                Some(packet.timestamp_raw()),
+                packet.original_length,
+                &packet.content,
+            ),
+            PcapNgBlock::SimplePacket(packet) => (0, None, packet.original_length, &packet.content),
+            _ => return Ok(None),
+        };
+        let interface = self.interfaces.get(interface_id as usize).ok_or(
+            PcapNgParseError::UndeclaredInterface {
+                interface_id,
+                declared: self.interfaces.len(),
+            },
+        )?;
+        let write_file_header = self.file_header.is_none();
+        let file_header = *self
+            .file_header
+            .get_or_insert_with(|| pcap_header_for_interface(interface));
+        if interface.link_type != file_header.link_type {
+            return Err(ConvertError::MixedLinkTypes {
+                expected: file_header.link_type,
+                got: interface.link_type,
+            });
+        }
+        if content.len() > file_header.snap_length as usize {
+            return Err(PcapParseError::InvalidPacketLength {
+                snap_length: file_header.snap_length,
+                incl_len: content.len() as u32,
+            }
+            .into());
+        }
+        let resolution = interface.ts_resolution();
+        let timestamp = raw_timestamp
+            .map(|raw| {
+                let timestamp = rescale_timestamp(
+                    timestamp_from_raw(raw, resolution),
+                    resolution.pcap_magic_number(),
+                    file_header.resolution(),
+                );
+                apply_ts_offset(
+                    timestamp,
+                    interface.ts_offset_secs(self.byte_order).unwrap_or(0),
+                )
+            })
+            .unwrap_or_default();
+        Ok(Some(ConvertedPacket {
+            file_header: write_file_header.then_some(file_header),
+            header: PacketHeader::new(timestamp, content.len() as u32, original_length),
+            content,
+        }))
+    }
+    /// Byte order of the pcap file
+    pub fn endianness(&self) -> Endianness {
+        self.file_header
+            .map(|header| header.endianness())
+            .unwrap_or(Endianness::LittleEndian)
+    }
+    /// Version of the pcap file
+    pub fn version(&self) -> crate::Version {
+        self.file_header
+            .map(|header| header.version)
+            .unwrap_or(crate::Version::PCAP_VERSION_2_4)
+    }
+    /// Returns the file header if no packets were converted
+    ///
+    /// Uses the first interface if there is one
+    pub fn finish(&mut self) -> Option<PcapFileHeader> {
+        if self.file_header.is_some() {
+            return None;
+        }
+        let header = self
+            .interfaces
+            .first()
+            .map(pcap_header_for_interface)
+            .unwrap_or_default();
+        self.file_header = Some(header);
+        Some(header)
+    }
+}
+fn pcap_header_for_interface(interface: &InterfaceDescriptionBlock) -> PcapFileHeader {
+    let mut header = PcapFileHeader::builder()
+        .link_type(interface.link_type)
+        .snap_length(if interface.snap_length == 0 {
+            MAXIMUM_SNAP_LENGTH
+        } else {
+            interface.snap_length
+        })
+        .build();
+    header.magic_number_and_endianness = MagicNumberAndEndianness {
+        magic_number: interface.ts_resolution().pcap_magic_number(),
+        endianness: Endianness::LittleEndian,
+    };
+    header
+}
+/// Converts the sub second part of `timestamp` from the units of `from` to the units of `to`
+fn rescale_timestamp(
+    mut timestamp: PacketTimestamp,
+    from: MagicNumber,
+    to: MagicNumber,
+) -> PacketTimestamp {
+    match (from, to) {
+        (MagicNumber::Nanosecond, MagicNumber::Microsecond | MagicNumber::Modified) => {
+            timestamp.usec /= 1000;
+        }
+        (MagicNumber::Microsecond | MagicNumber::Modified, MagicNumber::Nanosecond) => {
+            timestamp.usec *= 1000;
+        }
+        _ => {}
+    }
+    timestamp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+
+    use crate::{any_reader::SyncAnyPcapReader, pcap_ng::blocks::EnhancedPacket};
+
+    /// Reads every packet as `(timestamp, original length, data)`
+    fn read_pcap(file: &[u8]) -> anyhow::Result<Vec<(PacketTimestamp, u32, Vec<u8>)>> {
+        let mut reader = SyncPcapReader::new(file)?;
+        let mut packets = Vec::new();
+        while let Some((header, data)) = reader.next_packet()? {
+            packets.push((header.timestamp, header.orig_len, data.to_vec()));
+        }
+        Ok(packets)
+    }
+
+    #[test]
+    fn split_by_interface_two_interfaces() -> anyhow::Result<()> {
+        for declare_section_length in [false, true] {
+            let mut writer = SyncPcapNgWriter::new(
+                Vec::new(),
+                SectionHeaderBlock::new(Endianness::LittleEndian),
+            )?;
+            writer.add_interface(InterfaceDescriptionBlock::new(LinkType::Ethernet, 0))?;
+            writer.add_interface(InterfaceDescriptionBlock::new(LinkType::Raw, 1500))?;
+            for index in 0..10u8 {
+                writer.write_packet((index % 2) as u32, index as u64, &[index; 4], None)?;
+            }
+            let mut file = writer.into_inner();
+            if declare_section_length {
+                // The section length counts the bytes after the section header block
+                let header_length = u32::from_le_bytes(file[4..8].try_into()?) as usize;
+                let section_length = (file.len() - header_length) as u64;
+                file[16..24].copy_from_slice(&section_length.to_le_bytes());
+                let reader = SyncPcapNgReader::new(file.as_slice())?;
+                assert_eq!(
+                    reader.current_section().section_length,
+                    Some(section_length)
+                );
+            }
+
+            let out_dir = crate::test_helpers::test_target_dir()?
+                .join(format!("split_by_interface_{declare_section_length}"));
+            std::fs::create_dir_all(&out_dir)?;
+            let paths = split_by_interface(file.as_slice(), &out_dir)?;
+            assert_eq!(paths.len(), 2);
+            for (interface_id, (path, link_type)) in paths
+                .iter()
+                .zip([LinkType::Ethernet, LinkType::Raw])
+                .enumerate()
+            {
+                let mut reader = SyncPcapNgReader::new(File::open(path)?)?;
+                assert_eq!(reader.current_section().section_length, None);
+                let mut packets = Vec::new();
+                while let Some((header, interface, data)) = reader.next_packet_with_interface()? {
+                    assert_eq!(header.interface_id(), 0);
+                    assert_eq!(interface.link_type, link_type);
+                    packets.push(data[0]);
+                }
+                assert_eq!(reader.interfaces().len(), 1);
+                let expected: Vec<u8> = (0..10)
+                    .filter(|index| index % 2 == interface_id as u8)
+                    .collect();
+                assert_eq!(packets, expected);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn resnap_truncates_packets() -> anyhow::Result<()> {
+        let original = std::fs::read("test_data/test.pcap")?;
+        let mut resnapped = io::Cursor::new(Vec::new());
+        let copied = resnap(original.as_slice(), &mut resnapped, 64)?;
+        let resnapped = resnapped.into_inner();
+
+        let expected = read_pcap(&original)?;
+        assert_eq!(copied, expected.len() as u64);
+        let reader = SyncPcapReader::new(resnapped.as_slice())?;
+        assert_eq!(reader.file_header().snap_length, 64);
+        let packets = read_pcap(&resnapped)?;
+        assert_eq!(packets.len(), expected.len());
+        assert!(expected.iter().any(|(_, _, data)| data.len() > 64));
+        for ((timestamp, orig_len, data), (expected_timestamp, expected_orig_len, expected_data)) in
+            packets.iter().zip(&expected)
+        {
+            assert_eq!(timestamp, expected_timestamp);
+            assert_eq!(orig_len, expected_orig_len);
+            assert_eq!(data.len(), expected_data.len().min(64));
+            assert_eq!(data[..], expected_data[..data.len()]);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn resnap_keeps_legacy_timezone() -> anyhow::Result<()> {
+        let mut original = std::fs::read("test_data/test.pcap")?;
+        let mut file_header = SyncPcapReader::new(original.as_slice())?
+            .file_header()
+            .to_owned();
+        // -3600 as stored by some legacy writers
+        file_header.timezone = (-3600i32) as u32;
+        file_header.sig_figs = 6;
+        original[..PcapFileHeader::SIZE].copy_from_slice(&file_header.to_bytes());
+
+        let mut resnapped = io::Cursor::new(Vec::new());
+        resnap(original.as_slice(), &mut resnapped, 64)?;
+        let resnapped = resnapped.into_inner();
+        let reader = SyncPcapReader::new(resnapped.as_slice())?;
+        assert_eq!(reader.file_header().timezone, file_header.timezone);
+        assert_eq!(reader.file_header().sig_figs, 6);
+        assert_eq!(reader.file_header().snap_length, 64);
+        Ok(())
+    }
+
+    #[test]
+    fn resnap_rejects_zero_snap_length() -> anyhow::Result<()> {
+        let original = std::fs::read("test_data/test.pcap")?;
+        let mut resnapped = io::Cursor::new(Vec::new());
+        let err = resnap(original.as_slice(), &mut resnapped, 0).unwrap_err();
+        assert!(matches!(
+            err,
+            ConvertError::Pcap(PcapParseError::InvalidSnapLength)
+        ));
+        assert!(resnapped.into_inner().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn pcap_to_pcapng_and_back() -> anyhow::Result<()> {
+        let original = std::fs::read("test_data/test.pcap")?;
+        let mut pcap_ng = Vec::new();
+        let converted = convert_pcap_to_pcapng(original.as_slice(), &mut pcap_ng)?;
+
+        let expected = read_pcap(&original)?;
+        assert_eq!(converted, expected.len() as u64);
+        let mut reader = SyncAnyPcapReader::new(pcap_ng.as_slice())?;
+        assert_eq!(reader.file_type(), crate::PcapFileType::PcapNg);
+        for (_, orig_len, data) in &expected {
+            let (header, read) = reader.next_packet()?.expect("packet");
+            assert_eq!(read, data.as_slice());
+            assert_eq!(header.original_length(), *orig_len);
+        }
+        assert!(reader.next_packet()?.is_none());
+
+        let mut pcap = Vec::new();
+        assert_eq!(
+            convert_pcapng_to_pcap(pcap_ng.as_slice(), &mut pcap)?,
+            converted
+        );
+        assert_eq!(read_pcap(&pcap)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn pcapng_to_pcap() -> anyhow::Result<()> {
+        for file_name in [
+            "test_data/ng/test001_le.pcapng",
+            "test_data/ng/test001_be.pcapng",
+        ] {
+            let original = std::fs::read(file_name)?;
+            let mut pcap = Vec::new();
+            let converted = convert_pcapng_to_pcap(original.as_slice(), &mut pcap)?;
+
+            let mut expected = Vec::new();
+            let mut reader = SyncPcapNgReader::new(original.as_slice())?;
+            while let Some((header, data)) = reader.next_packet()? {
+                expected.push((header.original_length(), data.to_vec()));
+            }
+            assert_eq!(converted, expected.len() as u64);
+
+            let mut reader = SyncPcapReader::new(pcap.as_slice())?;
+            assert_eq!(reader.file_header().link_type, LinkType::Ethernet);
+            for (orig_len, data) in &expected {
+                let (header, read) = reader.next_packet()?.expect("packet");
+                assert_eq!(read, data.as_slice());
+                assert_eq!(header.orig_len, *orig_len);
+            }
+            assert!(reader.next_packet()?.is_none());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn pcapng_to_pcap_nanosecond_and_mixed_link_types() -> anyhow::Result<()> {
+        let mut writer =
+            SyncPcapNgWriter::new(Vec::new(), SectionHeaderBlock::new(Endianness::BigEndian))?;
+        let nanosecond_header = PcapFileHeader {
+            magic_number_and_endianness: MagicNumberAndEndianness {
+                magic_number: MagicNumber::Nanosecond,
+                endianness: Endianness::LittleEndian,
+            },
+            ..Default::default()
+        };
+        writer.add_interface(interface_for_pcap(&nanosecond_header))?;
+        writer.add_interface(InterfaceDescriptionBlock::new(LinkType::Raw, 0))?;
+        writer.write_packet(0, 2_000_000_123, &[1, 2], None)?;
+        let file = writer.into_inner();
+
+        let mut pcap = Vec::new();
+        assert_eq!(convert_pcapng_to_pcap(file.as_slice(), &mut pcap)?, 1);
+        let mut reader = SyncPcapReader::new(pcap.as_slice())?;
+        assert_eq!(reader.file_header().resolution(), MagicNumber::Nanosecond);
+        let (header, _) = reader.next_packet()?.expect("packet");
+        assert_eq!(
+            header.timestamp,
+            PacketTimestamp {
+                seconds: 2,
+                usec: 123
+            }
+        );
+
+        let mut writer = SyncPcapNgWriter::new(
+            Vec::new(),
+            SectionHeaderBlock::new(Endianness::LittleEndian),
+        )?;
+        writer.add_interface(InterfaceDescriptionBlock::new(LinkType::Ethernet, 0))?;
+        writer.add_interface(InterfaceDescriptionBlock::new(LinkType::Raw, 0))?;
+        writer.write_packet(0, 0, &[1], None)?;
+        writer.write_enhanced_packet(&EnhancedPacket {
+            block_length: 0,
+            interface_id: 1,
+            timestamp_high: 0,
+            timestamp_low: 0,
+            captured_length: 1,
+            original_length: 1,
+            content: Cow::Borrowed(&[2]),
+            options: None,
+        })?;
+        let file = writer.into_inner();
+        let err = convert_pcapng_to_pcap(file.as_slice(), &mut Vec::new())
+            .expect_err("pcap files have one link type");
+        assert!(matches!(
+            err,
+            ConvertError::MixedLinkTypes {
+                expected: LinkType::Ethernet,
+                got: LinkType::Raw
+            }
+        ));
+        Ok(())
+    }
+}
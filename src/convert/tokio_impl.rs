@@ -0,0 +1,120 @@
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+
+use super::{ConvertError, PcapNgToPcap, interface_for_pcap, pcap_ng_timestamp};
+use crate::{
+    byte_order::Endianness,
+    pcap::AsyncPcapReader,
+    pcap_ng::{AsyncPcapNgReader, AsyncPcapNgWriter, blocks::SectionHeaderBlock},
+};
+
+/// Converts a pcap file to a pcapng file
+///
+/// The async counterpart to [`convert_pcap_to_pcapng`](super::convert_pcap_to_pcapng).
+/// Returns the number of packets converted
+pub async fn convert_pcap_to_pcapng_async<R: AsyncRead + Unpin, W: AsyncWrite + Unpin>(
+    reader: R,
+    writer: W,
+) -> Result<u64, ConvertError> {
+    let mut reader = AsyncPcapReader::new(reader).await?;
+    let file_header = *reader.file_header();
+    let mut writer =
+        AsyncPcapNgWriter::new(writer, SectionHeaderBlock::new(Endianness::LittleEndian)).await?;
+    writer
+        .add_interface(interface_for_pcap(&file_header))
+        .await?;
+    while let Some((header, data)) = reader.next_packet().await? {
+        writer
+            .write_packet(
+                0,
+                pcap_ng_timestamp(&file_header, header.timestamp),
+                data,
+                Some(header.orig_len),
+            )
+            .await?;
+    }
+    let packets = writer.packets_written();
+    writer.finish().await?;
+    Ok(packets)
+}
+/// Converts a pcapng file to a pcap file
+///
+/// The async counterpart to [`convert_pcapng_to_pcap`](super::convert_pcapng_to_pcap).
+/// Returns the number of packets converted
+pub async fn convert_pcapng_to_pcap_async<R: AsyncRead + Unpin, W: AsyncWrite + Unpin>(
+    reader: R,
+    mut writer: W,
+) -> Result<u64, ConvertError> {
+    let mut reader = AsyncPcapNgReader::new(reader).await?;
+    let mut converter = PcapNgToPcap::new(reader.current_section().byte_order);
+    let mut buffer = Vec::new();
+    let mut packets = 0;
+    while let Some(block) = reader.next_block().await? {
+        let Some(packet) = converter.convert(&block)? else {
+            continue;
+        };
+        buffer.clear();
+        if let Some(file_header) = packet.file_header {
+            file_header.write(&mut buffer)?;
+        }
+        packet
+            .header
+            .write(&mut buffer, converter.endianness(), &converter.version())?;
+        writer.write_all(&buffer).await?;
+        writer.write_all(packet.content).await?;
+        packets += 1;
+    }
+    if let Some(file_header) = converter.finish() {
+        writer.write_all(&file_header.to_bytes()).await?;
+    }
+    writer.flush().await?;
+    Ok(packets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PcapFileType, any_reader::AsyncAnyPcapReader};
+
+    /// Reads every packet as `(original length, data)`
+    async fn read_packets(file: &[u8]) -> anyhow::Result<Vec<(u32, Vec<u8>)>> {
+        let mut reader = AsyncAnyPcapReader::new(file).await?;
+        let mut packets = Vec::new();
+        while let Some((header, data)) = reader.next_packet().await? {
+            packets.push((header.original_length(), data.to_vec()));
+        }
+        Ok(packets)
+    }
+
+    #[tokio::test]
+    async fn pcap_to_pcapng() -> anyhow::Result<()> {
+        let original = std::fs::read("test_data/test.pcap")?;
+        let mut pcap_ng = Vec::new();
+        let converted = convert_pcap_to_pcapng_async(original.as_slice(), &mut pcap_ng).await?;
+
+        let expected = read_packets(&original).await?;
+        assert_eq!(converted, expected.len() as u64);
+        let reader = AsyncAnyPcapReader::new(pcap_ng.as_slice()).await?;
+        assert_eq!(reader.file_type(), PcapFileType::PcapNg);
+        assert_eq!(read_packets(&pcap_ng).await?, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn pcapng_to_pcap() -> anyhow::Result<()> {
+        for file_name in [
+            "test_data/ng/test001_le.pcapng",
+            "test_data/ng/test001_be.pcapng",
+        ] {
+            let original = std::fs::read(file_name)?;
+            let mut pcap = Vec::new();
+            let converted = convert_pcapng_to_pcap_async(original.as_slice(), &mut pcap).await?;
+
+            let expected = read_packets(&original).await?;
+            assert_eq!(converted, expected.len() as u64);
+            let reader = AsyncAnyPcapReader::new(pcap.as_slice()).await?;
+            assert_eq!(reader.file_type(), PcapFileType::Pcap);
+            assert_eq!(read_packets(&pcap).await?, expected);
+        }
+        Ok(())
+    }
+}
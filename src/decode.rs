@@ -0,0 +1,316 @@
+//! Locating the network layer inside link-layer frames
+//!
+//! Requires the `decode` feature.
+//!
+//! Only the link-layer header is decoded. The returned payload can be handed to a packet parser such as `etherparse`.
+use crate::link_type::LinkType;
+#[cfg(feature = "etherparse")]
+mod checksum;
+#[cfg(feature = "etherparse")]
+pub use checksum::recompute_checksums;
+#[cfg(feature = "etherparse")]
+mod flow;
+#[cfg(feature = "etherparse")]
+pub use flow::{FlowKey, FlowReader, PacketRef};
+#[cfg(feature = "etherparse")]
+mod slice;
+#[cfg(feature = "etherparse")]
+pub use slice::{PacketSliceError, PacketSliceExt};
+
+/// EtherType for IPv4
+pub const ETHER_TYPE_IPV4: u16 = 0x0800;
+/// EtherType for IPv6
+pub const ETHER_TYPE_IPV6: u16 = 0x86DD;
+/// EtherType for 802.1Q VLAN tags
+const ETHER_TYPE_VLAN: u16 = 0x8100;
+/// EtherType for 802.1ad (QinQ) service VLAN tags
+const ETHER_TYPE_QINQ: u16 = 0x88A8;
+
+/// Length of an Ethernet header without VLAN tags
+const ETHERNET_HEADER_LENGTH: usize = 14;
+/// Length of a Linux "cooked" capture header
+const LINUX_SLL_HEADER_LENGTH: usize = 16;
+/// Length of a Linux "cooked" capture v2 header
+const LINUX_SLL2_HEADER_LENGTH: usize = 20;
+/// Length of the protocol family header of [`LinkType::Null`] and [`LinkType::Loop`]
+const NULL_HEADER_LENGTH: usize = 4;
+
+/// `AF_INET` is the same on every platform
+const AF_INET: u32 = 2;
+/// `AF_INET6` values used by Linux, Windows, NetBSD/OpenBSD, FreeBSD and Darwin
+const AF_INET6: [u32; 5] = [10, 23, 24, 28, 30];
+
+/// Protocol family from a [`LinkType::Null`] or [`LinkType::Loop`] header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AddressFamily {
+    /// `AF_INET`
+    Inet,
+    /// `AF_INET6`. The value depends on the capturing operating system
+    Inet6,
+    /// Any other family value
+    Other(u32),
+}
+impl AddressFamily {
+    /// Maps a raw family value to an [`AddressFamily`]
+    pub fn from_raw(value: u32) -> Self {
+        match value {
+            AF_INET => AddressFamily::Inet,
+            value if AF_INET6.contains(&value) => AddressFamily::Inet6,
+            other => AddressFamily::Other(other),
+        }
+    }
+    /// EtherType matching the family if it is an IP family
+    pub fn ether_type(&self) -> Option<u16> {
+        match self {
+            AddressFamily::Inet => Some(ETHER_TYPE_IPV4),
+            AddressFamily::Inet6 => Some(ETHER_TYPE_IPV6),
+            AddressFamily::Other(_) => None,
+        }
+    }
+}
+
+/// The result of [`decode_link_layer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkLayerFrame<'a> {
+    /// The link-layer header was decoded
+    Decoded {
+        /// EtherType of the payload if the link layer declares one
+        ///
+        /// For raw IP link types this is derived from the IP version.
+        ether_type: Option<u16>,
+        /// Offset of the network-layer payload from the start of the frame
+        payload_offset: usize,
+        /// The network-layer payload
+        payload: &'a [u8],
+    },
+    /// The frame is shorter than its link-layer header
+    Truncated {
+        /// Number of bytes needed to decode the link-layer header
+        required: usize,
+    },
+    /// The link type is not decoded
+    Unsupported(LinkType),
+}
+impl<'a> LinkLayerFrame<'a> {
+    /// Offset of the network-layer payload if the frame was decoded
+    pub fn payload_offset(&self) -> Option<usize> {
+        match self {
+            LinkLayerFrame::Decoded { payload_offset, .. } => Some(*payload_offset),
+            _ => None,
+        }
+    }
+    /// The network-layer payload if the frame was decoded
+    pub fn payload(&self) -> Option<&'a [u8]> {
+        match self {
+            LinkLayerFrame::Decoded { payload, .. } => Some(payload),
+            _ => None,
+        }
+    }
+    /// EtherType of the payload if the frame was decoded and declares one
+    pub fn ether_type(&self) -> Option<u16> {
+        match self {
+            LinkLayerFrame::Decoded { ether_type, .. } => *ether_type,
+            _ => None,
+        }
+    }
+}
+/// Finds the network-layer payload of a frame captured with `link_type`
+///
+/// Supports [`LinkType::Ethernet`] (including 802.1Q and 802.1ad VLAN tags), [`LinkType::LinuxSll`],
+/// [`LinkType::LinuxSll2`], [`LinkType::Null`], [`LinkType::Loop`], [`LinkType::Raw`], [`LinkType::Ipv4`]
+/// and [`LinkType::Ipv6`].
+pub fn decode_link_layer(link_type: LinkType, data: &[u8]) -> LinkLayerFrame<'_> {
+    match link_type {
+        LinkType::Ethernet => decode_ethernet(data),
+        LinkType::LinuxSll => decode_fixed(data, LINUX_SLL_HEADER_LENGTH, |header| {
+            Some(read_u16_be(header, 14))
+        }),
+        LinkType::LinuxSll2 => decode_fixed(data, LINUX_SLL2_HEADER_LENGTH, |header| {
+            Some(read_u16_be(header, 0))
+        }),
+        LinkType::Raw => decode_fixed(data, 0, |_| match data.first().map(|byte| byte >> 4) {
+            Some(4) => Some(ETHER_TYPE_IPV4),
+            Some(6) => Some(ETHER_TYPE_IPV6),
+            _ => None,
+        }),
+        LinkType::Ipv4 => decode_fixed(data, 0, |_| Some(ETHER_TYPE_IPV4)),
+        LinkType::Ipv6 => decode_fixed(data, 0, |_| Some(ETHER_TYPE_IPV6)),
+        LinkType::Null | LinkType::Loop => decode_fixed(data, NULL_HEADER_LENGTH, |_| {
+            decode_null_loopback(data, link_type).and_then(|(family, _)| family.ether_type())
+        }),
+        other => LinkLayerFrame::Unsupported(other),
+    }
+}
+/// Reads the protocol family header of a [`LinkType::Null`] or [`LinkType::Loop`] frame
+///
+/// [`LinkType::Null`] stores the family in the byte order of the capturing host and [`LinkType::Loop`] in
+/// network byte order, but writers do not always follow this. Family values are small, so the byte order
+/// that produces a value below 256 is used.
+///
+/// Returns `None` if `link_type` is neither of the two or the frame is shorter than the header.
+pub fn decode_null_loopback(data: &[u8], link_type: LinkType) -> Option<(AddressFamily, &[u8])> {
+    if !matches!(link_type, LinkType::Null | LinkType::Loop) {
+        return None;
+    }
+    let (header, payload) = data.split_first_chunk::<NULL_HEADER_LENGTH>()?;
+    let little = u32::from_le_bytes(*header);
+    let big = u32::from_be_bytes(*header);
+    let value = match link_type {
+        LinkType::Loop if big <= 0xFF || little > 0xFF => big,
+        _ if little <= 0xFF || big > 0xFF => little,
+        _ => big,
+    };
+    Some((AddressFamily::from_raw(value), payload))
+}
+fn decode_fixed(
+    data: &[u8],
+    header_length: usize,
+    ether_type: impl FnOnce(&[u8]) -> Option<u16>,
+) -> LinkLayerFrame<'_> {
+    if data.len() < header_length {
+        return LinkLayerFrame::Truncated {
+            required: header_length,
+        };
+    }
+    LinkLayerFrame::Decoded {
+        ether_type: ether_type(&data[..header_length]),
+        payload_offset: header_length,
+        payload: &data[header_length..],
+    }
+}
+fn decode_ethernet(data: &[u8]) -> LinkLayerFrame<'_> {
+    let mut header_length = ETHERNET_HEADER_LENGTH;
+    loop {
+        if data.len() < header_length {
+            return LinkLayerFrame::Truncated {
+                required: header_length,
+            };
+        }
+        let ether_type = read_u16_be(data, header_length - 2);
+        if ether_type == ETHER_TYPE_VLAN || ether_type == ETHER_TYPE_QINQ {
+            // The tag sits before the real EtherType
+            header_length += 4;
+            continue;
+        }
+        return LinkLayerFrame::Decoded {
+            ether_type: Some(ether_type),
+            payload_offset: header_length,
+            payload: &data[header_length..],
+        };
+    }
+}
+/// Caller must ensure `data` has at least `offset + 2` bytes
+fn read_u16_be(data: &[u8], offset: usize) -> u16 {
+    u16::from_be_bytes([data[offset], data[offset + 1]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ethernet() {
+        let mut frame = vec![0xFF; 12];
+        frame.extend_from_slice(&ETHER_TYPE_IPV4.to_be_bytes());
+        frame.extend_from_slice(&[0x45, 0, 0, 20]);
+        let decoded = decode_link_layer(LinkType::Ethernet, &frame);
+        assert_eq!(decoded.payload_offset(), Some(14));
+        assert_eq!(decoded.ether_type(), Some(ETHER_TYPE_IPV4));
+        assert_eq!(decoded.payload(), Some(&[0x45, 0, 0, 20][..]));
+
+        // 802.1Q tagged
+        let mut tagged = vec![0xFF; 12];
+        tagged.extend_from_slice(&ETHER_TYPE_VLAN.to_be_bytes());
+        tagged.extend_from_slice(&[0, 10]);
+        tagged.extend_from_slice(&ETHER_TYPE_IPV6.to_be_bytes());
+        let decoded = decode_link_layer(LinkType::Ethernet, &tagged);
+        assert_eq!(decoded.payload_offset(), Some(18));
+        assert_eq!(decoded.ether_type(), Some(ETHER_TYPE_IPV6));
+
+        assert_eq!(
+            decode_link_layer(LinkType::Ethernet, &tagged[..16]),
+            LinkLayerFrame::Truncated { required: 18 }
+        );
+    }
+
+    #[test]
+    fn linux_sll() {
+        let mut frame = vec![0; 14];
+        frame.extend_from_slice(&ETHER_TYPE_IPV6.to_be_bytes());
+        frame.push(0x60);
+        let decoded = decode_link_layer(LinkType::LinuxSll, &frame);
+        assert_eq!(decoded.payload_offset(), Some(16));
+        assert_eq!(decoded.ether_type(), Some(ETHER_TYPE_IPV6));
+        assert_eq!(decoded.payload(), Some(&[0x60][..]));
+
+        let mut frame = ETHER_TYPE_IPV4.to_be_bytes().to_vec();
+        frame.extend_from_slice(&[0; 18]);
+        let decoded = decode_link_layer(LinkType::LinuxSll2, &frame);
+        assert_eq!(decoded.payload_offset(), Some(20));
+        assert_eq!(decoded.ether_type(), Some(ETHER_TYPE_IPV4));
+
+        assert_eq!(
+            decode_link_layer(LinkType::LinuxSll, &[0; 4]),
+            LinkLayerFrame::Truncated { required: 16 }
+        );
+    }
+
+    #[test]
+    fn raw_ip() {
+        let decoded = decode_link_layer(LinkType::Raw, &[0x60, 0, 0, 0]);
+        assert_eq!(decoded.payload_offset(), Some(0));
+        assert_eq!(decoded.ether_type(), Some(ETHER_TYPE_IPV6));
+        assert_eq!(
+            decode_link_layer(LinkType::Ipv4, &[0x45]).ether_type(),
+            Some(ETHER_TYPE_IPV4)
+        );
+        assert_eq!(
+            decode_link_layer(LinkType::Ppp, &[0; 4]),
+            LinkLayerFrame::Unsupported(LinkType::Ppp)
+        );
+    }
+
+    #[test]
+    fn null_loopback() {
+        let mut frame = AF_INET.to_le_bytes().to_vec();
+        frame.extend_from_slice(&[0x45, 0, 0, 20]);
+        assert_eq!(
+            decode_null_loopback(&frame, LinkType::Null),
+            Some((AddressFamily::Inet, &[0x45, 0, 0, 20][..]))
+        );
+        let decoded = decode_link_layer(LinkType::Null, &frame);
+        assert_eq!(decoded.payload_offset(), Some(4));
+        assert_eq!(decoded.ether_type(), Some(ETHER_TYPE_IPV4));
+
+        // Loop uses network byte order
+        let mut frame = 24u32.to_be_bytes().to_vec();
+        frame.push(0x60);
+        assert_eq!(
+            decode_null_loopback(&frame, LinkType::Loop),
+            Some((AddressFamily::Inet6, &[0x60][..]))
+        );
+        // A big endian host writing Null
+        assert_eq!(
+            decode_null_loopback(&30u32.to_be_bytes(), LinkType::Null),
+            Some((AddressFamily::Inet6, &[][..]))
+        );
+        assert_eq!(
+            decode_link_layer(LinkType::Null, &10u32.to_le_bytes()).ether_type(),
+            Some(ETHER_TYPE_IPV6)
+        );
+
+        assert_eq!(
+            decode_null_loopback(&7u32.to_le_bytes(), LinkType::Null),
+            Some((AddressFamily::Other(7), &[][..]))
+        );
+        assert_eq!(decode_null_loopback(&[2, 0], LinkType::Null), None);
+        assert_eq!(
+            decode_null_loopback(&AF_INET.to_le_bytes(), LinkType::Ethernet),
+            None
+        );
+        assert_eq!(
+            decode_link_layer(LinkType::Loop, &[0; 2]),
+            LinkLayerFrame::Truncated { required: 4 }
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use etherparse::{IpNumber, IpSlice, TcpHeader, UdpHeader};
+
+use super::decode_link_layer;
+use crate::link_type::LinkType;
+
+/// Offset of the checksum inside an IPv4 header
+const IPV4_CHECKSUM_OFFSET: usize = 10;
+/// Offset of the checksum inside a TCP header
+const TCP_CHECKSUM_OFFSET: usize = 16;
+/// Offset of the checksum inside a UDP header
+const UDP_CHECKSUM_OFFSET: usize = 6;
+
+/// Source and destination addresses used for the transport pseudo header
+enum Addresses {
+    V4([u8; 4], [u8; 4]),
+    V6([u8; 16], [u8; 16]),
+}
+
+/// Recomputes the IPv4 header, TCP and UDP checksums of a packet in place
+///
+/// Requires the `etherparse` feature.
+///
+/// This is a best-effort convenience for tools that rewrite packet contents. The packet is reparsed with `etherparse`
+/// and any checksum that can be computed is overwritten. Packets that can not be parsed, are not IP, are fragmented or
+/// are truncated by the snap length are left untouched. IPv4 UDP packets without a checksum (a checksum of zero) keep it disabled.
+///
+/// Returns `true` if at least one checksum was rewritten
+pub fn recompute_checksums(link_type: LinkType, packet: &mut [u8]) -> bool {
+    let Some(network_offset) = decode_link_layer(link_type, packet).payload_offset() else {
+        return false;
+    };
+    let network = &mut packet[network_offset..];
+    let Ok(ip) = IpSlice::from_slice(network) else {
+        return false;
+    };
+    let (addresses, ipv4_checksum) = match &ip {
+        IpSlice::Ipv4(ipv4) => {
+            let header = ipv4.header();
+            (
+                Addresses::V4(header.source(), header.destination()),
+                Some(header.to_header().calc_header_checksum()),
+            )
+        }
+        IpSlice::Ipv6(ipv6) => {
+            let header = ipv6.header();
+            (Addresses::V6(header.source(), header.destination()), None)
+        }
+    };
+    let payload = ip.payload();
+    let transport = (!payload.fragmented).then(|| {
+        let start = payload.payload.as_ptr() as usize - network.as_ptr() as usize;
+        (payload.ip_number, start, start + payload.payload.len())
+    });
+
+    let mut rewritten = false;
+    if let Some(checksum) = ipv4_checksum {
+        write_checksum(network, IPV4_CHECKSUM_OFFSET, checksum);
+        rewritten = true;
+    }
+    if let Some((ip_number, start, end)) = transport {
+        let segment = &mut network[start..end];
+        let checksum = match ip_number {
+            IpNumber::TCP => tcp_checksum(&addresses, segment).map(|c| (TCP_CHECKSUM_OFFSET, c)),
+            IpNumber::UDP => udp_checksum(&addresses, segment).map(|c| (UDP_CHECKSUM_OFFSET, c)),
+            _ => None,
+        };
+        if let Some((offset, checksum)) = checksum {
+            write_checksum(segment, offset, checksum);
+            rewritten = true;
+        }
+    }
+    rewritten
+}
+
+fn tcp_checksum(addresses: &Addresses, segment: &[u8]) -> Option<u16> {
+    let (header, payload) = TcpHeader::from_slice(segment).ok()?;
+    match addresses {
+        Addresses::V4(source, destination) => header
+            .calc_checksum_ipv4_raw(*source, *destination, payload)
+            .ok(),
+        Addresses::V6(source, destination) => header
+            .calc_checksum_ipv6_raw(*source, *destination, payload)
+            .ok(),
+    }
+}
+
+fn udp_checksum(addresses: &Addresses, segment: &[u8]) -> Option<u16> {
+    let (header, _) = UdpHeader::from_slice(segment).ok()?;
+    // The UDP length excludes any trailing padding
+    let payload = segment.get(UdpHeader::LEN..header.length as usize)?;
+    match addresses {
+        Addresses::V4(_, _) if header.checksum == 0 => None,
+        Addresses::V4(source, destination) => header
+            .calc_checksum_ipv4_raw(*source, *destination, payload)
+            .ok(),
+        Addresses::V6(source, destination) => header
+            .calc_checksum_ipv6_raw(*source, *destination, payload)
+            .ok(),
+    }
+}
+
+fn write_checksum(data: &mut [u8], offset: usize, checksum: u16) {
+    data[offset..offset + 2].copy_from_slice(&checksum.to_be_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use etherparse::{PacketBuilder, SlicedPacket, TransportSlice};
+
+    use super::*;
+
+    fn build_udp(payload: &[u8]) -> Vec<u8> {
+        let builder = PacketBuilder::ethernet2([1, 2, 3, 4, 5, 6], [7, 8, 9, 10, 11, 12])
+            .ipv4([192, 168, 1, 1], [192, 168, 1, 2], 20)
+            .udp(21, 1234);
+        let mut packet = Vec::with_capacity(builder.size(payload.len()));
+        builder.write(&mut packet, payload).unwrap();
+        packet
+    }
+
+    #[test]
+    fn udp_payload_rewrite() -> anyhow::Result<()> {
+        let mut packet = build_udp(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        // Rewrite the payload and the IPv4 TTL, invalidating both checksums
+        let length = packet.len();
+        packet[length - 8..].copy_from_slice(&[8, 7, 6, 5, 4, 3, 2, 1]);
+        packet[14 + 8] = 5;
+        let expected = build_udp(&[8, 7, 6, 5, 4, 3, 2, 1]);
+        let stale = packet.clone();
+
+        assert!(recompute_checksums(LinkType::Ethernet, &mut packet));
+        assert_ne!(packet[14 + 10..14 + 12], stale[14 + 10..14 + 12]);
+
+        let sliced = SlicedPacket::from_ethernet(&packet)?;
+        let Some(etherparse::NetSlice::Ipv4(ipv4)) = &sliced.net else {
+            panic!("Expected an IPv4 packet");
+        };
+        let ipv4_header = ipv4.header().to_header();
+        assert_eq!(
+            ipv4_header.header_checksum,
+            ipv4_header.calc_header_checksum()
+        );
+        let Some(TransportSlice::Udp(udp)) = &sliced.transport else {
+            panic!("Expected a UDP packet");
+        };
+        let udp_header = udp.to_header();
+        assert_eq!(
+            udp_header.checksum,
+            udp_header.calc_checksum_ipv4(&ipv4_header, udp.payload())?
+        );
+        // The TTL change is kept
+        assert_eq!(packet[14 + 8], 5);
+        assert_eq!(packet[14 + 20..], expected[14 + 20..]);
+        Ok(())
+    }
+
+    #[test]
+    fn tcp_ipv6() -> anyhow::Result<()> {
+        let builder = PacketBuilder::ipv6([1; 16], [2; 16], 64).tcp(80, 443, 1, 1024);
+        let mut packet = Vec::new();
+        builder.write(&mut packet, &[1, 2, 3])?;
+        let length = packet.len();
+        packet[length - 3..].copy_from_slice(&[0; 3]);
+
+        assert!(recompute_checksums(LinkType::Ipv6, &mut packet));
+        let sliced = SlicedPacket::from_ip(&packet)?;
+        let Some(TransportSlice::Tcp(tcp)) = &sliced.transport else {
+            panic!("Expected a TCP packet");
+        };
+        assert_eq!(tcp.checksum(), tcp.calc_checksum_ipv6([1; 16], [2; 16])?);
+        Ok(())
+    }
+
+    #[test]
+    fn not_ip() {
+        let mut packet = vec![0xFF; 12];
+        packet.extend_from_slice(&[0x08, 0x06, 0, 1]);
+        let copy = packet.clone();
+        assert!(!recompute_checksums(LinkType::Ethernet, &mut packet));
+        assert_eq!(packet, copy);
+        assert!(!recompute_checksums(LinkType::Ppp, &mut [0; 20]));
+    }
+}
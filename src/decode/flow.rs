@@ -0,0 +1,186 @@
+use std::{collections::HashMap, io::Read, net::IpAddr};
+
+use etherparse::{LaxNetSlice, LaxSlicedPacket, TransportSlice};
+
+use super::decode_link_layer;
+use crate::{
+    any_reader::{AnyPacketHeader, AnyPcapPacket, AnyPcapReaderError, SyncAnyPcapReader},
+    link_type::LinkType,
+};
+
+/// The flow a packet belongs to, returned by [`FlowReader::next_flow_packet`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FlowKey {
+    /// An IPv4 or IPv6 packet identified by its 5-tuple
+    Ip {
+        source: IpAddr,
+        destination: IpAddr,
+        /// 0 if the transport protocol has no ports or the transport header could not be parsed
+        source_port: u16,
+        /// 0 if the transport protocol has no ports or the transport header could not be parsed
+        destination_port: u16,
+        /// The IP protocol number of the payload
+        protocol: u8,
+    },
+    /// The packet is not IP or its link layer is not supported by [`decode_link_layer`]
+    NonIp,
+}
+impl FlowKey {
+    /// Parses the flow of a packet captured with `link_type`
+    pub fn from_packet(link_type: LinkType, data: &[u8]) -> Self {
+        let Some(payload) = decode_link_layer(link_type, data).payload() else {
+            return FlowKey::NonIp;
+        };
+        let Ok(packet) = LaxSlicedPacket::from_ip(payload) else {
+            return FlowKey::NonIp;
+        };
+        let (source, destination, protocol) = match &packet.net {
+            Some(LaxNetSlice::Ipv4(ipv4)) => (
+                IpAddr::V4(ipv4.header().source_addr()),
+                IpAddr::V4(ipv4.header().destination_addr()),
+                ipv4.payload().ip_number,
+            ),
+            Some(LaxNetSlice::Ipv6(ipv6)) => (
+                IpAddr::V6(ipv6.header().source_addr()),
+                IpAddr::V6(ipv6.header().destination_addr()),
+                ipv6.payload().ip_number,
+            ),
+            _ => return FlowKey::NonIp,
+        };
+        let (source_port, destination_port) = match &packet.transport {
+            Some(TransportSlice::Tcp(tcp)) => (tcp.source_port(), tcp.destination_port()),
+            Some(TransportSlice::Udp(udp)) => (udp.source_port(), udp.destination_port()),
+            _ => (0, 0),
+        };
+        FlowKey::Ip {
+            source,
+            destination,
+            source_port,
+            destination_port,
+            protocol: protocol.0,
+        }
+    }
+}
+/// A packet collected by [`FlowReader::into_flows`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PacketRef {
+    /// Position of the packet in the file, starting at 0
+    pub index: u64,
+    pub header: AnyPacketHeader,
+    pub data: Vec<u8>,
+}
+/// Wraps a [`SyncAnyPcapReader`] and tags every packet with its [`FlowKey`]
+///
+/// Requires the `etherparse` feature.
+#[derive(Debug)]
+pub struct FlowReader<R: Read> {
+    reader: SyncAnyPcapReader<R>,
+    /// The current packet, copied out of the reader so its link type can be looked up
+    buffer: Vec<u8>,
+}
+impl<R: Read> FlowReader<R> {
+    pub fn new(reader: SyncAnyPcapReader<R>) -> Self {
+        Self {
+            reader,
+            buffer: Vec::new(),
+        }
+    }
+    /// Returns the wrapped reader
+    pub fn into_inner(self) -> SyncAnyPcapReader<R> {
+        self.reader
+    }
+    /// Reads the next packet and the flow it belongs to
+    ///
+    /// Packets of unknown interfaces are keyed as [`FlowKey::NonIp`]
+    pub fn next_flow_packet(
+        &mut self,
+    ) -> Result<Option<(FlowKey, AnyPcapPacket<'_>)>, AnyPcapReaderError> {
+        let Some((header, data)) = self.reader.next_packet()? else {
+            return Ok(None);
+        };
+        self.buffer.clear();
+        self.buffer.extend_from_slice(data);
+        let key = match self.reader.link_type(header.interface_id()) {
+            Some(link_type) => FlowKey::from_packet(link_type, &self.buffer),
+            None => FlowKey::NonIp,
+        };
+        Ok(Some((key, (header, &self.buffer))))
+    }
+    /// Reads the remaining packets and groups them by flow
+    ///
+    /// Packets of a flow are kept in the order they were read
+    pub fn into_flows(mut self) -> Result<HashMap<FlowKey, Vec<PacketRef>>, AnyPcapReaderError> {
+        let mut flows: HashMap<FlowKey, Vec<PacketRef>> = HashMap::new();
+        let mut index = 0;
+        while let Some((key, (header, data))) = self.next_flow_packet()? {
+            flows.entry(key).or_default().push(PacketRef {
+                index,
+                header,
+                data: data.to_vec(),
+            });
+            index += 1;
+        }
+        Ok(flows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use etherparse::{NetSlice, SlicedPacket, TransportSlice};
+
+    use super::*;
+    use crate::pcap::SyncPcapReader;
+
+    #[test]
+    fn flows_of_test_pcap() -> anyhow::Result<()> {
+        let reader = SyncAnyPcapReader::new(File::open("test_data/test.pcap")?)?;
+        let flows = FlowReader::new(reader).into_flows()?;
+
+        let packets = SyncPcapReader::new(File::open("test_data/test.pcap")?)?.read_all()?;
+        assert_eq!(flows.values().map(Vec::len).sum::<usize>(), packets.len());
+        for (index, (_, data)) in packets.iter().enumerate() {
+            let sliced = SlicedPacket::from_ethernet(data)?;
+            let (source, destination, protocol) = match &sliced.net {
+                Some(NetSlice::Ipv4(ipv4)) => (
+                    IpAddr::V4(ipv4.header().source_addr()),
+                    IpAddr::V4(ipv4.header().destination_addr()),
+                    ipv4.header().protocol().0,
+                ),
+                Some(NetSlice::Ipv6(ipv6)) => (
+                    IpAddr::V6(ipv6.header().source_addr()),
+                    IpAddr::V6(ipv6.header().destination_addr()),
+                    ipv6.payload().ip_number.0,
+                ),
+                _ => {
+                    assert!(
+                        flows[&FlowKey::NonIp]
+                            .iter()
+                            .any(|p| p.index == index as u64)
+                    );
+                    continue;
+                }
+            };
+            let (source_port, destination_port) = match &sliced.transport {
+                Some(TransportSlice::Tcp(tcp)) => (tcp.source_port(), tcp.destination_port()),
+                Some(TransportSlice::Udp(udp)) => (udp.source_port(), udp.destination_port()),
+                _ => (0, 0),
+            };
+            let key = FlowKey::Ip {
+                source,
+                destination,
+                source_port,
+                destination_port,
+                protocol,
+            };
+            let packet = flows[&key]
+                .iter()
+                .find(|packet| packet.index == index as u64)
+                .expect("Packet is missing from its flow");
+            assert_eq!(&packet.data, data);
+        }
+        assert!(flows.keys().any(|key| matches!(key, FlowKey::Ip { .. })));
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use etherparse::{EtherType, SlicedPacket, err::packet::SliceError};
+use thiserror::Error;
+
+use super::{LinkLayerFrame, decode_link_layer};
+use crate::link_type::LinkType;
+
+/// Errors returned by [`PacketSliceExt::sliced`]
+#[derive(Debug, Error)]
+pub enum PacketSliceError {
+    /// etherparse failed to slice the packet
+    #[error(transparent)]
+    Slice(#[from] SliceError),
+    /// The frame is shorter than its link-layer header
+    #[error("Frame is shorter than its link-layer header, {required} bytes are required")]
+    Truncated { required: usize },
+    /// The link layer does not declare the type of its payload
+    #[error("The link-layer payload has no known EtherType")]
+    UnknownPayload,
+    /// The link type is not supported by [`decode_link_layer`]
+    #[error("Unsupported link type {0:?}")]
+    UnsupportedLinkType(LinkType),
+}
+
+/// Slices captured packet data with etherparse based on the capture's link type
+///
+/// Requires the `etherparse` feature.
+pub trait PacketSliceExt {
+    /// Slices the packet with the etherparse parser matching `link_type`
+    ///
+    /// [`LinkType::Ethernet`] and [`LinkType::LinuxSll`] are sliced from the link layer and
+    /// the raw IP link types from the IP header. Other link types supported by [`decode_link_layer`]
+    /// are sliced from the EtherType of their payload.
+    fn sliced(&self, link_type: LinkType) -> Result<SlicedPacket<'_>, PacketSliceError>;
+}
+impl PacketSliceExt for [u8] {
+    fn sliced(&self, link_type: LinkType) -> Result<SlicedPacket<'_>, PacketSliceError> {
+        let packet = match link_type {
+            LinkType::Ethernet => SlicedPacket::from_ethernet(self)?,
+            LinkType::LinuxSll => SlicedPacket::from_linux_sll(self)?,
+            LinkType::Raw | LinkType::Ipv4 | LinkType::Ipv6 => SlicedPacket::from_ip(self)?,
+            other => match decode_link_layer(other, self) {
+                LinkLayerFrame::Decoded {
+                    ether_type: Some(ether_type),
+                    payload,
+                    ..
+                } => SlicedPacket::from_ether_type(EtherType(ether_type), payload)?,
+                LinkLayerFrame::Decoded { .. } => return Err(PacketSliceError::UnknownPayload),
+                LinkLayerFrame::Truncated { required } => {
+                    return Err(PacketSliceError::Truncated { required });
+                }
+                LinkLayerFrame::Unsupported(other) => {
+                    return Err(PacketSliceError::UnsupportedLinkType(other));
+                }
+            },
+        };
+        Ok(packet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use etherparse::{IpHeaders, NetSlice, PacketBuilder, PacketBuilderStep, TransportSlice};
+
+    use super::*;
+
+    fn udp_payload(builder: PacketBuilderStep<IpHeaders>) -> anyhow::Result<Vec<u8>> {
+        let builder = builder.udp(1000, 53);
+        let mut packet = Vec::with_capacity(builder.size(4));
+        builder.write(&mut packet, &[1, 2, 3, 4])?;
+        Ok(packet)
+    }
+
+    fn assert_udp(packet: &SlicedPacket<'_>) {
+        assert!(matches!(packet.net, Some(NetSlice::Ipv4(_))));
+        let Some(TransportSlice::Udp(udp)) = &packet.transport else {
+            panic!("expected a UDP packet, got {:?}", packet.transport);
+        };
+        assert_eq!(udp.destination_port(), 53);
+        assert_eq!(udp.payload(), &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn slice_ethernet() -> anyhow::Result<()> {
+        let packet = udp_payload(PacketBuilder::ethernet2([1; 6], [2; 6]).ipv4(
+            [10, 0, 0, 1],
+            [10, 0, 0, 2],
+            64,
+        ))?;
+        let sliced = packet.sliced(LinkType::Ethernet)?;
+        assert!(sliced.link.is_some());
+        assert_udp(&sliced);
+        Ok(())
+    }
+
+    #[test]
+    fn slice_raw() -> anyhow::Result<()> {
+        let packet = udp_payload(PacketBuilder::ipv4([10, 0, 0, 1], [10, 0, 0, 2], 64))?;
+        let sliced = packet.sliced(LinkType::Raw)?;
+        assert!(sliced.link.is_none());
+        assert_udp(&sliced);
+
+        // Sliced as Ethernet the IP header is misread as MAC addresses
+        assert!(packet.sliced(LinkType::Ethernet)?.net.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn slice_from_decoded_link_layer() -> anyhow::Result<()> {
+        let mut packet = 2u32.to_le_bytes().to_vec();
+        packet.extend(udp_payload(PacketBuilder::ipv4(
+            [10, 0, 0, 1],
+            [10, 0, 0, 2],
+            64,
+        ))?);
+        assert_udp(&packet.sliced(LinkType::Null)?);
+
+        assert!(matches!(
+            packet[..2].sliced(LinkType::Null),
+            Err(PacketSliceError::Truncated { required: 4 })
+        ));
+        assert!(matches!(
+            packet.sliced(LinkType::Ieee802_11),
+            Err(PacketSliceError::UnsupportedLinkType(LinkType::Ieee802_11))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+//! The I/O traits used by the block parsers and writers
+//!
+//! With the `std` feature these are the `std::io` types. Without it they are minimal replacements
+//! implemented for byte slices and `Vec<u8>`, so the pcap-ng blocks can be parsed from and written to memory
+//! with only the `alloc` feature.
+#[cfg(feature = "std")]
+pub use std::io::{Error, ErrorKind, Read, Write};
+
+#[cfg(not(feature = "std"))]
+pub use no_std::{Error, ErrorKind, Read, Write};
+
+#[cfg(not(feature = "std"))]
+mod no_std {
+    use alloc::vec::Vec;
+    use core::fmt;
+
+    /// The kinds of [`Error`] returned without the `std` feature
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[non_exhaustive]
+    pub enum ErrorKind {
+        /// The reader ended before the requested bytes were read
+        UnexpectedEof,
+        /// A value could not be written
+        InvalidInput,
+    }
+    /// The error returned by [`Read`] and [`Write`] without the `std` feature
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Error {
+        kind: ErrorKind,
+        message: &'static str,
+    }
+    impl Error {
+        /// Creates an error of `kind` described by `message`
+        pub fn new(kind: ErrorKind, message: &'static str) -> Self {
+            Self { kind, message }
+        }
+        /// Returns the kind of the error
+        pub fn kind(&self) -> ErrorKind {
+            self.kind
+        }
+    }
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.message)
+        }
+    }
+    impl core::error::Error for Error {}
+
+    /// Replacement for `std::io::Read` with only [`Read::read_exact`]
+    pub trait Read {
+        /// Fills `buf` from the reader
+        ///
+        /// Returns an [`ErrorKind::UnexpectedEof`] error if the reader ends first
+        fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), Error>;
+    }
+    impl Read for &[u8] {
+        fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+            let Some((read, rest)) = self.split_at_checked(buf.len()) else {
+                *self = &self[self.len()..];
+                return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "failed to fill whole buffer",
+                ));
+            };
+            buf.copy_from_slice(read);
+            *self = rest;
+            Ok(())
+        }
+    }
+    impl<R: Read + ?Sized> Read for &mut R {
+        fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+            (**self).read_exact(buf)
+        }
+    }
+    /// Replacement for `std::io::Write` with only [`Write::write_all`]
+    pub trait Write {
+        /// Writes all of `buf`
+        fn write_all(&mut self, buf: &[u8]) -> Result<(), Error>;
+    }
+    impl Write for Vec<u8> {
+        fn write_all(&mut self, buf: &[u8]) -> Result<(), Error> {
+            self.extend_from_slice(buf);
+            Ok(())
+        }
+    }
+    impl<W: Write + ?Sized> Write for &mut W {
+        fn write_all(&mut self, buf: &[u8]) -> Result<(), Error> {
+            (**self).write_all(buf)
+        }
+    }
+}
@@ -1,21 +1,44 @@
 //! rusty-pcap is a pcap library for Rust
 //!
 //! 100% Rust implementation of a pcap reader
-use std::{cmp, fmt::Display, io::Write};
+//!
+//! ## `no_std`
+//!
+//! Without the default `std` feature the crate is `no_std`. Only the byte slice parsers for the pcap file and packet
+//! headers, [`link_type`], [`byte_order`] and the `decode` module are available. The `alloc` feature adds the
+//! pcap-ng blocks and their `parse_from_slice` functions.
+//!
+//! The `no_std` build is tested by the `no-std-test` crate in the repository.
+#![cfg_attr(not(feature = "std"), no_std)]
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use crate::io::Write;
+use core::{cmp, fmt::Display};
 
 use crate::{
-    byte_order::{ByteOrder, WriteExt},
-    pcap::file_header::MagicNumberAndEndianness,
-    pcap_ng::PCAP_NG_MAGIC,
+    byte_order::ByteOrder, pcap::file_header::MagicNumberAndEndianness, pcap_ng::PCAP_NG_MAGIC,
 };
+#[cfg(feature = "std")]
 pub mod any_reader;
+#[cfg(feature = "std")]
+pub mod any_writer;
 /// Lock-free buffer pool shared by the pooled async readers of both file formats.
 ///
 /// Requires the `tokio-async` feature.
 #[cfg(feature = "tokio-async")]
 pub mod buffer_pool;
 pub mod byte_order;
+#[cfg(feature = "std")]
+pub mod convert;
+#[cfg(feature = "decode")]
+pub mod decode;
+#[cfg(feature = "alloc")]
+pub mod io;
 pub mod link_type;
+#[cfg(feature = "std")]
+pub mod packet_source;
 pub mod pcap;
 pub mod pcap_ng;
 pub(crate) mod utils;
@@ -30,7 +53,7 @@ pub enum PcapFileType {
     PcapNg,
 }
 impl Display for PcapFileType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             PcapFileType::Pcap => write!(f, "PCAP"),
             PcapFileType::PcapNg => write!(f, "PCAP-NG"),
@@ -53,7 +76,7 @@ impl PcapFileType {
 /// Represents the version of the pcap file format
 ///
 /// Also used in pcap-ng files for the section header block
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Version {
     /// Major version
     pub major: u16,
@@ -77,6 +100,18 @@ impl Version {
     pub const PCAP_VERSION_2_4: Version = Version { major: 2, minor: 4 };
     /// The libpcap version 2.3
     pub const PCAP_VERSION_2_3: Version = Version { major: 2, minor: 3 };
+    /// The pcap-ng section header version 1.0
+    pub const PCAP_NG_VERSION_1_0: Version = Version { major: 1, minor: 0 };
+    /// Returns `true` if this crate can read the major version in a `file_type` file
+    ///
+    /// Pcap files are version 2.x, almost always 2.4. Pcap-ng sections are version 1.x, almost always 1.0.
+    /// The minor version is not checked
+    pub fn is_supported(&self, file_type: PcapFileType) -> bool {
+        match file_type {
+            PcapFileType::Pcap => self.major == 2,
+            PcapFileType::PcapNg => self.major == 1,
+        }
+    }
     /// Parses the version from the bytes
     #[inline(always)]
     pub(crate) fn parse(bytes: &[u8], byte_order: impl ByteOrder) -> Self {
@@ -84,14 +119,20 @@ impl Version {
         let minor = byte_order.u16_from_bytes([bytes[2], bytes[3]]);
         Self { major, minor }
     }
+    /// Serializes the version in `byte_order`
+    #[inline(always)]
+    pub(crate) fn to_bytes(self, byte_order: impl ByteOrder) -> [u8; 4] {
+        let [major_0, major_1] = byte_order.u16_to_bytes(self.major);
+        let [minor_0, minor_1] = byte_order.u16_to_bytes(self.minor);
+        [major_0, major_1, minor_0, minor_1]
+    }
+    #[cfg(feature = "alloc")]
     pub(crate) fn write<W: Write>(
         &self,
         target: &mut W,
         byte_order: impl ByteOrder,
-    ) -> Result<(), std::io::Error> {
-        target.write_u16(self.major, byte_order)?;
-        target.write_u16(self.minor, byte_order)?;
-        Ok(())
+    ) -> Result<(), io::Error> {
+        target.write_all(&self.to_bytes(byte_order))
     }
 }
 
@@ -165,4 +206,16 @@ pub(crate) mod test_helpers {
         assert!(v2_3 > v2_1);
         assert!(v2_4 > v2_1);
     }
+
+    #[test]
+    fn test_version_is_supported() {
+        use crate::PcapFileType;
+
+        assert!(Version::PCAP_VERSION_2_4.is_supported(PcapFileType::Pcap));
+        assert!(Version::PCAP_VERSION_2_3.is_supported(PcapFileType::Pcap));
+        assert!(!Version { major: 3, minor: 0 }.is_supported(PcapFileType::Pcap));
+        assert!(!Version::PCAP_VERSION_2_4.is_supported(PcapFileType::PcapNg));
+        assert!(Version::PCAP_NG_VERSION_1_0.is_supported(PcapFileType::PcapNg));
+        assert!(!Version { major: 2, minor: 0 }.is_supported(PcapFileType::PcapNg));
+    }
 }
@@ -14,7 +14,7 @@ macro_rules! link_type {
         ),*
     ) => {
         /// Represents the link type for pcap and pcap-ng files
-        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
         #[repr(u16)]
         pub enum LinkType {
             $(
@@ -177,9 +177,119 @@ link_type! {
     Usb2_0 = 288,
     AtscAlp = 289
 }
+impl LinkType {
+    /// Size of the fixed pseudo-header that precedes the link-layer payload
+    ///
+    /// Returns `Some(16)` for [`LinkType::LinuxSll`] and `Some(20)` for [`LinkType::LinuxSll2`].
+    /// Link types without a pseudo-header, such as [`LinkType::Ethernet`] and [`LinkType::Raw`], return `None`,
+    /// as do link types with variable length headers such as radiotap.
+    pub fn payload_offset(&self) -> Option<usize> {
+        match self {
+            LinkType::LinuxSll => Some(16),
+            LinkType::LinuxSll2 => Some(20),
+            _ => None,
+        }
+    }
+    /// Returns true if frames of this link type start with Ethernet destination and source addresses
+    ///
+    /// This is [`LinkType::Ethernet`] and the DSA tagged variants that insert a switch tag after the
+    /// addresses ([`LinkType::DsaTagBrcm`], [`LinkType::DsaTagDsa`] and [`LinkType::DsaTagEdsa`]).
+    pub fn is_ethernet_like(&self) -> bool {
+        matches!(
+            self,
+            LinkType::Ethernet | LinkType::DsaTagBrcm | LinkType::DsaTagDsa | LinkType::DsaTagEdsa
+        )
+    }
+    /// Returns true if frames are raw IP packets with no link-layer header
+    ///
+    /// This is [`LinkType::Raw`], [`LinkType::Ipv4`] and [`LinkType::Ipv6`]
+    pub fn is_ip_raw(&self) -> bool {
+        matches!(self, LinkType::Raw | LinkType::Ipv4 | LinkType::Ipv6)
+    }
+    /// Returns true if frames include a trailing frame check sequence
+    ///
+    /// Only [`LinkType::Ieee802_15_4Withfcs`] and [`LinkType::Ieee802_15_4NonaskPhy`] always carry one.
+    pub fn has_fcs(&self) -> bool {
+        matches!(
+            self,
+            LinkType::Ieee802_15_4Withfcs | LinkType::Ieee802_15_4NonaskPhy
+        )
+    }
+}
 #[allow(clippy::derivable_impls)]
 impl Default for LinkType {
     fn default() -> Self {
         LinkType::Null
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_offset() {
+        assert_eq!(LinkType::LinuxSll.payload_offset(), Some(16));
+        assert_eq!(LinkType::LinuxSll2.payload_offset(), Some(20));
+        assert_eq!(LinkType::Ethernet.payload_offset(), None);
+        assert_eq!(LinkType::Raw.payload_offset(), None);
+        assert_eq!(LinkType::Ieee802_11Radiotap.payload_offset(), None);
+    }
+
+    #[test]
+    fn classification() {
+        assert!(LinkType::Ethernet.is_ethernet_like());
+        assert!(LinkType::DsaTagBrcm.is_ethernet_like());
+        assert!(!LinkType::DsaTagBrcmPrepend.is_ethernet_like());
+        assert!(!LinkType::LinuxSll.is_ethernet_like());
+        assert!(!LinkType::Raw.is_ethernet_like());
+
+        assert!(LinkType::Raw.is_ip_raw());
+        assert!(LinkType::Ipv4.is_ip_raw());
+        assert!(LinkType::Ipv6.is_ip_raw());
+        assert!(!LinkType::Ethernet.is_ip_raw());
+        assert!(!LinkType::Ipnet.is_ip_raw());
+
+        assert!(LinkType::Ieee802_15_4Withfcs.has_fcs());
+        assert!(LinkType::Ieee802_15_4NonaskPhy.has_fcs());
+        assert!(!LinkType::Ieee802_15_4Nofcs.has_fcs());
+        assert!(!LinkType::Ethernet.has_fcs());
+    }
+
+    #[test]
+    fn key_types_are_hashable() {
+        use std::collections::HashSet;
+
+        use crate::{
+            Version,
+            byte_order::Endianness,
+            pcap::{file_header::MagicNumber, packet_header::PacketTimestamp},
+        };
+
+        let link_types: HashSet<_> = [LinkType::Ethernet, LinkType::Raw, LinkType::Ethernet].into();
+        assert_eq!(link_types.len(), 2);
+        let versions: HashSet<_> = [Version::PCAP_VERSION_2_4, Version::PCAP_VERSION_2_3].into();
+        assert_eq!(versions.len(), 2);
+        let endianness: HashSet<_> = [Endianness::LittleEndian, Endianness::LittleEndian].into();
+        assert_eq!(endianness.len(), 1);
+        let magic_numbers: HashSet<_> = [
+            MagicNumber::Microsecond,
+            MagicNumber::Nanosecond,
+            MagicNumber::Modified,
+        ]
+        .into();
+        assert_eq!(magic_numbers.len(), 3);
+        let timestamps: HashSet<_> = [
+            PacketTimestamp {
+                seconds: 1,
+                usec: 2,
+            },
+            PacketTimestamp {
+                seconds: 1,
+                usec: 2,
+            },
+        ]
+        .into();
+        assert_eq!(timestamps.len(), 1);
+    }
+}
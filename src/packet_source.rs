@@ -0,0 +1,100 @@
+//! A common trait for the synchronous packet readers
+use std::{borrow::Cow, io::Read};
+
+use crate::{
+    any_reader::{AnyPacketHeader, AnyPcapReaderError, SyncAnyPcapReader},
+    pcap::{PcapParseError, SyncPcapReader},
+    pcap_ng::{PcapNgParseError, SyncPcapNgReader},
+};
+
+/// A packet returned by [`PacketSource::next_packet`]
+pub type SourcePacket<'a> = (AnyPacketHeader, Cow<'a, [u8]>);
+
+/// Anything packets can be read from one at a time
+///
+/// Implemented by [`SyncPcapReader`], [`SyncPcapNgReader`] and [`SyncAnyPcapReader`]
+/// so helpers can accept `&mut impl PacketSource` instead of a specific reader.
+pub trait PacketSource {
+    /// The error returned when reading a packet fails
+    type Error: std::error::Error;
+    /// Reads the next packet
+    ///
+    /// Returns `Ok(None)` when there are no more packets
+    fn next_packet(&mut self) -> Result<Option<SourcePacket<'_>>, Self::Error>;
+}
+impl<R: Read> PacketSource for SyncPcapReader<R> {
+    type Error = PcapParseError;
+
+    fn next_packet(&mut self) -> Result<Option<SourcePacket<'_>>, Self::Error> {
+        Ok(SyncPcapReader::next_packet(self)?
+            .map(|(header, data)| (AnyPacketHeader::Pcap(header), Cow::Borrowed(data))))
+    }
+}
+impl<R: Read> PacketSource for SyncPcapNgReader<R> {
+    type Error = PcapNgParseError;
+
+    fn next_packet(&mut self) -> Result<Option<SourcePacket<'_>>, Self::Error> {
+        Ok(
+            SyncPcapNgReader::next_packet(self)?
+                .map(|(header, data)| (header, Cow::Borrowed(data))),
+        )
+    }
+}
+impl<R: Read> PacketSource for SyncAnyPcapReader<R> {
+    type Error = AnyPcapReaderError;
+
+    fn next_packet(&mut self) -> Result<Option<SourcePacket<'_>>, Self::Error> {
+        Ok(SyncAnyPcapReader::next_packet(self)?
+            .map(|(header, data)| (header, Cow::Borrowed(data))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count_packets<S: PacketSource>(source: &mut S) -> Result<usize, S::Error> {
+        let mut count = 0;
+        while source.next_packet()?.is_some() {
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    #[test]
+    fn count_packets_with_every_reader() -> anyhow::Result<()> {
+        let pcap = std::fs::read("test_data/test.pcap")?;
+        let pcap_ng = std::fs::read("test_data/ng/test001_le.pcapng")?;
+
+        let mut expected_pcap = 0;
+        let mut reader = SyncPcapReader::new(pcap.as_slice())?;
+        while reader.next_packet()?.is_some() {
+            expected_pcap += 1;
+        }
+        let mut expected_pcap_ng = 0;
+        let mut reader = SyncPcapNgReader::new(pcap_ng.as_slice())?;
+        while reader.next_packet()?.is_some() {
+            expected_pcap_ng += 1;
+        }
+        assert!(expected_pcap > 0);
+        assert!(expected_pcap_ng > 0);
+
+        assert_eq!(
+            count_packets(&mut SyncPcapReader::new(pcap.as_slice())?)?,
+            expected_pcap
+        );
+        assert_eq!(
+            count_packets(&mut SyncPcapNgReader::new(pcap_ng.as_slice())?)?,
+            expected_pcap_ng
+        );
+        assert_eq!(
+            count_packets(&mut SyncAnyPcapReader::new(pcap.as_slice())?)?,
+            expected_pcap
+        );
+        assert_eq!(
+            count_packets(&mut SyncAnyPcapReader::new(pcap_ng.as_slice())?)?,
+            expected_pcap_ng
+        );
+        Ok(())
+    }
+}
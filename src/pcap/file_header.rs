@@ -1,21 +1,38 @@
 //! PCAP file header representation and parsing
-use std::io::{Cursor, Read, Write};
+#[cfg(feature = "std")]
+use std::io::{Read, Write};
 
 use crate::{
     Version,
-    byte_order::{Endianness, ExtendedByteOrder, WriteExt},
+    byte_order::{ByteOrder, Endianness, ExtendedByteOrder, UnexpectedSize},
     link_type::LinkType,
     pcap::PcapParseError,
 };
 
 /// The magic number used to identify pcap files and their endianness
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum MagicNumber {
     /// Microsecond Resolution
     #[default]
     Microsecond,
     /// Nanosecond Resolution
     Nanosecond,
+    /// The obsolete "modified" libpcap format (Alexey Kuznetsov's patches)
+    ///
+    /// Microsecond resolution, but each packet header carries an extra 8 bytes.
+    /// See [`ExtendedPacketHeader`](crate::pcap::packet_header::ExtendedPacketHeader)
+    Modified,
+}
+impl MagicNumber {
+    /// Returns the size of each packet header in bytes for files using this magic number
+    ///
+    /// 16 bytes, or 24 bytes for [`MagicNumber::Modified`]
+    pub fn packet_header_length(&self) -> usize {
+        match self {
+            MagicNumber::Microsecond | MagicNumber::Nanosecond => 16,
+            MagicNumber::Modified => 24,
+        }
+    }
 }
 
 /// Represents the magic number and endianness of a pcap file
@@ -48,6 +65,14 @@ impl TryFrom<[u8; 4]> for MagicNumberAndEndianness {
                 magic_number: MagicNumber::Nanosecond,
                 endianness: Endianness::LittleEndian,
             }),
+            [0xa1, 0xb2, 0xcd, 0x34] => Ok(Self {
+                magic_number: MagicNumber::Modified,
+                endianness: Endianness::BigEndian,
+            }),
+            [0x34, 0xcd, 0xb2, 0xa1] => Ok(Self {
+                magic_number: MagicNumber::Modified,
+                endianness: Endianness::LittleEndian,
+            }),
             _ => Err(PcapParseError::InvalidMagicNumber(Some(value))),
         }
     }
@@ -57,8 +82,10 @@ impl From<MagicNumberAndEndianness> for [u8; 4] {
         match (value.magic_number, value.endianness) {
             (MagicNumber::Microsecond, Endianness::LittleEndian) => [0xd4, 0xc3, 0xb2, 0xa1],
             (MagicNumber::Microsecond, Endianness::BigEndian) => [0xa1, 0xb2, 0xc3, 0xd4],
-            (MagicNumber::Nanosecond, Endianness::LittleEndian) => [0xA1, 0xB2, 0x3C, 0x4D],
-            (MagicNumber::Nanosecond, Endianness::BigEndian) => [0x4d, 0x3c, 0xb2, 0xa1],
+            (MagicNumber::Nanosecond, Endianness::LittleEndian) => [0x4d, 0x3c, 0xb2, 0xa1],
+            (MagicNumber::Nanosecond, Endianness::BigEndian) => [0xA1, 0xB2, 0x3C, 0x4D],
+            (MagicNumber::Modified, Endianness::LittleEndian) => [0x34, 0xcd, 0xb2, 0xa1],
+            (MagicNumber::Modified, Endianness::BigEndian) => [0xa1, 0xb2, 0xcd, 0x34],
         }
     }
 }
@@ -81,11 +108,16 @@ pub struct PcapFileHeader {
     /// The version of the pcap file format
     /// Bytes 4..8
     pub version: Version,
-    /// The timezone offset
+    /// The timezone offset (`thiszone` in libpcap)
     /// Bytes 8..12
+    ///
+    /// Zero in headers from [`PcapFileHeader::default`] and the builder, which is what most tools write.
+    /// Writers emit the value as is, so a header read from a legacy file with a nonzero offset is preserved.
     pub timezone: u32,
-    /// The number of significant figures
+    /// The number of significant figures (`sigfigs` in libpcap)
     /// Bytes 12..16
+    ///
+    /// Zero unless explicitly set, like [`Self::timezone`]
     pub sig_figs: u32,
     /// The maximum byte length of captured packets
     ///
@@ -95,32 +127,119 @@ pub struct PcapFileHeader {
     /// Bytes 20..24
     pub link_type: LinkType,
 }
+/// Default snap length used by [`PcapFileHeader::default`]
+///
+/// Matches the default used by tcpdump/libpcap for most link types
+pub const DEFAULT_SNAP_LENGTH: u32 = 65535;
+/// A little-endian, microsecond resolution, version 2.4 Ethernet header
+/// with a snap length of [`DEFAULT_SNAP_LENGTH`]
 impl Default for PcapFileHeader {
     fn default() -> Self {
         Self {
-            magic_number_and_endianness: MagicNumberAndEndianness::default(),
+            magic_number_and_endianness: MagicNumberAndEndianness {
+                magic_number: MagicNumber::Microsecond,
+                endianness: Endianness::LittleEndian,
+            },
             version: Version::PCAP_VERSION_2_4,
-            timezone: Default::default(),
-            sig_figs: Default::default(),
-            snap_length: Default::default(),
-            link_type: Default::default(),
+            timezone: 0,
+            sig_figs: 0,
+            snap_length: DEFAULT_SNAP_LENGTH,
+            link_type: LinkType::Ethernet,
         }
     }
 }
 impl PcapFileHeader {
+    /// Size of the file header in bytes
+    pub const SIZE: usize = 24;
+    /// Returns a [`PcapFileHeaderBuilder`] starting from [`PcapFileHeader::default`]
+    pub fn builder() -> PcapFileHeaderBuilder {
+        PcapFileHeaderBuilder::default()
+    }
     /// Reads the file header from the reader
+    #[cfg(feature = "std")]
     pub fn read<R: Read>(reader: &mut R) -> Result<Self, PcapParseError> {
         let mut header = [0u8; 24];
         reader.read_exact(&mut header)?;
         Self::try_from(&header)
     }
+    /// Returns [`PcapParseError::InvalidSnapLength`] if the snap length is 0
+    ///
+    /// Called by the reader constructors. Every packet of a zero snap length file would fail with
+    /// [`PcapParseError::InvalidPacketLength`]
+    #[cfg(feature = "std")]
+    pub(crate) fn validate_snap_length(&self) -> Result<(), PcapParseError> {
+        if self.snap_length == 0 {
+            return Err(PcapParseError::InvalidSnapLength);
+        }
+        Ok(())
+    }
+    /// The timestamp resolution of the file
+    pub fn resolution(&self) -> MagicNumber {
+        self.magic_number_and_endianness.magic_number
+    }
+    /// The byte order of the file
+    pub fn endianness(&self) -> Endianness {
+        self.magic_number_and_endianness.endianness
+    }
+    /// Returns the serialized 24 byte file header
+    pub fn to_bytes(&self) -> [u8; 24] {
+        self.into()
+    }
     /// Writes the file header to the writer
+    #[cfg(feature = "std")]
     pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), std::io::Error> {
-        let as_bytes: [u8; 24] = self.into();
-        writer.write_all(&as_bytes)?;
+        writer.write_all(&self.to_bytes())?;
         Ok(())
     }
 }
+/// Fluent builder for [`PcapFileHeader`]
+///
+/// Any field that is not set falls back to the value from [`PcapFileHeader::default`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PcapFileHeaderBuilder {
+    header: PcapFileHeader,
+}
+impl PcapFileHeaderBuilder {
+    /// Sets the link type of the captured packets
+    pub fn link_type(mut self, link_type: LinkType) -> Self {
+        self.header.link_type = link_type;
+        self
+    }
+    /// Sets the maximum byte length of captured packets
+    pub fn snap_length(mut self, snap_length: u32) -> Self {
+        self.header.snap_length = snap_length;
+        self
+    }
+    /// Sets the byte order the file will be written in
+    pub fn endianness(mut self, endianness: Endianness) -> Self {
+        self.header.magic_number_and_endianness.endianness = endianness;
+        self
+    }
+    /// Sets the timestamp resolution
+    pub fn resolution(mut self, resolution: MagicNumber) -> Self {
+        self.header.magic_number_and_endianness.magic_number = resolution;
+        self
+    }
+    /// Sets the version of the pcap file format
+    pub fn version(mut self, version: Version) -> Self {
+        self.header.version = version;
+        self
+    }
+    /// Sets the timezone offset (`thiszone`). Defaults to 0
+    pub fn timezone(mut self, timezone: u32) -> Self {
+        self.header.timezone = timezone;
+        self
+    }
+    /// Sets the number of significant figures (`sigfigs`). Defaults to 0
+    pub fn sig_figs(mut self, sig_figs: u32) -> Self {
+        self.header.sig_figs = sig_figs;
+        self
+    }
+    /// Builds the [`PcapFileHeader`]
+    pub fn build(self) -> PcapFileHeader {
+        self.header
+    }
+}
 impl TryFrom<&[u8; 24]> for PcapFileHeader {
     type Error = PcapParseError;
 
@@ -152,19 +271,35 @@ impl TryFrom<&[u8; 24]> for PcapFileHeader {
         })
     }
 }
+/// Parses the first 24 bytes of `bytes`. Any bytes after the header are ignored
+///
+/// Returns [`PcapParseError::UnexpectedSize`] if `bytes` is shorter than a file header
+impl TryFrom<&[u8]> for PcapFileHeader {
+    type Error = PcapParseError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let header: &[u8; 24] = bytes
+            .get(..Self::SIZE)
+            .and_then(|header| header.try_into().ok())
+            .ok_or(UnexpectedSize {
+                name: "PcapFileHeader",
+                expected: Self::SIZE,
+                got: bytes.len(),
+            })?;
+        Self::try_from(header)
+    }
+}
 impl<'a> From<&'a PcapFileHeader> for [u8; 24] {
     fn from(value: &'a PcapFileHeader) -> Self {
-        // It is impossible for these write calls to error out.
-        let mut header = Cursor::new([0u8; 24]);
-        let magic_number: [u8; 4] = value.magic_number_and_endianness.into();
-        let _ = header.write_all(&magic_number);
-        let endianness = value.magic_number_and_endianness.endianness;
-        let _ = value.version.write(&mut header, endianness);
-        let _ = header.write_u32(value.timezone, endianness);
-        let _ = header.write_u32(value.sig_figs, endianness);
-        let _ = header.write_u32(value.snap_length, endianness);
-        let _ = header.write_u32((value.link_type as u16).into(), endianness);
-        header.into_inner()
+        let endianness = value.endianness();
+        let mut header = [0u8; 24];
+        header[0..4].copy_from_slice(&<[u8; 4]>::from(value.magic_number_and_endianness));
+        header[4..8].copy_from_slice(&value.version.to_bytes(endianness));
+        header[8..12].copy_from_slice(&endianness.u32_to_bytes(value.timezone));
+        header[12..16].copy_from_slice(&endianness.u32_to_bytes(value.sig_figs));
+        header[16..20].copy_from_slice(&endianness.u32_to_bytes(value.snap_length));
+        header[20..24].copy_from_slice(&endianness.u32_to_bytes((value.link_type as u16).into()));
+        header
     }
 }
 
@@ -178,6 +313,49 @@ impl From<PcapFileHeader> for [u8; 24] {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_magic_number_bytes_round_trip() -> anyhow::Result<()> {
+        for magic_number in [
+            MagicNumber::Microsecond,
+            MagicNumber::Nanosecond,
+            MagicNumber::Modified,
+        ] {
+            for endianness in [Endianness::LittleEndian, Endianness::BigEndian] {
+                let value = MagicNumberAndEndianness {
+                    magic_number,
+                    endianness,
+                };
+                let bytes: [u8; 4] = value.into();
+                assert_eq!(MagicNumberAndEndianness::try_from(bytes)?, value);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_bytes_round_trip() -> anyhow::Result<()> {
+        for endianness in [Endianness::LittleEndian, Endianness::BigEndian] {
+            let header = PcapFileHeader::builder()
+                .endianness(endianness)
+                .resolution(MagicNumber::Nanosecond)
+                .snap_length(1500)
+                .link_type(LinkType::Raw)
+                .build();
+            let bytes = header.to_bytes();
+            assert_eq!(PcapFileHeader::try_from(&bytes)?, header);
+
+            let mut written = Vec::new();
+            header.write(&mut written)?;
+            assert_eq!(written, bytes);
+        }
+        let big_endian = PcapFileHeader::builder()
+            .endianness(Endianness::BigEndian)
+            .resolution(MagicNumber::Nanosecond)
+            .build();
+        assert_eq!(big_endian.to_bytes()[..4], [0xa1, 0xb2, 0x3c, 0x4d]);
+        Ok(())
+    }
+
     #[test]
     fn test_magic_number_and_endianness() {
         let magic_bytes = [0xa1, 0xb2, 0xc3, 0xd4];
@@ -186,6 +364,20 @@ mod tests {
         assert_eq!(magic.endianness, Endianness::BigEndian);
     }
 
+    #[test]
+    fn test_modified_magic_number() {
+        let magic = MagicNumberAndEndianness::try_from([0x34, 0xcd, 0xb2, 0xa1]).unwrap();
+        assert_eq!(magic.magic_number, MagicNumber::Modified);
+        assert_eq!(magic.endianness, Endianness::LittleEndian);
+        let as_bytes: [u8; 4] = magic.into();
+        assert_eq!(as_bytes, [0x34, 0xcd, 0xb2, 0xa1]);
+
+        let magic = MagicNumberAndEndianness::try_from([0xa1, 0xb2, 0xcd, 0x34]).unwrap();
+        assert_eq!(magic.magic_number, MagicNumber::Modified);
+        assert_eq!(magic.endianness, Endianness::BigEndian);
+        assert_eq!(magic.magic_number.packet_header_length(), 24);
+    }
+
     #[test]
     fn test_pcap_file_header_read() {
         let file = std::fs::File::open("test_data/test.pcap").expect("Failed to open test.pcap");
@@ -223,4 +415,148 @@ mod tests {
 
         assert_eq!(header, from_bytes)
     }
+
+    #[test]
+    fn test_header_write_read_round_trip() -> anyhow::Result<()> {
+        let header = PcapFileHeader {
+            magic_number_and_endianness: MagicNumberAndEndianness {
+                magic_number: MagicNumber::Microsecond,
+                endianness: Endianness::LittleEndian,
+            },
+            snap_length: 65535,
+            link_type: LinkType::Ethernet,
+            ..Default::default()
+        };
+        let mut buffer = Vec::new();
+        header.write(&mut buffer)?;
+        assert_eq!(buffer.len(), 24);
+
+        let read_back = PcapFileHeader::read(&mut buffer.as_slice())?;
+        assert_eq!(header, read_back);
+        Ok(())
+    }
+
+    #[test]
+    fn test_header_write_le_ethernet_bytes() -> anyhow::Result<()> {
+        let header = PcapFileHeader {
+            magic_number_and_endianness: MagicNumberAndEndianness {
+                magic_number: MagicNumber::Microsecond,
+                endianness: Endianness::LittleEndian,
+            },
+            version: Version::PCAP_VERSION_2_4,
+            timezone: 0,
+            sig_figs: 0,
+            snap_length: 65535,
+            link_type: LinkType::Ethernet,
+        };
+        let mut buffer = Vec::new();
+        header.write(&mut buffer)?;
+        let expected: [u8; 24] = [
+            0xd4, 0xc3, 0xb2, 0xa1, // magic
+            0x02, 0x00, 0x04, 0x00, // version 2.4
+            0x00, 0x00, 0x00, 0x00, // timezone
+            0x00, 0x00, 0x00, 0x00, // sig_figs
+            0xff, 0xff, 0x00, 0x00, // snap_length
+            0x01, 0x00, 0x00, 0x00, // link_type
+        ];
+        assert_eq!(buffer, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_header_default() {
+        let header = PcapFileHeader::default();
+        assert_eq!(header.snap_length, DEFAULT_SNAP_LENGTH);
+        assert_eq!(header.version, Version::PCAP_VERSION_2_4);
+        assert_eq!(header.link_type, LinkType::Ethernet);
+        assert_eq!(
+            header.magic_number_and_endianness.endianness,
+            Endianness::LittleEndian
+        );
+        assert_eq!(
+            header.magic_number_and_endianness.magic_number,
+            MagicNumber::Microsecond
+        );
+        assert_eq!(header.timezone, 0);
+        assert_eq!(header.sig_figs, 0);
+    }
+
+    #[test]
+    fn test_builder_defaults() {
+        let header = PcapFileHeader::builder()
+            .link_type(LinkType::LinuxSll)
+            .build();
+        assert_eq!(header.link_type, LinkType::LinuxSll);
+        assert_eq!(header.snap_length, DEFAULT_SNAP_LENGTH);
+        assert_eq!(header.version, Version::PCAP_VERSION_2_4);
+        assert_eq!(
+            header.magic_number_and_endianness,
+            MagicNumberAndEndianness {
+                magic_number: MagicNumber::Microsecond,
+                endianness: Endianness::LittleEndian,
+            }
+        );
+        assert_eq!(header.timezone, 0);
+        assert_eq!(header.sig_figs, 0);
+    }
+
+    #[test]
+    fn test_builder_all_fields() {
+        let header = PcapFileHeader::builder()
+            .link_type(LinkType::Raw)
+            .snap_length(1500)
+            .endianness(Endianness::BigEndian)
+            .resolution(MagicNumber::Nanosecond)
+            .version(Version::PCAP_VERSION_2_3)
+            .timezone(3600)
+            .sig_figs(6)
+            .build();
+        assert_eq!(header.link_type, LinkType::Raw);
+        assert_eq!(header.snap_length, 1500);
+        assert_eq!(
+            header.magic_number_and_endianness.endianness,
+            Endianness::BigEndian
+        );
+        assert_eq!(
+            header.magic_number_and_endianness.magic_number,
+            MagicNumber::Nanosecond
+        );
+        assert_eq!(header.version, Version::PCAP_VERSION_2_3);
+        assert_eq!(header.timezone, 3600);
+        assert_eq!(header.sig_figs, 6);
+    }
+
+    #[test]
+    fn try_from_slice() -> anyhow::Result<()> {
+        let header = PcapFileHeader::default();
+        let mut bytes = header.to_bytes().to_vec();
+        bytes.extend_from_slice(&[0xFF; 6]);
+        assert_eq!(bytes.len(), 30);
+        assert_eq!(PcapFileHeader::try_from(bytes.as_slice())?, header);
+
+        let err = PcapFileHeader::try_from(&bytes[..10]).expect_err("slice is too short");
+        let PcapParseError::UnexpectedSize(size) = err else {
+            panic!("unexpected error {err:?}");
+        };
+        assert_eq!(size.expected, 24);
+        assert_eq!(size.got, 10);
+        Ok(())
+    }
+
+    #[test]
+    fn resolution_and_endianness_accessors() -> anyhow::Result<()> {
+        let bytes = PcapFileHeader::builder()
+            .endianness(Endianness::BigEndian)
+            .resolution(MagicNumber::Nanosecond)
+            .build()
+            .to_bytes();
+        let header = PcapFileHeader::try_from(bytes.as_slice())?;
+        assert_eq!(header.resolution(), MagicNumber::Nanosecond);
+        assert_eq!(header.endianness(), Endianness::BigEndian);
+
+        let header = PcapFileHeader::default();
+        assert_eq!(header.resolution(), MagicNumber::Microsecond);
+        assert_eq!(header.endianness(), Endianness::LittleEndian);
+        Ok(())
+    }
 }
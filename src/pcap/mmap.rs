@@ -0,0 +1,159 @@
+//! Zero-copy pcap reader backed by a memory mapped file
+//!
+//! Requires the `memmap` feature
+#![allow(unsafe_code)]
+use std::{fs::File, path::Path};
+
+use memmap2::Mmap;
+
+use crate::{
+    Version,
+    pcap::{PcapParseError, file_header::PcapFileHeader, packet_header::PacketHeader},
+};
+
+/// A pcap reader that memory maps the file and returns packet data as slices
+/// into the mapped region
+///
+/// Unlike [`SyncPcapReader`](crate::pcap::SyncPcapReader) no packet data is copied
+/// into an intermediate buffer.
+///
+/// ## Safety
+///
+/// Memory mapping a file is only sound as long as the file is not modified or
+/// truncated by another process while it is mapped. See [`Mmap`] for more information.
+#[derive(Debug)]
+pub struct MmapPcapReader {
+    mmap: Mmap,
+    file_header: PcapFileHeader,
+    /// Offset of the next packet header in the map
+    position: usize,
+}
+impl MmapPcapReader {
+    /// Opens and memory maps the file at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, PcapParseError> {
+        let file = File::open(path)?;
+        Self::from_file(&file)
+    }
+    /// Memory maps an already opened file
+    pub fn from_file(file: &File) -> Result<Self, PcapParseError> {
+        // SAFETY: The caller is responsible for not modifying the file while it is mapped.
+        // This is documented on the type.
+        let mmap = unsafe { Mmap::map(file)? };
+        Self::from_mmap(mmap)
+    }
+    /// Creates a reader from an existing memory map
+    pub fn from_mmap(mmap: Mmap) -> Result<Self, PcapParseError> {
+        let header_bytes: &[u8; 24] = mmap
+            .get(..24)
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "File is too small for a pcap file header",
+                )
+            })?
+            .try_into()?;
+        let file_header = PcapFileHeader::try_from(header_bytes)?;
+        file_header.validate_snap_length()?;
+        Ok(Self {
+            mmap,
+            file_header,
+            position: 24,
+        })
+    }
+    /// Returns the file header of the pcap file
+    pub fn file_header(&self) -> &PcapFileHeader {
+        &self.file_header
+    }
+    /// Returns the version of the pcap file
+    pub fn version(&self) -> &Version {
+        &self.file_header.version
+    }
+    /// Reads the next packet from the mapped file
+    ///
+    /// Returns `Ok(None)` when end-of-file is reached. The returned slice borrows
+    /// directly from the memory map.
+    pub fn next_packet(&mut self) -> Result<Option<(PacketHeader, &[u8])>, PcapParseError> {
+        let endianness = self.file_header.endianness();
+        let magic_number = self.file_header.resolution();
+        let header_length = magic_number.packet_header_length();
+        let Some(header_bytes) = self
+            .mmap
+            .get(self.position..self.position.saturating_add(header_length))
+        else {
+            return Ok(None); // No more packets
+        };
+        let packet_header = PacketHeader::parse_bytes_with_magic(
+            header_bytes,
+            magic_number,
+            endianness,
+            &self.file_header.version,
+        )?;
+        if packet_header.include_len > self.file_header.snap_length {
+            return Err(PcapParseError::InvalidPacketLength {
+                snap_length: self.file_header.snap_length,
+                incl_len: packet_header.include_len,
+            });
+        }
+        let data_start = self.position + header_length;
+        let data_end = data_start.saturating_add(packet_header.include_len as usize);
+        let Some(data) = self.mmap.get(data_start..data_end) else {
+            return Err(PcapParseError::IO(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "Packet data extends past the end of the file",
+            )));
+        };
+        self.position = data_end;
+        Ok(Some((packet_header, data)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+    use crate::pcap::SyncPcapReader;
+
+    #[test]
+    fn matches_sync_reader() -> anyhow::Result<()> {
+        let mut mmap_reader = MmapPcapReader::open("test_data/test.pcap")?;
+        let mut sync_reader = SyncPcapReader::new(File::open("test_data/test.pcap")?)?;
+        assert_eq!(mmap_reader.file_header(), sync_reader.file_header());
+        let mut count = 0;
+        loop {
+            match (mmap_reader.next_packet()?, sync_reader.next_packet()?) {
+                (Some((mmap_header, mmap_data)), Some((sync_header, sync_data))) => {
+                    assert_eq!(mmap_header, sync_header);
+                    assert_eq!(mmap_data, sync_data);
+                    count += 1;
+                }
+                (None, None) => break,
+                _ => panic!("packet count mismatch between mmap and sync reader"),
+            }
+        }
+        assert!(count > 0);
+        Ok(())
+    }
+
+    #[test]
+    fn include_len_past_end_of_map() -> anyhow::Result<()> {
+        let (path, _) = crate::test_helpers::test_files("mmap_truncated.pcap")?;
+        {
+            let mut file = File::create(&path)?;
+            let header = PcapFileHeader::default();
+            header.write(&mut file)?;
+            // Claims 1000 bytes but only 4 follow
+            file.write_all(&[0, 0, 0, 0, 0, 0, 0, 0, 0xe8, 0x03, 0, 0, 0xe8, 0x03, 0, 0])?;
+            file.write_all(&[1, 2, 3, 4])?;
+        }
+        let mut reader = MmapPcapReader::open(&path)?;
+        let err = reader
+            .next_packet()
+            .expect_err("packet should not fit in the map");
+        assert!(
+            matches!(&err, PcapParseError::IO(io) if io.kind() == std::io::ErrorKind::UnexpectedEof),
+            "unexpected error {err:?}"
+        );
+        Ok(())
+    }
+}
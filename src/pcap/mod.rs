@@ -3,29 +3,40 @@
 //! Sources
 //! - [Wireshark Wiki - File Format](https://wiki.wireshark.org/Development/LibpcapFileFormat)
 pub mod file_header;
+#[cfg(feature = "memmap")]
+pub mod mmap;
 pub mod packet_header;
+#[cfg(feature = "std")]
 mod sync;
+#[cfg(feature = "memmap")]
+pub use mmap::MmapPcapReader;
+#[cfg(feature = "std")]
 pub use sync::*;
 #[cfg(feature = "tokio-async")]
 mod tokio_impl;
 use thiserror::Error;
 #[cfg(feature = "tokio-async")]
-pub use tokio_impl::AsyncPcapReader;
+pub use tokio_impl::AsyncPcapWriter;
 #[cfg(feature = "tokio-async")]
 pub use tokio_impl::AsyncPooledPcapReader;
+#[cfg(feature = "tokio-async")]
+pub use tokio_impl::{AsyncPcapReader, SpawnedPacket};
 // Re-exported for backward compatibility; the buffer pool now lives in the
 // shared top-level [`crate::buffer_pool`] module (used by pcap and pcap-ng).
 #[cfg(feature = "tokio-async")]
 pub use crate::buffer_pool::{BufferPool, PooledPacket};
 
-use crate::{byte_order::UnexpectedSize, link_type::InvalidLinkType};
+#[cfg(feature = "std")]
+use crate::pcap::monotonic::TimestampRegression;
+use crate::{byte_order::UnexpectedSize, link_type::InvalidLinkType, utils::HexBytes};
 
 /// Errors that can occur when parsing or writing pcap files
 #[derive(Debug, Error)]
 pub enum PcapParseError {
+    #[cfg(feature = "std")]
     #[error(transparent)]
     IO(#[from] std::io::Error),
-    #[error("Invalid magic number got {0:?}")]
+    #[error("Invalid magic number got {}", HexBytes(.0.as_ref().map_or(&[], |magic| magic)))]
     InvalidMagicNumber(Option<[u8; 4]>),
     #[error(transparent)]
     InvalidLinkType(#[from] InvalidLinkType),
@@ -35,9 +46,20 @@ pub enum PcapParseError {
     InvalidPacketLength { snap_length: u32, incl_len: u32 },
     #[error("Invalid version")]
     InvalidVersion,
+    /// The file header has a snap length of 0, so no packet data would fit in the capture
+    #[error("Invalid snap length: the file header has a snap length of 0")]
+    InvalidSnapLength,
     /// This should never happen. But preventing panics
     #[error(transparent)]
-    TryFromSliceError(#[from] std::array::TryFromSliceError),
+    TryFromSliceError(#[from] core::array::TryFromSliceError),
     #[error(transparent)]
     UnexpectedSize(#[from] UnexpectedSize),
+    #[cfg(feature = "std")]
+    #[error(transparent)]
+    TimestampRegression(#[from] TimestampRegression),
+    /// The packet is larger than the maximum buffer size of the reader
+    ///
+    /// See [`SyncPcapReader::with_max_buffer`]
+    #[error("Packet of {incl_len} bytes exceeds the maximum buffer size of {max_buffer} bytes")]
+    PacketExceedsMaxBuffer { max_buffer: usize, incl_len: u32 },
 }
@@ -1,16 +1,23 @@
 //! Packet header representation and parsing for pcap files
+use core::time::Duration;
+#[cfg(feature = "std")]
 use std::{
-    io::{Cursor, Read, Write},
+    io::{Read, Write},
     time::{SystemTime, SystemTimeError},
 };
 
+#[cfg(feature = "std")]
+use crate::byte_order::{ReadExt, WriteExt};
 use crate::{
     Version,
-    byte_order::{Endianness, ReadExt, WriteExt},
-    pcap::PcapParseError,
+    byte_order::{ByteOrder, Endianness, UnexpectedSize},
+    pcap::{PcapParseError, file_header::MagicNumber},
 };
 /// Represents the timestamp of a packet
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+///
+/// Timestamps are ordered by `seconds` then `usec`. This is only meaningful between timestamps of the same
+/// resolution; use [`PacketTimestamp::as_nanos`] to compare timestamps from files with different resolutions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct PacketTimestamp {
     /// Seconds since epoch
     pub seconds: u32,
@@ -18,6 +25,21 @@ pub struct PacketTimestamp {
     /// If nanoseconds resolution - nanoseconds part
     pub usec: u32,
 }
+impl PacketTimestamp {
+    /// Returns the timestamp as nanoseconds since the epoch
+    ///
+    /// `usec` is interpreted according to the file's `MagicNumber` resolution
+    /// (microseconds vs nanoseconds), making timestamps from files with different
+    /// resolutions comparable.
+    pub fn as_nanos(&self, resolution: MagicNumber) -> u64 {
+        let subsec_nanos = match resolution {
+            MagicNumber::Microsecond | MagicNumber::Modified => self.usec as u64 * 1000,
+            MagicNumber::Nanosecond => self.usec as u64,
+        };
+        self.seconds as u64 * 1_000_000_000 + subsec_nanos
+    }
+}
+#[cfg(feature = "std")]
 impl TryFrom<SystemTime> for PacketTimestamp {
     type Error = SystemTimeError;
 
@@ -31,18 +53,32 @@ impl TryFrom<SystemTime> for PacketTimestamp {
 }
 #[cfg(feature = "chrono")]
 mod _chrono_impl {
-    use chrono::{DateTime, NaiveDateTime};
-
-    use crate::pcap::file_header::MagicNumber;
+    use chrono::{DateTime, NaiveDateTime, TimeZone};
 
-    use super::PacketTimestamp;
+    use super::{MagicNumber, PacketHeader, PacketTimestamp};
     impl PacketTimestamp {
+        /// Converts a [`DateTime`] into a timestamp with `usec` in the given resolution
+        ///
+        /// Sub-second precision finer than `resolution` is truncated.
+        /// Returns `None` if the time is before the epoch or after the seconds field overflows in 2106
+        pub fn from_chrono_datetime<Tz: TimeZone>(
+            datetime: &DateTime<Tz>,
+            resolution: MagicNumber,
+        ) -> Option<Self> {
+            let seconds = u32::try_from(datetime.timestamp()).ok()?;
+            let nanos = datetime.timestamp_subsec_nanos();
+            let usec = match resolution {
+                MagicNumber::Microsecond | MagicNumber::Modified => nanos / 1000,
+                MagicNumber::Nanosecond => nanos,
+            };
+            Some(Self { seconds, usec })
+        }
         /// Converts this timestamp into a [`NaiveDateTime`], interpreting
         /// `usec` according to the file's `MagicNumber` resolution
         /// (microseconds vs nanoseconds).
         pub fn to_chrono_naive_datetime(&self, resolution: MagicNumber) -> Option<NaiveDateTime> {
             match resolution {
-                MagicNumber::Microsecond => {
+                MagicNumber::Microsecond | MagicNumber::Modified => {
                     DateTime::from_timestamp(self.seconds as i64, self.usec * 1000)
                         .map(|x| x.naive_utc())
                 }
@@ -52,6 +88,109 @@ mod _chrono_impl {
             }
         }
     }
+    impl PacketHeader {
+        /// Creates a packet header captured at `datetime`
+        ///
+        /// See [`PacketTimestamp::from_chrono_datetime`]
+        pub fn from_datetime<Tz: TimeZone>(
+            datetime: &DateTime<Tz>,
+            incl_len: u32,
+            orig_len: u32,
+            resolution: MagicNumber,
+        ) -> Option<Self> {
+            PacketTimestamp::from_chrono_datetime(datetime, resolution)
+                .map(|timestamp| Self::new(timestamp, incl_len, orig_len))
+        }
+    }
+}
+#[cfg(feature = "jiff")]
+mod _jiff_impl {
+    use jiff::Timestamp;
+
+    use super::{MagicNumber, PacketHeader, PacketTimestamp};
+    impl PacketTimestamp {
+        /// Converts a jiff [`Timestamp`] into a timestamp with `usec` in the given resolution
+        ///
+        /// Sub-second precision finer than `resolution` is truncated.
+        /// Returns `None` if the time is before the epoch or after the seconds field overflows in 2106
+        pub fn from_jiff_timestamp(timestamp: Timestamp, resolution: MagicNumber) -> Option<Self> {
+            let seconds = u32::try_from(timestamp.as_second()).ok()?;
+            // Negative for times in the second before the epoch
+            let nanos = u32::try_from(timestamp.subsec_nanosecond()).ok()?;
+            let usec = match resolution {
+                MagicNumber::Microsecond | MagicNumber::Modified => nanos / 1000,
+                MagicNumber::Nanosecond => nanos,
+            };
+            Some(Self { seconds, usec })
+        }
+        /// Converts this timestamp into a jiff [`Timestamp`], interpreting
+        /// `usec` according to the file's `MagicNumber` resolution
+        /// (microseconds vs nanoseconds).
+        pub fn to_jiff_timestamp(&self, resolution: MagicNumber) -> Option<Timestamp> {
+            let nanos = match resolution {
+                MagicNumber::Microsecond | MagicNumber::Modified => self.usec.checked_mul(1000)?,
+                MagicNumber::Nanosecond => self.usec,
+            };
+            Timestamp::new(self.seconds as i64, i32::try_from(nanos).ok()?).ok()
+        }
+    }
+    impl PacketHeader {
+        /// Creates a packet header captured at `timestamp`
+        ///
+        /// See [`PacketTimestamp::from_jiff_timestamp`]
+        pub fn from_jiff_timestamp(
+            timestamp: Timestamp,
+            incl_len: u32,
+            orig_len: u32,
+            resolution: MagicNumber,
+        ) -> Option<Self> {
+            PacketTimestamp::from_jiff_timestamp(timestamp, resolution)
+                .map(|timestamp| Self::new(timestamp, incl_len, orig_len))
+        }
+    }
+}
+/// The extra 8 bytes that follow each packet header in files using the
+/// "modified" libpcap magic ([`MagicNumber::Modified`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ExtendedPacketHeader {
+    /// Index of the interface the packet was captured on
+    pub if_index: u32,
+    /// Ethernet protocol of the packet
+    pub protocol: u16,
+    /// Linux packet type (e.g. `PACKET_HOST`, `PACKET_OUTGOING`)
+    pub pkt_type: u8,
+    /// Padding byte; preserved verbatim
+    pub pad: u8,
+}
+impl ExtendedPacketHeader {
+    /// Parses the extended fields from their raw 8 bytes
+    #[inline(always)]
+    pub fn parse_bytes(bytes: &[u8; 8], endianness: Endianness) -> Self {
+        Self {
+            if_index: endianness.u32_from_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            protocol: endianness.u16_from_bytes([bytes[4], bytes[5]]),
+            pkt_type: bytes[6],
+            pad: bytes[7],
+        }
+    }
+    /// Reads the extended fields from the reader
+    #[cfg(feature = "std")]
+    pub fn read<R: Read>(reader: &mut R, endianness: Endianness) -> Result<Self, PcapParseError> {
+        let bytes = reader.read_bytes::<8>()?;
+        Ok(Self::parse_bytes(&bytes, endianness))
+    }
+    /// Writes the extended fields to the writer
+    #[cfg(feature = "std")]
+    pub fn write<W: Write>(
+        &self,
+        writer: &mut W,
+        endianness: Endianness,
+    ) -> Result<(), std::io::Error> {
+        writer.write_u32(self.if_index, endianness)?;
+        writer.write_u16(self.protocol, endianness)?;
+        writer.write_all(&[self.pkt_type, self.pad])?;
+        Ok(())
+    }
 }
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PacketHeader {
@@ -61,6 +200,8 @@ pub struct PacketHeader {
     pub include_len: u32,
     /// The original length of the packet data
     pub orig_len: u32,
+    /// Extra fields present only in files using the "modified" magic number
+    pub extended: Option<ExtendedPacketHeader>,
 }
 
 impl PacketHeader {
@@ -70,8 +211,27 @@ impl PacketHeader {
             timestamp,
             include_len: incl_len,
             orig_len,
+            extended: None,
         }
     }
+    /// Returns the time between the timestamps of `self` and `other` regardless of which is earlier
+    ///
+    /// Useful for inter-packet gaps and jitter. `resolution` is the timestamp resolution of the file.
+    /// Returns `None` if the sub-second part of either timestamp does not fit in a second at `resolution`
+    pub fn delta(&self, other: &PacketHeader, resolution: MagicNumber) -> Option<Duration> {
+        let max_subsec = match resolution {
+            MagicNumber::Microsecond | MagicNumber::Modified => 1_000_000,
+            MagicNumber::Nanosecond => 1_000_000_000,
+        };
+        if self.timestamp.usec >= max_subsec || other.timestamp.usec >= max_subsec {
+            return None;
+        }
+        let nanos = self
+            .timestamp
+            .as_nanos(resolution)
+            .abs_diff(other.timestamp.as_nanos(resolution));
+        Some(Duration::from_nanos(nanos))
+    }
     /// Reads the packet header from the reader
     ///
     /// Returns `Ok(Self)` on success, or `Err` if there was an error reading
@@ -79,6 +239,7 @@ impl PacketHeader {
     /// determines field order (pre-2.3 stores `orig_len` before
     /// `include_len`).
     #[inline(always)]
+    #[cfg(feature = "std")]
     pub fn read<R: Read>(
         reader: &mut R,
         endianness: Endianness,
@@ -92,23 +253,29 @@ impl PacketHeader {
     ///
     /// `endianness` controls byte order, and `version` determines field order
     /// (pre-2.3 stores `orig_len` before `include_len`).
+    ///
+    /// [`PacketHeader::extended`] is always `None`. Use [`PacketHeader::parse_bytes_with_magic`]
+    /// to also parse the extended fields of files using [`MagicNumber::Modified`].
     #[inline(always)]
     pub fn parse_bytes(
         bytes: &[u8; 16],
         endianness: Endianness,
         version: &Version,
     ) -> Result<Self, PcapParseError> {
-        let mut cursor = Cursor::new(bytes);
-        let ts = cursor.read_u32(endianness)?;
-        let ts_usec = cursor.read_u32(endianness)?;
+        let field = |offset: usize| {
+            endianness.u32_from_bytes([
+                bytes[offset],
+                bytes[offset + 1],
+                bytes[offset + 2],
+                bytes[offset + 3],
+            ])
+        };
+        let ts = field(0);
+        let ts_usec = field(4);
         let (include_len, orig_len) = if version < &Version::PCAP_VERSION_2_3 {
-            let orig_len = cursor.read_u32(endianness)?;
-            let include_len = cursor.read_u32(endianness)?;
-            (include_len, orig_len)
+            (field(12), field(8))
         } else {
-            let include_len = cursor.read_u32(endianness)?;
-            let orig_len = cursor.read_u32(endianness)?;
-            (include_len, orig_len)
+            (field(8), field(12))
         };
         Ok(Self {
             timestamp: PacketTimestamp {
@@ -117,12 +284,45 @@ impl PacketHeader {
             },
             include_len,
             orig_len,
+            extended: None,
         })
     }
+    /// Parses a packet header from the start of `bytes` for a file using `magic_number`
+    ///
+    /// `bytes` must hold [`MagicNumber::packet_header_length`] bytes: the 16 byte header, followed by the
+    /// 8 bytes stored in [`PacketHeader::extended`] for [`MagicNumber::Modified`].
+    /// Returns [`PcapParseError::UnexpectedSize`] if `bytes` is shorter.
+    pub fn parse_bytes_with_magic(
+        bytes: &[u8],
+        magic_number: MagicNumber,
+        endianness: Endianness,
+        version: &Version,
+    ) -> Result<Self, PcapParseError> {
+        let header_length = magic_number.packet_header_length();
+        let Some(bytes) = bytes.get(..header_length) else {
+            return Err(UnexpectedSize {
+                name: "PacketHeader",
+                expected: header_length,
+                got: bytes.len(),
+            }
+            .into());
+        };
+        let mut header = Self::parse_bytes(bytes[..16].try_into()?, endianness, version)?;
+        if magic_number == MagicNumber::Modified {
+            header.extended = Some(ExtendedPacketHeader::parse_bytes(
+                bytes[16..24].try_into()?,
+                endianness,
+            ));
+        }
+        Ok(header)
+    }
     /// Writes the packet header to the writer
     ///
     /// `endianness` controls byte order, and `version` determines field order
     /// (pre-2.3 stores `orig_len` before `include_len`).
+    ///
+    /// If [`PacketHeader::extended`] is set, the extra 8 bytes are written after the header.
+    #[cfg(feature = "std")]
     pub fn write<W: Write>(
         &self,
         writer: &mut W,
@@ -134,11 +334,13 @@ impl PacketHeader {
         if version < &Version::PCAP_VERSION_2_3 {
             writer.write_u32(self.orig_len, endianness)?;
             writer.write_u32(self.include_len, endianness)?;
-            return Ok(());
         } else {
             writer.write_u32(self.include_len, endianness)?;
             writer.write_u32(self.orig_len, endianness)?;
         }
+        if let Some(extended) = &self.extended {
+            extended.write(writer, endianness)?;
+        }
 
         Ok(())
     }
@@ -146,16 +348,126 @@ impl PacketHeader {
 
 #[cfg(test)]
 mod tests {
-    use std::io::Cursor;
+    use std::{io::Cursor, time::Duration};
 
-    use chrono::{TimeZone, Utc};
+    use chrono::{TimeZone, Timelike, Utc};
 
     use crate::{
         Version,
         byte_order::Endianness,
-        pcap::packet_header::{PacketHeader, PacketTimestamp},
+        pcap::{
+            PcapParseError,
+            file_header::MagicNumber,
+            packet_header::{ExtendedPacketHeader, PacketHeader, PacketTimestamp},
+        },
     };
 
+    #[test]
+    fn test_delta() {
+        let header = |seconds, usec| PacketHeader::new(PacketTimestamp { seconds, usec }, 0, 0);
+        for (resolution, subsec) in [
+            (MagicNumber::Microsecond, 250_000),
+            (MagicNumber::Nanosecond, 250_000_000),
+        ] {
+            let first = header(10, subsec);
+            let second = header(11, subsec);
+            assert_eq!(
+                first.delta(&second, resolution),
+                Some(Duration::from_secs(1))
+            );
+            assert_eq!(
+                second.delta(&first, resolution),
+                Some(Duration::from_secs(1))
+            );
+            assert_eq!(
+                header(10, 0).delta(&first, resolution),
+                Some(Duration::from_millis(250))
+            );
+        }
+        assert_eq!(
+            header(10, 1_000_000).delta(&header(11, 0), MagicNumber::Microsecond),
+            None
+        );
+    }
+
+    /// Only uses the slice parsers that are available without the `std` feature
+    #[test]
+    fn parse_headers_from_slice() -> anyhow::Result<()> {
+        use crate::{link_type::LinkType, pcap::file_header::PcapFileHeader};
+
+        let capture = [
+            0xa1, 0xb2, 0x3c, 0x4d, // nanosecond magic, big-endian
+            0x00, 0x02, 0x00, 0x04, // version 2.4
+            0x00, 0x00, 0x00, 0x00, // timezone
+            0x00, 0x00, 0x00, 0x00, // sig_figs
+            0x00, 0x00, 0x05, 0xdc, // snap_length
+            0x00, 0x00, 0x00, 0x65, // link_type raw
+            0x00, 0x00, 0x00, 0x01, // ts_sec
+            0x00, 0x00, 0x00, 0x02, // ts_nsec
+            0x00, 0x00, 0x00, 0x01, // incl_len
+            0x00, 0x00, 0x00, 0x3c, // orig_len
+            0x45, // packet data
+        ];
+        let file_header = PcapFileHeader::try_from(&capture[..])?;
+        assert_eq!(file_header.endianness(), Endianness::BigEndian);
+        assert_eq!(file_header.resolution(), MagicNumber::Nanosecond);
+        assert_eq!(file_header.snap_length, 1500);
+        assert_eq!(file_header.link_type, LinkType::Raw);
+        assert_eq!(file_header.to_bytes(), capture[..PcapFileHeader::SIZE]);
+
+        let packet_header = PacketHeader::parse_bytes(
+            capture[PcapFileHeader::SIZE..][..16].try_into()?,
+            file_header.endianness(),
+            &file_header.version,
+        )?;
+        assert_eq!(
+            packet_header,
+            PacketHeader::new(
+                PacketTimestamp {
+                    seconds: 1,
+                    usec: 2
+                },
+                1,
+                60
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_timestamp_ordering() {
+        let timestamp = |seconds, usec| PacketTimestamp { seconds, usec };
+        let mut timestamps = vec![
+            timestamp(2, 0),
+            timestamp(1, 999_999),
+            timestamp(2, 5),
+            timestamp(0, 10),
+            timestamp(1, 0),
+        ];
+        timestamps.sort();
+        assert_eq!(
+            timestamps,
+            [
+                timestamp(0, 10),
+                timestamp(1, 0),
+                timestamp(1, 999_999),
+                timestamp(2, 0),
+                timestamp(2, 5),
+            ]
+        );
+        assert_eq!(timestamps.iter().max(), Some(&timestamp(2, 5)));
+    }
+
+    #[test]
+    fn test_timestamp_as_nanos() {
+        let timestamp = PacketTimestamp {
+            seconds: 2,
+            usec: 500,
+        };
+        assert_eq!(timestamp.as_nanos(MagicNumber::Microsecond), 2_000_500_000);
+        assert_eq!(timestamp.as_nanos(MagicNumber::Nanosecond), 2_000_000_500);
+    }
+
     #[test]
     fn write_test() -> anyhow::Result<()> {
         let specific_datetime_utc = Utc.with_ymd_and_hms(2025, 11, 27, 10, 30, 0).unwrap();
@@ -173,6 +485,7 @@ mod tests {
             },
             include_len: 100,
             orig_len: 100,
+            extended: None,
         };
         {
             let mut writer: Cursor<&mut [u8]> = Cursor::new(&mut target);
@@ -196,6 +509,7 @@ mod tests {
             timestamp: PacketTimestamp::default(),
             include_len: 1500,
             orig_len: 2000,
+            extended: None,
         };
         let mut buffer: [u8; 16] = [0; 16];
         {
@@ -232,6 +546,7 @@ mod tests {
             timestamp: PacketTimestamp::default(),
             include_len: 1500,
             orig_len: 2000,
+            extended: None,
         };
         let version = Version { major: 2, minor: 2 };
         let mut buffer: [u8; 16] = [0; 16];
@@ -255,4 +570,130 @@ mod tests {
         assert_eq!(parsed_header_v2_2.include_len, 1500);
         assert_eq!(parsed_header_v2_2.orig_len, 2000);
     }
+
+    #[test]
+    fn test_extended_header_round_trip() -> anyhow::Result<()> {
+        let header = PacketHeader {
+            timestamp: PacketTimestamp {
+                seconds: 10,
+                usec: 20,
+            },
+            include_len: 60,
+            orig_len: 60,
+            extended: Some(ExtendedPacketHeader {
+                if_index: 2,
+                protocol: 0x0800,
+                pkt_type: 4,
+                pad: 0,
+            }),
+        };
+        let mut buffer = Vec::new();
+        header.write(
+            &mut buffer,
+            Endianness::BigEndian,
+            &Version::PCAP_VERSION_2_4,
+        )?;
+        assert_eq!(buffer.len(), 24);
+        assert_eq!(&buffer[16..], &[0, 0, 0, 2, 0x08, 0x00, 4, 0]);
+
+        let base: [u8; 16] = buffer[..16].try_into()?;
+        let parsed =
+            PacketHeader::parse_bytes(&base, Endianness::BigEndian, &Version::PCAP_VERSION_2_4)?;
+        assert_eq!(parsed.extended, None);
+        let parsed = PacketHeader::parse_bytes_with_magic(
+            &buffer,
+            MagicNumber::Modified,
+            Endianness::BigEndian,
+            &Version::PCAP_VERSION_2_4,
+        )?;
+        assert_eq!(parsed, header);
+
+        // The other magic numbers ignore the extended bytes
+        let parsed = PacketHeader::parse_bytes_with_magic(
+            &buffer,
+            MagicNumber::Microsecond,
+            Endianness::BigEndian,
+            &Version::PCAP_VERSION_2_4,
+        )?;
+        assert_eq!(parsed.extended, None);
+        assert_eq!(parsed.timestamp, header.timestamp);
+
+        let err = PacketHeader::parse_bytes_with_magic(
+            &buffer[..20],
+            MagicNumber::Modified,
+            Endianness::BigEndian,
+            &Version::PCAP_VERSION_2_4,
+        )
+        .expect_err("the extended fields are truncated");
+        assert!(
+            matches!(&err, PcapParseError::UnexpectedSize(size) if size.expected == 24 && size.got == 20),
+            "unexpected error {err:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_datetime() {
+        let datetime = Utc
+            .with_ymd_and_hms(2025, 11, 27, 10, 30, 0)
+            .unwrap()
+            .with_nanosecond(123_456_789)
+            .unwrap();
+        for (resolution, usec, truncated_nanos) in [
+            (MagicNumber::Microsecond, 123_456, 123_456_000),
+            (MagicNumber::Modified, 123_456, 123_456_000),
+            (MagicNumber::Nanosecond, 123_456_789, 123_456_789),
+        ] {
+            let header = PacketHeader::from_datetime(&datetime, 60, 1500, resolution).unwrap();
+            assert_eq!(header.timestamp.seconds, 1_764_239_400);
+            assert_eq!(header.timestamp.usec, usec);
+            assert_eq!(header.include_len, 60);
+            assert_eq!(header.orig_len, 1500);
+            assert_eq!(
+                header
+                    .timestamp
+                    .to_chrono_naive_datetime(resolution)
+                    .unwrap(),
+                datetime
+                    .naive_utc()
+                    .with_nanosecond(truncated_nanos)
+                    .unwrap()
+            );
+        }
+        let before_epoch = Utc.with_ymd_and_hms(1969, 12, 31, 23, 59, 59).unwrap();
+        assert_eq!(
+            PacketHeader::from_datetime(&before_epoch, 0, 0, MagicNumber::Microsecond),
+            None
+        );
+    }
+    #[test]
+    fn test_from_jiff_timestamp() {
+        let timestamp = jiff::Timestamp::new(1_764_239_400, 123_456_789).unwrap();
+        for (resolution, usec, truncated_nanos) in [
+            (MagicNumber::Microsecond, 123_456, 123_456_000),
+            (MagicNumber::Modified, 123_456, 123_456_000),
+            (MagicNumber::Nanosecond, 123_456_789, 123_456_789),
+        ] {
+            let header =
+                PacketHeader::from_jiff_timestamp(timestamp, 60, 1500, resolution).unwrap();
+            assert_eq!(header.timestamp.seconds, 1_764_239_400);
+            assert_eq!(header.timestamp.usec, usec);
+            assert_eq!(header.include_len, 60);
+            assert_eq!(header.orig_len, 1500);
+            assert_eq!(
+                header.timestamp.to_jiff_timestamp(resolution).unwrap(),
+                jiff::Timestamp::new(1_764_239_400, truncated_nanos).unwrap()
+            );
+        }
+        // Half a second before the epoch has zero whole seconds but negative nanoseconds
+        for before_epoch in [
+            jiff::Timestamp::new(-1, 0).unwrap(),
+            jiff::Timestamp::new(0, -500_000_000).unwrap(),
+        ] {
+            assert_eq!(
+                PacketHeader::from_jiff_timestamp(before_epoch, 0, 0, MagicNumber::Microsecond),
+                None
+            );
+        }
+    }
 }
@@ -0,0 +1,283 @@
+//! Writing and reading per-packet verdicts alongside a pcap file
+//!
+//! pcap has nowhere to store whether a packet was accepted or dropped, so the verdicts
+//! are written to a separate sidecar file. Each sidecar record is the little endian `u64`
+//! index of the packet, a little endian `u32` length and then the encoded [`Verdict`].
+//! Records are written in packet order and packets without a verdict have no record.
+use std::{
+    io::{self, Read, Seek, Write},
+    marker::PhantomData,
+};
+
+use crate::pcap::{
+    PcapParseError, SyncPcapReader,
+    file_header::PcapFileHeader,
+    packet_header::PacketHeader,
+    writer::{NewPacketHeader, SyncPcapWriter},
+};
+
+/// A value stored for a packet in the sidecar file
+pub trait Verdict: Sized {
+    /// Encodes the verdict into the bytes stored in the sidecar
+    fn encode(&self) -> Vec<u8>;
+    /// Decodes a verdict written by [`Self::encode`]. Returns `None` if the bytes are invalid
+    fn decode(bytes: &[u8]) -> Option<Self>;
+}
+/// Writes packets to a pcap file and their verdicts to a sidecar file
+pub struct AnnotatedWriter<W: Write + Seek, S: Write, V: Verdict> {
+    writer: SyncPcapWriter<W>,
+    sidecar: S,
+    _verdict: PhantomData<V>,
+}
+impl<W: Write + Seek, S: Write, V: Verdict> AnnotatedWriter<W, S, V> {
+    /// Creates a new writer and writes the file header to `target`
+    pub fn new(target: W, header: PcapFileHeader, sidecar: S) -> Result<Self, io::Error> {
+        Ok(Self::from_writer(
+            SyncPcapWriter::new(target, header)?,
+            sidecar,
+        ))
+    }
+    /// Wraps an existing writer. Packet indexes start at the number of packets it has already written
+    pub fn from_writer(writer: SyncPcapWriter<W>, sidecar: S) -> Self {
+        Self {
+            writer,
+            sidecar,
+            _verdict: PhantomData,
+        }
+    }
+    /// Writes a packet and, if one is given, its verdict
+    pub fn write_packet(
+        &mut self,
+        header: NewPacketHeader,
+        content: &[u8],
+        verdict: Option<&V>,
+    ) -> Result<(), io::Error> {
+        let index = self.writer.packets_written();
+        self.writer.write_header(header, content)?;
+        if let Some(verdict) = verdict {
+            let encoded = verdict.encode();
+            let length = u32::try_from(encoded.len())
+                .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Verdict is too large"))?;
+            self.sidecar.write_all(&index.to_le_bytes())?;
+            self.sidecar.write_all(&length.to_le_bytes())?;
+            self.sidecar.write_all(&encoded)?;
+        }
+        Ok(())
+    }
+    /// Finishes the pcap file and flushes the sidecar, returning both targets
+    pub fn into_inner(mut self) -> Result<(W, S), io::Error> {
+        self.sidecar.flush()?;
+        Ok((self.writer.into_inner()?, self.sidecar))
+    }
+    /// Finishes the pcap file and flushes the sidecar
+    pub fn finish(self) -> Result<(), io::Error> {
+        self.into_inner()?;
+        Ok(())
+    }
+}
+/// Reads a pcap file and its sidecar written by [`AnnotatedWriter`] in lockstep
+#[derive(Debug)]
+pub struct AnnotatedReader<R: Read, S: Read, V: Verdict> {
+    reader: SyncPcapReader<R>,
+    sidecar: S,
+    packet_index: u64,
+    /// The next sidecar record, read ahead until its packet is reached
+    pending: Option<(u64, V)>,
+    sidecar_done: bool,
+}
+impl<R: Read, S: Read, V: Verdict> AnnotatedReader<R, S, V> {
+    /// Creates a new reader from a pcap file and its sidecar
+    pub fn new(reader: R, sidecar: S) -> Result<Self, PcapParseError> {
+        Ok(Self::from_reader(SyncPcapReader::new(reader)?, sidecar))
+    }
+    /// Wraps an existing reader that has not read any packets yet
+    pub fn from_reader(reader: SyncPcapReader<R>, sidecar: S) -> Self {
+        Self {
+            reader,
+            sidecar,
+            packet_index: 0,
+            pending: None,
+            sidecar_done: false,
+        }
+    }
+    /// Returns the file header of the pcap file
+    pub fn file_header(&self) -> &PcapFileHeader {
+        self.reader.file_header()
+    }
+    /// Reads the next packet and its verdict, if it has one
+    ///
+    /// Returns an [`io::ErrorKind::InvalidData`] error if the sidecar is truncated, its records are
+    /// out of order, or a verdict can not be decoded
+    #[allow(clippy::type_complexity)]
+    pub fn next_packet(
+        &mut self,
+    ) -> Result<Option<(PacketHeader, &[u8], Option<V>)>, PcapParseError> {
+        if self.pending.is_none() && !self.sidecar_done {
+            self.pending = self.read_record()?;
+            self.sidecar_done = self.pending.is_none();
+        }
+        let Some((header, data)) = self.reader.next_packet()? else {
+            return Ok(None);
+        };
+        let index = self.packet_index;
+        self.packet_index += 1;
+        let verdict = match &self.pending {
+            Some((record_index, _)) if *record_index < index => {
+                return Err(invalid_sidecar("Sidecar records are out of order").into());
+            }
+            Some((record_index, _)) if *record_index == index => {
+                self.pending.take().map(|(_, verdict)| verdict)
+            }
+            _ => None,
+        };
+        Ok(Some((header, data, verdict)))
+    }
+    fn read_record(&mut self) -> Result<Option<(u64, V)>, io::Error> {
+        let mut index = [0u8; 8];
+        let mut filled = 0;
+        while filled < index.len() {
+            match self.sidecar.read(&mut index[filled..]) {
+                Ok(0) if filled == 0 => return Ok(None),
+                Ok(0) => return Err(invalid_sidecar("Sidecar record is truncated")),
+                Ok(read) => filled += read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        let mut length = [0u8; 4];
+        self.read_exact_sidecar(&mut length)?;
+        let length = u64::from(u32::from_le_bytes(length));
+        // Grow the buffer as the bytes arrive so a corrupt length can not allocate up to 4 GiB
+        let mut encoded = Vec::new();
+        (&mut self.sidecar).take(length).read_to_end(&mut encoded)?;
+        if encoded.len() as u64 != length {
+            return Err(invalid_sidecar("Sidecar record is truncated"));
+        }
+        let verdict =
+            V::decode(&encoded).ok_or_else(|| invalid_sidecar("Invalid verdict in sidecar"))?;
+        Ok(Some((u64::from_le_bytes(index), verdict)))
+    }
+    fn read_exact_sidecar(&mut self, buffer: &mut [u8]) -> Result<(), io::Error> {
+        self.sidecar.read_exact(buffer).map_err(|err| {
+            if err.kind() == io::ErrorKind::UnexpectedEof {
+                invalid_sidecar("Sidecar record is truncated")
+            } else {
+                err
+            }
+        })
+    }
+}
+fn invalid_sidecar(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::pcap::packet_header::PacketTimestamp;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum FirewallVerdict {
+        Accept,
+        Drop { reason: String },
+    }
+    impl Verdict for FirewallVerdict {
+        fn encode(&self) -> Vec<u8> {
+            match self {
+                FirewallVerdict::Accept => vec![0],
+                FirewallVerdict::Drop { reason } => {
+                    let mut bytes = vec![1];
+                    bytes.extend_from_slice(reason.as_bytes());
+                    bytes
+                }
+            }
+        }
+        fn decode(bytes: &[u8]) -> Option<Self> {
+            match bytes.split_first()? {
+                (0, []) => Some(FirewallVerdict::Accept),
+                (1, reason) => Some(FirewallVerdict::Drop {
+                    reason: String::from_utf8(reason.to_vec()).ok()?,
+                }),
+                _ => None,
+            }
+        }
+    }
+    fn verdict_for(index: u32) -> Option<FirewallVerdict> {
+        match index % 3 {
+            0 => Some(FirewallVerdict::Accept),
+            1 => Some(FirewallVerdict::Drop {
+                reason: format!("rule {index}"),
+            }),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn round_trip() -> anyhow::Result<()> {
+        let mut writer = AnnotatedWriter::new(
+            Cursor::new(Vec::new()),
+            PcapFileHeader::default(),
+            Vec::new(),
+        )?;
+        for index in 0..10u32 {
+            writer.write_packet(
+                NewPacketHeader {
+                    timestamp: PacketTimestamp {
+                        seconds: index,
+                        usec: 0,
+                    },
+                    orig_len: None,
+                },
+                &index.to_le_bytes(),
+                verdict_for(index).as_ref(),
+            )?;
+        }
+        let (pcap, sidecar) = writer.into_inner()?;
+
+        let mut reader = AnnotatedReader::<_, _, FirewallVerdict>::new(
+            pcap.get_ref().as_slice(),
+            sidecar.as_slice(),
+        )?;
+        let mut index = 0u32;
+        while let Some((header, data, verdict)) = reader.next_packet()? {
+            assert_eq!(header.timestamp.seconds, index);
+            assert_eq!(data, index.to_le_bytes());
+            assert_eq!(verdict, verdict_for(index));
+            index += 1;
+        }
+        assert_eq!(index, 10);
+        Ok(())
+    }
+
+    #[test]
+    fn truncated_sidecar() -> anyhow::Result<()> {
+        let mut writer = AnnotatedWriter::new(
+            Cursor::new(Vec::new()),
+            PcapFileHeader::default(),
+            Vec::new(),
+        )?;
+        let drop = FirewallVerdict::Drop {
+            reason: "rate limit".into(),
+        };
+        writer.write_packet(NewPacketHeader::default(), &[1, 2, 3], Some(&drop))?;
+        let (pcap, mut sidecar) = writer.into_inner()?;
+        let mut corrupt_length = sidecar.clone();
+        sidecar.truncate(sidecar.len() - 2);
+        // A length far past the end of the sidecar
+        corrupt_length[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        for sidecar in [sidecar, corrupt_length] {
+            let mut reader = AnnotatedReader::<_, _, FirewallVerdict>::new(
+                pcap.get_ref().as_slice(),
+                sidecar.as_slice(),
+            )?;
+            let Err(PcapParseError::IO(err)) = reader.next_packet() else {
+                panic!("Expected an IO error");
+            };
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+//! Offset index of the packets in a pcap file
+use std::io::{Read, Seek};
+
+use crate::pcap::{PcapParseError, SyncPcapReader, packet_header::PacketHeader};
+
+/// Byte offsets of every packet header in a pcap file
+///
+/// Built by scanning the file with [`SyncPcapReader::skip_packet_seek`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PcapIndex {
+    offsets: Vec<u64>,
+}
+impl PcapIndex {
+    /// Scans the remaining packets in `reader` recording the offset of each packet header
+    ///
+    /// The reader will be positioned at the end of the file afterwards.
+    pub fn build<R: Read + Seek>(reader: &mut SyncPcapReader<R>) -> Result<Self, PcapParseError> {
+        let mut offsets = Vec::new();
+        loop {
+            let offset = reader.position();
+            if reader.skip_packet_seek()?.is_none() {
+                break;
+            }
+            offsets.push(offset);
+        }
+        Ok(Self { offsets })
+    }
+    /// Offsets of the packet headers in file order
+    pub fn offsets(&self) -> &[u64] {
+        &self.offsets
+    }
+    /// Number of packets in the index
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+    /// Returns true if the index contains no packets
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+}
+/// Iterator over packets from last to first
+///
+/// Returned by [`SyncPcapReader::iter_reverse`]
+#[derive(Debug)]
+pub struct ReversePacketIter<R: Read + Seek> {
+    reader: SyncPcapReader<R>,
+    offsets: std::vec::IntoIter<u64>,
+}
+impl<R: Read + Seek> ReversePacketIter<R> {
+    pub(crate) fn new(reader: SyncPcapReader<R>, index: PcapIndex) -> Self {
+        Self {
+            reader,
+            offsets: index.offsets.into_iter(),
+        }
+    }
+    fn read_at(&mut self, offset: u64) -> Result<(PacketHeader, Vec<u8>), PcapParseError> {
+        self.reader.seek_to(offset)?;
+        let Some((header, data)) = self.reader.next_packet()? else {
+            return Err(PcapParseError::IO(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "Indexed packet is no longer present",
+            )));
+        };
+        Ok((header, data.to_vec()))
+    }
+}
+impl<R: Read + Seek> Iterator for ReversePacketIter<R> {
+    type Item = Result<(PacketHeader, Vec<u8>), PcapParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offsets.next_back()?;
+        Some(self.read_at(offset))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.offsets.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_offsets() -> anyhow::Result<()> {
+        let file = std::fs::read("test_data/test.pcap")?;
+        let mut reader = SyncPcapReader::new(std::io::Cursor::new(file.as_slice()))?;
+        let index = PcapIndex::build(&mut reader)?;
+        assert!(!index.is_empty());
+        assert_eq!(index.offsets()[0], 24);
+
+        let mut forward = SyncPcapReader::new(file.as_slice())?;
+        for offset in index.offsets() {
+            assert_eq!(forward.position(), *offset);
+            forward.skip_packet()?;
+        }
+        assert!(forward.skip_packet()?.is_none());
+        Ok(())
+    }
+}
@@ -1,9 +1,17 @@
 //! Synchronous PCAP reader and writer
-use std::io::Read;
+use std::io::{Read, Seek, SeekFrom};
+pub mod annotated;
+pub mod index;
+pub mod monotonic;
 pub mod writer;
 use crate::{
-    Version,
-    pcap::{PcapParseError, file_header::PcapFileHeader, packet_header::PacketHeader},
+    PcapFileType, Version,
+    byte_order::ReadExt,
+    pcap::{
+        PcapParseError,
+        file_header::{MagicNumber, MagicNumberAndEndianness, PcapFileHeader},
+        packet_header::{ExtendedPacketHeader, PacketHeader},
+    },
 };
 /// A synchronous reader for PCAP files
 #[derive(Debug)]
@@ -11,76 +19,330 @@ pub struct SyncPcapReader<R: Read> {
     reader: R,
     /// Buffer for packet data
     ///
-    /// Allocated to the snap length in the file header
-    buffer: Box<[u8]>,
+    /// Grows as larger packets are read up to `max_buffer`
+    buffer: Vec<u8>,
+    /// The maximum size `buffer` may grow to
+    max_buffer: usize,
     header_buffer: [u8; 16],
     file_header: PcapFileHeader,
+    /// Bytes consumed from the start of the stream
+    position: u64,
+    /// Accept file headers between packets. See [`SyncPcapReader::multi_file`]
+    multi_file: bool,
+    /// Reject unknown major versions. See [`SyncPcapReader::strict_version`]
+    strict_version: bool,
+}
+/// Clones the source and the reader state
+///
+/// The clone gets its own packet buffer so packets read from either reader never share data.
+impl<R: Read + Clone> Clone for SyncPcapReader<R> {
+    fn clone(&self) -> Self {
+        Self {
+            reader: self.reader.clone(),
+            buffer: vec![0u8; self.buffer.len()],
+            max_buffer: self.max_buffer,
+            header_buffer: [0; 16],
+            file_header: self.file_header,
+            position: self.position,
+            multi_file: self.multi_file,
+            strict_version: self.strict_version,
+        }
+    }
 }
 impl<R: Read> SyncPcapReader<R> {
+    /// The maximum buffer size used by [`Self::new`] (16 MiB)
+    pub const DEFAULT_MAX_BUFFER: usize = 16 * 1024 * 1024;
+    /// The largest buffer allocated before any packet is read (64 KiB)
+    const INITIAL_BUFFER_CAP: usize = 64 * 1024;
     /// Creates a new `SyncPcapReader` from a reader
     /// Returns `Ok(Self)` on success, or `Err` if there was an error
     /// reading the file header
     ///
-    /// A buffer is allocated based on the snap length in the file header
-    pub fn new(mut reader: R) -> Result<Self, PcapParseError> {
+    /// The packet buffer can grow up to [`Self::DEFAULT_MAX_BUFFER`]. See [`Self::with_max_buffer`]
+    pub fn new(reader: R) -> Result<Self, PcapParseError> {
+        Self::with_max_buffer(reader, Self::DEFAULT_MAX_BUFFER)
+    }
+    /// Creates a new `SyncPcapReader` whose packet buffer will never grow larger than `max_buffer` bytes
+    ///
+    /// The snap length in the file header is not trusted for the initial allocation.
+    /// The buffer starts small and grows as larger packets are read.
+    ///
+    /// Packets larger than `max_buffer` return [`PcapParseError::PacketExceedsMaxBuffer`]
+    pub fn with_max_buffer(mut reader: R, max_buffer: usize) -> Result<Self, PcapParseError> {
         let file_header = PcapFileHeader::read(&mut reader)?;
-        let buffer = vec![0u8; file_header.snap_length as usize].into_boxed_slice();
-        Ok(Self {
+        file_header.validate_snap_length()?;
+        Ok(Self::new_with_header_and_max_buffer(
             reader,
-            buffer,
             file_header,
-            header_buffer: [0; 16],
-        })
+            max_buffer,
+        ))
     }
     pub(crate) fn new_with_header(reader: R, file_header: PcapFileHeader) -> Self {
-        let buffer = vec![0u8; file_header.snap_length as usize].into_boxed_slice();
+        Self::new_with_header_and_max_buffer(reader, file_header, Self::DEFAULT_MAX_BUFFER)
+    }
+    fn new_with_header_and_max_buffer(
+        reader: R,
+        file_header: PcapFileHeader,
+        max_buffer: usize,
+    ) -> Self {
+        let initial_size = (file_header.snap_length as usize)
+            .min(max_buffer)
+            .min(Self::INITIAL_BUFFER_CAP);
         Self {
             reader,
-            buffer,
+            buffer: vec![0u8; initial_size],
+            max_buffer,
             file_header,
             header_buffer: [0; 16],
+            position: PcapFileHeader::SIZE as u64,
+            multi_file: false,
+            strict_version: false,
+        }
+    }
+    /// Enables reading streams made of several concatenated pcap files
+    ///
+    /// libpcap tools can produce a stream where a second file header follows the packets of the first file.
+    /// In this mode, when the first 4 bytes of a packet header are a pcap magic number and the next 4 bytes
+    /// are a 2.x version, a file header is read in its place and [`Self::file_header`] is replaced before reading continues.
+    ///
+    /// This is a heuristic. Those bytes are the timestamp of a packet, so a packet whose timestamp happens to
+    /// look like a magic number and version is mistaken for a file header. Positions recorded before a new file header
+    /// are only valid with the file header they were read with.
+    ///
+    /// Disabled by default
+    pub fn multi_file(mut self, multi_file: bool) -> Self {
+        self.multi_file = multi_file;
+        self
+    }
+    /// Enables or disables version validation
+    ///
+    /// When enabled, a file header whose major version is not supported (see [`Version::is_supported`])
+    /// returns [`PcapParseError::InvalidVersion`]. The file header already read is checked when enabling.
+    ///
+    /// Disabled by default, any version is read as if it were 2.4
+    pub fn strict_version(mut self, strict_version: bool) -> Result<Self, PcapParseError> {
+        self.strict_version = strict_version;
+        self.check_version(&self.file_header)?;
+        Ok(self)
+    }
+    /// Returns [`PcapParseError::InvalidVersion`] if strict version validation is enabled and `file_header` is not supported
+    fn check_version(&self, file_header: &PcapFileHeader) -> Result<(), PcapParseError> {
+        if self.strict_version && !file_header.version.is_supported(PcapFileType::Pcap) {
+            return Err(PcapParseError::InvalidVersion);
         }
+        Ok(())
+    }
+    /// The maximum size the packet buffer may grow to
+    pub fn max_buffer(&self) -> usize {
+        self.max_buffer
     }
     /// Returns the file header of the pcap file
     pub fn file_header(&self) -> &PcapFileHeader {
         &self.file_header
     }
+    /// Consumes the reader and returns the underlying source
+    ///
+    /// The source is positioned after the last packet read, at [`Self::position`]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
     /// Returns the version of the pcap file
     pub fn version(&self) -> &Version {
         &self.file_header.version
     }
-    /// Reads the next packet from the pcap file
+    /// Number of bytes consumed from the start of the stream
     ///
-    /// Returns `Ok(None)` when end-of-file is reached and `Ok(Some((header,
-    /// data)))` for each successfully read packet. The returned slice borrows
-    /// from the reader's internal buffer and is valid until the next call.
-    pub fn next_packet(&mut self) -> Result<Option<(PacketHeader, &[u8])>, PcapParseError> {
-        if let Err(err) = self.reader.read_exact(&mut self.header_buffer) {
-            if err.kind() == std::io::ErrorKind::UnexpectedEof {
-                return Ok(None); // No more packets
-            } else {
-                return Err(PcapParseError::IO(err));
+    /// This is the offset of the next packet header. Assumes the reader was
+    /// positioned at the start of the stream when the reader was created.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+    /// Reads the next packet header and validates it against the file header
+    ///
+    /// Returns `Ok(None)` when end-of-file is reached.
+    fn read_packet_header(&mut self) -> Result<Option<PacketHeader>, PcapParseError> {
+        loop {
+            if let Err(err) = self.reader.read_exact(&mut self.header_buffer) {
+                if err.kind() == std::io::ErrorKind::UnexpectedEof {
+                    return Ok(None); // No more packets
+                } else {
+                    return Err(PcapParseError::IO(err));
+                }
+            }
+            if !(self.multi_file && self.read_concatenated_file_header()?) {
+                break;
             }
         }
-        let packet_header = PacketHeader::parse_bytes(
+        let mut packet_header = PacketHeader::parse_bytes(
             &self.header_buffer,
-            self.file_header.magic_number_and_endianness.endianness,
+            self.file_header.endianness(),
             &self.file_header.version,
         )?;
+        if self.file_header.resolution() == MagicNumber::Modified {
+            packet_header.extended = Some(ExtendedPacketHeader::read(
+                &mut self.reader,
+                self.file_header.endianness(),
+            )?);
+        }
         if packet_header.include_len > self.file_header.snap_length {
             return Err(PcapParseError::InvalidPacketLength {
                 snap_length: self.file_header.snap_length,
                 incl_len: packet_header.include_len,
             });
         }
-        let mut_buffer: &mut [u8] = &mut self.buffer;
+        Ok(Some(packet_header))
+    }
+    /// Replaces the file header if the packet header just read is the start of another file header
+    ///
+    /// Returns `true` if a file header was read. See [`Self::multi_file`]
+    fn read_concatenated_file_header(&mut self) -> Result<bool, PcapParseError> {
+        let Ok(magic) = MagicNumberAndEndianness::try_from(&self.header_buffer[0..4]) else {
+            return Ok(false);
+        };
+        if Version::parse(&self.header_buffer[4..8], magic.endianness).major != 2 {
+            return Ok(false);
+        }
+        let mut header = [0u8; PcapFileHeader::SIZE];
+        header[..16].copy_from_slice(&self.header_buffer);
+        self.reader.read_exact(&mut header[16..])?;
+        let file_header = PcapFileHeader::try_from(&header)?;
+        file_header.validate_snap_length()?;
+        self.check_version(&file_header)?;
+        self.file_header = file_header;
+        self.position += PcapFileHeader::SIZE as u64;
+        Ok(true)
+    }
+    /// Advances [`Self::position`] past the packet described by `packet_header`
+    fn advance_position(&mut self, packet_header: &PacketHeader) {
+        self.position += (self.file_header.resolution().packet_header_length()
+            + packet_header.include_len as usize) as u64;
+    }
+    /// Reads the next packet from the pcap file
+    ///
+    /// Returns `Ok(None)` when end-of-file is reached and `Ok(Some((header,
+    /// data)))` for each successfully read packet. The returned slice borrows
+    /// from the reader's internal buffer and is valid until the next call.
+    pub fn next_packet(&mut self) -> Result<Option<(PacketHeader, &[u8])>, PcapParseError> {
+        let Some(packet_header) = self.read_packet_header()? else {
+            return Ok(None);
+        };
+        let include_len = packet_header.include_len as usize;
+        if include_len > self.buffer.len() {
+            if include_len > self.max_buffer {
+                return Err(PcapParseError::PacketExceedsMaxBuffer {
+                    max_buffer: self.max_buffer,
+                    incl_len: packet_header.include_len,
+                });
+            }
+            self.buffer.resize(include_len, 0);
+        }
+        self.reader.read_exact(&mut self.buffer[..include_len])?;
+        self.advance_position(&packet_header);
+
+        Ok(Some((packet_header, &self.buffer[..include_len])))
+    }
+    /// Reads the next packet header and discards the packet data without copying it into the packet buffer
+    ///
+    /// Returns `Ok(None)` when end-of-file is reached.
+    pub fn skip_packet(&mut self) -> Result<Option<PacketHeader>, PcapParseError> {
+        let Some(packet_header) = self.read_packet_header()? else {
+            return Ok(None);
+        };
+        self.reader.skip_exact(packet_header.include_len as u64)?;
+        self.advance_position(&packet_header);
+        Ok(Some(packet_header))
+    }
+    /// Reads every remaining packet into a vector
+    ///
+    /// Intended for small files as every packet is copied.
+    pub fn read_all(self) -> Result<Vec<(PacketHeader, Vec<u8>)>, PcapParseError> {
+        self.read_all_with_capacity(0)
+    }
+    /// Like [`Self::read_all`] but reserves space for `capacity` packets up front
+    pub fn read_all_with_capacity(
+        mut self,
+        capacity: usize,
+    ) -> Result<Vec<(PacketHeader, Vec<u8>)>, PcapParseError> {
+        let mut packets = Vec::with_capacity(capacity);
+        while let Some((header, data)) = self.next_packet()? {
+            packets.push((header, data.to_vec()));
+        }
+        Ok(packets)
+    }
+}
+impl<R: Read + Seek> SyncPcapReader<R> {
+    /// Like [`Self::skip_packet`] but seeks past the packet data instead of reading it
+    ///
+    /// This is much faster for header-only scans of large files.
+    ///
+    /// Seeking past the end of the file is not an error, so a truncated final
+    /// packet is not detected; the next read will return `Ok(None)`.
+    pub fn skip_packet_seek(&mut self) -> Result<Option<PacketHeader>, PcapParseError> {
+        let Some(packet_header) = self.read_packet_header()? else {
+            return Ok(None);
+        };
         self.reader
-            .read_exact(&mut mut_buffer[0..(packet_header.include_len as usize)])?;
+            .seek(SeekFrom::Current(packet_header.include_len as i64))?;
+        self.advance_position(&packet_header);
+        Ok(Some(packet_header))
+    }
+    /// Number of packet headers sampled by [`Self::estimate_packet_count`]
+    const ESTIMATE_SAMPLE_PACKETS: u64 = 32;
+    /// Estimates the number of packets left in the file, for progress reporting
+    ///
+    /// The headers of the next few packets are read to find an average packet size, and the remaining
+    /// file size is divided by it. The result is only approximate unless the file is small enough to be
+    /// fully sampled. Packet sizes vary, so the estimate can be far off for captures that are not uniform.
+    ///
+    /// The reader is returned to its current position afterwards.
+    pub fn estimate_packet_count(&mut self) -> std::io::Result<u64> {
+        let start = self.reader.stream_position()?;
+        let end = self.reader.seek(SeekFrom::End(0))?;
+        self.reader.seek(SeekFrom::Start(start))?;
+        let (position, file_header) = (self.position, self.file_header);
+
+        let sampled = self.sample_packet_sizes();
+        self.reader.seek(SeekFrom::Start(start))?;
+        self.position = position;
+        self.file_header = file_header;
+        let (packets, bytes, reached_end) = sampled.map_err(|err| match err {
+            PcapParseError::IO(err) => err,
+            other => std::io::Error::new(std::io::ErrorKind::InvalidData, other),
+        })?;
 
-        Ok(Some((
-            packet_header,
-            &self.buffer[..(packet_header.include_len as usize)],
-        )))
+        if reached_end || packets == 0 || bytes == 0 {
+            return Ok(packets);
+        }
+        let remaining = end.saturating_sub(start);
+        Ok((remaining as u128 * packets as u128 / bytes as u128) as u64)
+    }
+    /// Skips up to [`Self::ESTIMATE_SAMPLE_PACKETS`] packets
+    ///
+    /// Returns the number of packets, the bytes they occupy including headers and whether the end of the file was reached
+    fn sample_packet_sizes(&mut self) -> Result<(u64, u64, bool), PcapParseError> {
+        let mut packets = 0;
+        let start = self.position;
+        while packets < Self::ESTIMATE_SAMPLE_PACKETS {
+            if self.skip_packet_seek()?.is_none() {
+                return Ok((packets, self.position - start, true));
+            }
+            packets += 1;
+        }
+        Ok((packets, self.position - start, false))
+    }
+    /// Seeks to `position` which must be the offset of a packet header
+    pub(crate) fn seek_to(&mut self, position: u64) -> Result<(), PcapParseError> {
+        self.reader.seek(SeekFrom::Start(position))?;
+        self.position = position;
+        Ok(())
+    }
+    /// Returns an iterator over the packets from last to first
+    ///
+    /// This requires a full forward scan of the remaining packets to build a
+    /// [`PcapIndex`](index::PcapIndex) before the first packet is returned.
+    pub fn iter_reverse(mut self) -> Result<index::ReversePacketIter<R>, PcapParseError> {
+        let index = index::PcapIndex::build(&mut self)?;
+        Ok(index::ReversePacketIter::new(self, index))
     }
 }
 #[cfg(test)]
@@ -115,4 +377,312 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn read_modified_magic() -> anyhow::Result<()> {
+        let mut file = vec![
+            0x34, 0xcd, 0xb2, 0xa1, // modified magic, little-endian
+            0x02, 0x00, 0x04, 0x00, // version 2.4
+            0x00, 0x00, 0x00, 0x00, // timezone
+            0x00, 0x00, 0x00, 0x00, // sig_figs
+            0xff, 0xff, 0x00, 0x00, // snap_length
+            0x01, 0x00, 0x00, 0x00, // link_type
+        ];
+        for payload in [[0xAAu8; 4], [0xBB; 4]] {
+            file.extend_from_slice(&[
+                0x01, 0x00, 0x00, 0x00, // ts_sec
+                0x02, 0x00, 0x00, 0x00, // ts_usec
+                0x04, 0x00, 0x00, 0x00, // incl_len
+                0x04, 0x00, 0x00, 0x00, // orig_len
+                0x03, 0x00, 0x00, 0x00, // ifindex
+                0x00, 0x08, // protocol
+                0x04, // pkt_type
+                0x00, // pad
+            ]);
+            file.extend_from_slice(&payload);
+        }
+        let mut reader = SyncPcapReader::new(file.as_slice())?;
+        assert_eq!(reader.file_header().resolution(), MagicNumber::Modified);
+        let (header, data) = reader.next_packet()?.expect("first packet");
+        assert_eq!(data, [0xAA; 4]);
+        assert_eq!(header.include_len, 4);
+        assert_eq!(
+            header.extended,
+            Some(ExtendedPacketHeader {
+                if_index: 3,
+                protocol: 0x0800,
+                pkt_type: 4,
+                pad: 0,
+            })
+        );
+        let (_, data) = reader.next_packet()?.expect("second packet");
+        assert_eq!(data, [0xBB; 4]);
+        assert!(reader.next_packet()?.is_none());
+        assert_eq!(reader.position(), file.len() as u64);
+        Ok(())
+    }
+
+    #[test]
+    fn skip_packet_matches_next_packet() -> anyhow::Result<()> {
+        let file = std::fs::read("test_data/test.pcap")?;
+        let mut reader = SyncPcapReader::new(file.as_slice())?;
+        let mut headers = Vec::new();
+        while let Some((header, _)) = reader.next_packet()? {
+            headers.push(header);
+        }
+
+        let mut skipping_reader = SyncPcapReader::new(file.as_slice())?;
+        let mut skipped_headers = Vec::new();
+        while let Some(header) = skipping_reader.skip_packet()? {
+            skipped_headers.push(header);
+        }
+        assert!(!headers.is_empty());
+        assert_eq!(headers, skipped_headers);
+        assert_eq!(skipping_reader.position(), reader.position());
+        Ok(())
+    }
+
+    #[test]
+    fn skip_packet_seek_matches_next_packet() -> anyhow::Result<()> {
+        let mut reader = SyncPcapReader::new(std::fs::File::open("test_data/test.pcap")?)?;
+        let mut count = 0;
+        while reader.next_packet()?.is_some() {
+            count += 1;
+        }
+
+        let mut seeking_reader = SyncPcapReader::new(std::fs::File::open("test_data/test.pcap")?)?;
+        let mut seek_count = 0;
+        while let Some(header) = seeking_reader.skip_packet_seek()? {
+            assert!(header.include_len <= header.orig_len);
+            seek_count += 1;
+        }
+        assert!(count > 0);
+        assert_eq!(count, seek_count);
+        assert_eq!(seeking_reader.position(), reader.position());
+        assert_eq!(
+            seeking_reader.reader.stream_position()?,
+            std::fs::metadata("test_data/test.pcap")?.len()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn iter_reverse_matches_forward() -> anyhow::Result<()> {
+        let mut reader = SyncPcapReader::new(std::fs::File::open("test_data/test.pcap")?)?;
+        let mut forward = Vec::new();
+        while let Some((header, data)) = reader.next_packet()? {
+            forward.push((header, data.to_vec()));
+        }
+        let reversed = SyncPcapReader::new(std::fs::File::open("test_data/test.pcap")?)?
+            .iter_reverse()?
+            .collect::<Result<Vec<_>, _>>()?;
+        forward.reverse();
+        assert!(!forward.is_empty());
+        assert_eq!(forward, reversed);
+        Ok(())
+    }
+
+    #[test]
+    fn read_all_with_capacity() -> anyhow::Result<()> {
+        let file = std::fs::read("test_data/test.pcap")?;
+        let packets = SyncPcapReader::new(file.as_slice())?.read_all()?;
+        assert!(!packets.is_empty());
+        let with_capacity = SyncPcapReader::new(file.as_slice())?.read_all_with_capacity(64)?;
+        assert!(with_capacity.capacity() >= 64);
+        assert_eq!(with_capacity, packets);
+        Ok(())
+    }
+    #[test]
+    fn clone_mid_stream() -> anyhow::Result<()> {
+        let file = std::fs::read("test_data/test.pcap")?;
+        let mut reader = SyncPcapReader::new(std::io::Cursor::new(file.as_slice()))?;
+        reader.next_packet()?;
+        let mut cloned = reader.clone();
+        assert_eq!(cloned.position(), reader.position());
+        let mut packets = 0;
+        while let Some((header, data)) = reader.next_packet()? {
+            let (cloned_header, cloned_data) = cloned.next_packet()?.expect("same packets");
+            assert_eq!(cloned_header, header);
+            assert_eq!(cloned_data, data);
+            packets += 1;
+        }
+        assert!(packets > 0);
+        assert!(cloned.next_packet()?.is_none());
+        Ok(())
+    }
+    #[test]
+    fn skip_packet_truncated() -> anyhow::Result<()> {
+        let mut file = Vec::new();
+        PcapFileHeader::default().write(&mut file)?;
+        // Claims 8 bytes but only 2 follow
+        file.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0, 8, 0, 0, 0, 8, 0, 0, 0, 1, 2]);
+        let mut reader = SyncPcapReader::new(file.as_slice())?;
+        let err = reader.skip_packet().expect_err("packet is truncated");
+        assert!(
+            matches!(&err, PcapParseError::IO(io) if io.kind() == std::io::ErrorKind::UnexpectedEof),
+            "unexpected error {err:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn position_tracks_consumed_bytes() -> anyhow::Result<()> {
+        let file = std::fs::read("test_data/test.pcap")?;
+        let mut reader = SyncPcapReader::new(file.as_slice())?;
+        assert_eq!(reader.position(), 24);
+        let mut expected = 24;
+        for _ in 0..5 {
+            let (header, _) = reader
+                .next_packet()?
+                .expect("test.pcap has at least 5 packets");
+            expected += 16 + header.include_len as u64;
+            assert_eq!(reader.position(), expected);
+        }
+        while reader.next_packet()?.is_some() {}
+        assert_eq!(reader.position(), file.len() as u64);
+        Ok(())
+    }
+
+    #[test]
+    fn zero_snap_length_is_rejected() -> anyhow::Result<()> {
+        let mut file = Vec::new();
+        PcapFileHeader::builder()
+            .snap_length(0)
+            .build()
+            .write(&mut file)?;
+        file.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 4, 0, 0, 0, 1, 2, 3, 4]);
+
+        let err = SyncPcapReader::new(file.as_slice()).expect_err("snap length is 0");
+        assert!(
+            matches!(err, PcapParseError::InvalidSnapLength),
+            "unexpected error {err:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn strict_version() -> anyhow::Result<()> {
+        let mut file = Vec::new();
+        PcapFileHeader::default().write(&mut file)?;
+        let reader = SyncPcapReader::new(file.as_slice())?.strict_version(true)?;
+        assert_eq!(reader.file_header().version, Version::PCAP_VERSION_2_4);
+
+        PcapFileHeader::builder()
+            .version(Version { major: 3, minor: 0 })
+            .build()
+            .write(&mut file.as_mut_slice())?;
+        // Lenient by default
+        let reader = SyncPcapReader::new(file.as_slice())?;
+        assert_eq!(reader.file_header().version.major, 3);
+        let err = reader.strict_version(true).expect_err("version 3.0");
+        assert!(
+            matches!(err, PcapParseError::InvalidVersion),
+            "unexpected error {err:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn huge_snap_length_is_not_allocated() -> anyhow::Result<()> {
+        let mut file = Vec::new();
+        PcapFileHeader::builder()
+            .snap_length(u32::MAX)
+            .build()
+            .write(&mut file)?;
+        file.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0, 100, 0, 0, 0, 100, 0, 0, 0]);
+        file.extend_from_slice(&[7; 100]);
+
+        let mut reader = SyncPcapReader::new(file.as_slice())?;
+        assert_eq!(reader.file_header().snap_length, u32::MAX);
+        assert!(reader.buffer.len() <= 64 * 1024);
+        let (_, data) = reader.next_packet()?.expect("one packet");
+        assert_eq!(data, &[7; 100]);
+
+        let mut reader = SyncPcapReader::with_max_buffer(file.as_slice(), 64)?;
+        let err = reader.next_packet().expect_err("packet is larger than max");
+        assert!(
+            matches!(
+                err,
+                PcapParseError::PacketExceedsMaxBuffer {
+                    max_buffer: 64,
+                    incl_len: 100
+                }
+            ),
+            "unexpected error {err:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn multi_file_concatenated() -> anyhow::Result<()> {
+        let single = std::fs::read("test_data/test.pcap")?;
+        let mut concatenated = single.clone();
+        concatenated.extend_from_slice(&single);
+
+        let mut reader = SyncPcapReader::new(single.as_slice())?;
+        let mut expected = Vec::new();
+        while let Some((header, data)) = reader.next_packet()? {
+            expected.push((header, data.to_vec()));
+        }
+        assert!(!expected.is_empty());
+
+        let mut reader = SyncPcapReader::new(concatenated.as_slice())?.multi_file(true);
+        let mut packets = Vec::new();
+        while let Some((header, data)) = reader.next_packet()? {
+            packets.push((header, data.to_vec()));
+        }
+        assert_eq!(packets.len(), expected.len() * 2);
+        assert_eq!(packets[..expected.len()], expected[..]);
+        assert_eq!(packets[expected.len()..], expected[..]);
+        assert_eq!(reader.position(), concatenated.len() as u64);
+
+        // Without multi_file the second file header is read as a packet header
+        let mut reader = SyncPcapReader::new(concatenated.as_slice())?;
+        let mut count = 0;
+        while let Ok(Some(_)) = reader.next_packet() {
+            count += 1;
+        }
+        assert_ne!(count, expected.len() * 2);
+        Ok(())
+    }
+
+    #[test]
+    fn estimate_packet_count() -> anyhow::Result<()> {
+        let file = std::fs::read("test_data/test.pcap")?;
+        let total = SyncPcapReader::new(file.as_slice())?.read_all()?.len() as u64;
+        assert!(total > SyncPcapReader::<std::io::Cursor<&[u8]>>::ESTIMATE_SAMPLE_PACKETS);
+
+        let mut reader = SyncPcapReader::new(std::io::Cursor::new(file.as_slice()))?;
+        let estimate = reader.estimate_packet_count()?;
+        assert!(
+            estimate >= total / 2 && estimate <= total * 2,
+            "estimate {estimate} is far from {total}"
+        );
+        // The reader is left where it was
+        assert_eq!(reader.position(), PcapFileHeader::SIZE as u64);
+        assert_eq!(reader.read_all()?.len() as u64, total);
+
+        // Fully sampled files are counted exactly
+        let mut reader = SyncPcapReader::new(std::io::Cursor::new(file.as_slice()))?;
+        for _ in 0..total - 3 {
+            reader.skip_packet()?;
+        }
+        assert_eq!(reader.estimate_packet_count()?, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn into_inner_keeps_remaining_bytes() -> anyhow::Result<()> {
+        let file = std::fs::read("test_data/test.pcap")?;
+        let mut reader = SyncPcapReader::new(std::io::Cursor::new(file.as_slice()))?;
+        reader.next_packet()?;
+        reader.next_packet()?;
+        let position = reader.position() as usize;
+
+        let mut source = reader.into_inner();
+        let mut remaining = Vec::new();
+        source.read_to_end(&mut remaining)?;
+        assert_eq!(remaining, &file[position..]);
+        Ok(())
+    }
 }
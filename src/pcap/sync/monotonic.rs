@@ -0,0 +1,163 @@
+//! Reader wrapper that verifies packet timestamps never go backwards
+//!
+//! Out-of-order timestamps usually indicate clock adjustments during the capture
+//! or files that were concatenated without being merged.
+use std::io::Read;
+
+use thiserror::Error;
+
+use crate::pcap::{
+    PcapParseError, SyncPcapReader,
+    file_header::PcapFileHeader,
+    packet_header::{PacketHeader, PacketTimestamp},
+};
+
+/// A packet whose timestamp is earlier than the packet before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("Timestamp regression at packet {packet_index}: {current:?} is earlier than {previous:?}")]
+pub struct TimestampRegression {
+    /// Timestamp of the previous packet
+    pub previous: PacketTimestamp,
+    /// Timestamp of the offending packet
+    pub current: PacketTimestamp,
+    /// Zero based index of the offending packet
+    pub packet_index: u64,
+}
+
+/// Wraps a [`SyncPcapReader`] and checks that packet timestamps are non-decreasing
+///
+/// In strict mode (the default) a regression is returned as
+/// [`PcapParseError::TimestampRegression`]. In non-strict mode the packet is
+/// returned as normal and the regression is recorded in [`Self::regressions`].
+#[derive(Debug)]
+pub struct MonotonicCheckReader<R: Read> {
+    reader: SyncPcapReader<R>,
+    strict: bool,
+    previous: Option<PacketTimestamp>,
+    packet_index: u64,
+    regressions: Vec<TimestampRegression>,
+}
+impl<R: Read> MonotonicCheckReader<R> {
+    /// Creates a new strict `MonotonicCheckReader` from a reader
+    pub fn new(reader: R) -> Result<Self, PcapParseError> {
+        SyncPcapReader::new(reader).map(Self::from)
+    }
+    /// Sets whether a regression is returned as an error (`true`) or only recorded (`false`)
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+    /// Returns the file header of the pcap file
+    pub fn file_header(&self) -> &PcapFileHeader {
+        self.reader.file_header()
+    }
+    /// Regressions recorded in non-strict mode
+    pub fn regressions(&self) -> &[TimestampRegression] {
+        &self.regressions
+    }
+    /// Returns the inner reader
+    pub fn into_inner(self) -> SyncPcapReader<R> {
+        self.reader
+    }
+    /// Reads the next packet and checks its timestamp against the previous packet
+    pub fn next_packet(&mut self) -> Result<Option<(PacketHeader, &[u8])>, PcapParseError> {
+        let resolution = self.reader.file_header().resolution();
+        let Some((header, data)) = self.reader.next_packet()? else {
+            return Ok(None);
+        };
+        let packet_index = self.packet_index;
+        self.packet_index += 1;
+        let current = header.timestamp;
+        if let Some(previous) = self.previous
+            && current.as_nanos(resolution) < previous.as_nanos(resolution)
+        {
+            let regression = TimestampRegression {
+                previous,
+                current,
+                packet_index,
+            };
+            if self.strict {
+                return Err(regression.into());
+            }
+            self.regressions.push(regression);
+        }
+        self.previous = Some(current);
+        Ok(Some((header, data)))
+    }
+}
+impl<R: Read> From<SyncPcapReader<R>> for MonotonicCheckReader<R> {
+    fn from(reader: SyncPcapReader<R>) -> Self {
+        Self {
+            reader,
+            strict: true,
+            previous: None,
+            packet_index: 0,
+            regressions: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::pcap::writer::{NewPacketHeader, SyncPcapWriter};
+
+    fn out_of_order_pcap() -> anyhow::Result<Vec<u8>> {
+        let mut writer = SyncPcapWriter::new(Cursor::new(Vec::new()), PcapFileHeader::default())?;
+        for (seconds, usec) in [(10, 0), (10, 500), (9, 999_999), (11, 0)] {
+            writer.write_header(
+                NewPacketHeader {
+                    timestamp: PacketTimestamp { seconds, usec },
+                    orig_len: None,
+                },
+                &[0xAA; 4],
+            )?;
+        }
+        Ok(writer.into_inner()?.into_inner())
+    }
+
+    #[test]
+    fn strict_returns_regression() -> anyhow::Result<()> {
+        let mut reader = MonotonicCheckReader::new(Cursor::new(out_of_order_pcap()?))?;
+        assert!(reader.next_packet()?.is_some());
+        assert!(reader.next_packet()?.is_some());
+        let err = reader
+            .next_packet()
+            .expect_err("third packet goes backwards");
+        let PcapParseError::TimestampRegression(regression) = err else {
+            panic!("unexpected error {err:?}");
+        };
+        assert_eq!(regression.packet_index, 2);
+        assert_eq!(
+            regression.previous,
+            PacketTimestamp {
+                seconds: 10,
+                usec: 500
+            }
+        );
+        assert_eq!(
+            regression.current,
+            PacketTimestamp {
+                seconds: 9,
+                usec: 999_999
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn non_strict_records_regression() -> anyhow::Result<()> {
+        let mut reader =
+            MonotonicCheckReader::new(Cursor::new(out_of_order_pcap()?))?.strict(false);
+        let mut count = 0;
+        while reader.next_packet()?.is_some() {
+            count += 1;
+        }
+        assert_eq!(count, 4);
+        assert_eq!(reader.regressions().len(), 1);
+        assert_eq!(reader.regressions()[0].packet_index, 2);
+        Ok(())
+    }
+}
@@ -1,9 +1,12 @@
 //! Synchronous pcap writer
 use std::io::{self, Seek, Write};
+#[cfg(feature = "etherparse")]
+pub mod built;
+pub mod rotating;
 pub mod seekless;
 use crate::pcap::{
-    file_header::PcapFileHeader,
-    packet_header::{PacketHeader, PacketTimestamp},
+    file_header::{MagicNumber, PcapFileHeader},
+    packet_header::{ExtendedPacketHeader, PacketHeader, PacketTimestamp},
 };
 
 /// Header data supplied by callers when writing a new packet.
@@ -17,6 +20,17 @@ pub struct NewPacketHeader {
     /// The original length of the packet data
     pub orig_len: Option<u32>,
 }
+/// What [`SyncPcapWriter`] does when a packet is larger than the snap length
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TruncationPolicy {
+    /// Write the whole packet and grow the snap length in the file header
+    #[default]
+    GrowSnapLength,
+    /// Only write `snap_length` bytes of the packet.
+    ///
+    /// `orig_len` is still set to the full length of the packet
+    TruncateToSnap,
+}
 /// A Sync Pcap Writer
 ///
 /// ## Why is Seek Required?
@@ -29,34 +43,69 @@ pub struct SyncPcapWriter<W: Write + Seek> {
     header: PcapFileHeader,
     /// If a written packet size exceeds snap_len then this will flip to true
     requires_header_rewrite: bool,
+    truncation_policy: TruncationPolicy,
+    /// Total bytes written including the file header
+    bytes_written: u64,
+    packets_written: u64,
+    /// Reused by [`Self::write_built`]
+    #[cfg(feature = "etherparse")]
+    built_buffer: Vec<u8>,
 }
 
 impl<W: Write + Seek> SyncPcapWriter<W> {
     /// Creates a new writer and immediately writes the file header to
     /// `target`.
+    ///
+    /// The header is written as given, including its `timezone` and `sig_figs`.
     pub fn new(mut target: W, header: PcapFileHeader) -> Result<Self, io::Error> {
         header.write(&mut target)?;
         Ok(Self {
             target,
             header,
             requires_header_rewrite: false,
+            truncation_policy: TruncationPolicy::default(),
+            bytes_written: PcapFileHeader::SIZE as u64,
+            packets_written: 0,
+            #[cfg(feature = "etherparse")]
+            built_buffer: Vec::new(),
         })
     }
 
+    /// Sets what happens when a packet is larger than the snap length
+    pub fn with_truncation_policy(mut self, truncation_policy: TruncationPolicy) -> Self {
+        self.truncation_policy = truncation_policy;
+        self
+    }
+    /// Returns the current [`TruncationPolicy`]
+    pub fn truncation_policy(&self) -> TruncationPolicy {
+        self.truncation_policy
+    }
+
     /// Writes a packet to the target.
     ///
     /// If `content.len()` exceeds the file header's `snap_length`, the
-    /// header's snap length is updated in memory and a rewrite is queued for
-    /// [`Self::finish`] / [`Self::update_snap_length`].
+    /// behavior depends on the [`TruncationPolicy`]. With
+    /// [`TruncationPolicy::GrowSnapLength`] the header's snap length is updated
+    /// in memory and a rewrite is queued for [`Self::finish`] / [`Self::update_snap_length`].
+    /// With [`TruncationPolicy::TruncateToSnap`] only the first `snap_length` bytes are written.
     pub fn write_header(
         &mut self,
         header: NewPacketHeader,
         content: &[u8],
     ) -> Result<(), io::Error> {
+        let orig_len = header.orig_len.unwrap_or(content.len() as u32);
+        let content = match self.truncation_policy {
+            TruncationPolicy::TruncateToSnap => {
+                &content[..content.len().min(self.header.snap_length as usize)]
+            }
+            TruncationPolicy::GrowSnapLength => content,
+        };
         let new_header = PacketHeader {
             timestamp: header.timestamp,
             include_len: content.len() as u32,
-            orig_len: header.orig_len.unwrap_or(content.len() as u32),
+            orig_len,
+            extended: (self.header.resolution() == MagicNumber::Modified)
+                .then(ExtendedPacketHeader::default),
         };
         if new_header.include_len > self.header.snap_length {
             self.requires_header_rewrite = true;
@@ -65,25 +114,37 @@ impl<W: Write + Seek> SyncPcapWriter<W> {
 
         new_header.write(
             &mut self.target,
-            self.header.magic_number_and_endianness.endianness,
+            self.header.endianness(),
             &self.header.version,
         )?;
         self.target.write_all(content)?;
+        self.bytes_written +=
+            (self.header.resolution().packet_header_length() + content.len()) as u64;
+        self.packets_written += 1;
         Ok(())
     }
+    /// Total number of bytes written to the target, including the file header
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+    /// Number of packets written to the target
+    pub fn packets_written(&self) -> u64 {
+        self.packets_written
+    }
 
     /// Flushes the target and rewrites the file header if any packet exceeded
     /// the original snap length.
-    pub fn finish(mut self) -> Result<(), io::Error> {
-        self.target.flush()?;
-        self.update_snap_length()?;
+    pub fn finish(self) -> Result<(), io::Error> {
+        self.into_inner()?;
         Ok(())
     }
     /// Consumes the writer and returns the underlying target
     ///
-    /// Warning: this will not update the header even if packets larger than snap_length were written
-    pub fn into_inner(self) -> W {
-        self.target
+    /// Like [`Self::finish`] the target is flushed and the file header is rewritten if required.
+    pub fn into_inner(mut self) -> Result<W, io::Error> {
+        self.target.flush()?;
+        self.update_snap_length()?;
+        Ok(self.target)
     }
     /// Seeks back to the start and updates the snap_length in the header if required
     /// Returns `Ok(true)` if the header was rewritten, `Ok(false)` if no rewrite was necessary
@@ -105,7 +166,7 @@ impl<W: Write + Seek> SyncPcapWriter<W> {
 
 #[cfg(test)]
 mod tests {
-    use std::{fs::File, time::Duration};
+    use std::{fs::File, io::Cursor, time::Duration};
 
     use chrono::{TimeZone, Utc};
     use etherparse::PacketBuilder;
@@ -118,7 +179,7 @@ mod tests {
             packet_header::PacketTimestamp,
             sync::{
                 SyncPcapReader,
-                writer::{NewPacketHeader, SyncPcapWriter},
+                writer::{NewPacketHeader, SyncPcapWriter, TruncationPolicy},
             },
         },
     };
@@ -139,6 +200,8 @@ mod tests {
                         endianness: Endianness::LittleEndian,
                         magic_number: MagicNumber::Microsecond,
                     },
+                    // Forces the header rewrite path in `finish`
+                    snap_length: 0,
                     ..Default::default()
                 },
             )?;
@@ -192,4 +255,137 @@ mod tests {
         crate::test_helpers::do_files_match(actual, expected)?;
         Ok(())
     }
+
+    #[test]
+    fn test_write_big_endian() -> anyhow::Result<()> {
+        let (actual, expected) = crate::test_helpers::test_files("sync_writer_big_endian.pcap")?;
+        let header = PcapFileHeader::builder()
+            .endianness(Endianness::BigEndian)
+            .resolution(MagicNumber::Microsecond)
+            .snap_length(0x0102)
+            .build();
+        let mut writer = SyncPcapWriter::new(File::create(&actual)?, header)?;
+        let packets: [(u32, &[u8]); 3] = [
+            (0x0A0B_0C0D, &[1, 2, 3, 4]),
+            (0x0A0B_0C0E, &[5, 6, 7, 8, 9, 10]),
+            (0x0A0B_0C0F, &[0xAB; 0x0110]),
+        ];
+        for (seconds, data) in packets {
+            writer.write_header(
+                NewPacketHeader {
+                    orig_len: None,
+                    timestamp: PacketTimestamp {
+                        seconds,
+                        usec: 0x0001_0203,
+                    },
+                },
+                data,
+            )?;
+        }
+        writer.finish()?;
+
+        let file = std::fs::read(&actual)?;
+        assert_eq!(file[0..4], [0xa1, 0xb2, 0xc3, 0xd4], "magic number");
+        assert_eq!(file[4..8], [0, 2, 0, 4], "version");
+        // The last packet grew the snap length
+        assert_eq!(file[16..20], 0x0110u32.to_be_bytes(), "snap length");
+        assert_eq!(file[20..24], 1u32.to_be_bytes(), "link type");
+        assert_eq!(
+            file[24..40],
+            [
+                0x0A, 0x0B, 0x0C, 0x0D, // ts_sec
+                0x00, 0x01, 0x02, 0x03, // ts_usec
+                0x00, 0x00, 0x00, 0x04, // incl_len
+                0x00, 0x00, 0x00, 0x04, // orig_len
+            ],
+            "first packet header"
+        );
+
+        let mut reader = SyncPcapReader::new(file.as_slice())?;
+        assert_eq!(reader.file_header().endianness(), Endianness::BigEndian);
+        for (seconds, data) in packets {
+            let (header, read) = reader.next_packet()?.expect("packet");
+            assert_eq!(header.timestamp.seconds, seconds);
+            assert_eq!(header.timestamp.usec, 0x0001_0203);
+            assert_eq!(read, data);
+        }
+        assert!(reader.next_packet()?.is_none());
+
+        crate::test_helpers::do_files_match(actual, expected)?;
+        Ok(())
+    }
+
+    fn write_oversized(policy: TruncationPolicy) -> anyhow::Result<(PcapFileHeader, u32, u32)> {
+        let header = PcapFileHeader::builder().snap_length(64).build();
+        let mut writer =
+            SyncPcapWriter::new(Cursor::new(Vec::new()), header)?.with_truncation_policy(policy);
+        writer.write_header(NewPacketHeader::default(), &[0xAB; 100])?;
+        let file = writer.into_inner()?.into_inner();
+
+        let mut reader = SyncPcapReader::new(file.as_slice())?;
+        let file_header = *reader.file_header();
+        let (packet_header, data) = reader.next_packet()?.expect("one packet");
+        assert_eq!(packet_header.include_len as usize, data.len());
+        Ok((
+            file_header,
+            packet_header.include_len,
+            packet_header.orig_len,
+        ))
+    }
+
+    #[test]
+    fn test_truncation_policy_grow() -> anyhow::Result<()> {
+        let (file_header, include_len, orig_len) =
+            write_oversized(TruncationPolicy::GrowSnapLength)?;
+        assert_eq!(file_header.snap_length, 100);
+        assert_eq!(include_len, 100);
+        assert_eq!(orig_len, 100);
+        Ok(())
+    }
+
+    #[test]
+    fn test_truncation_policy_truncate() -> anyhow::Result<()> {
+        let (file_header, include_len, orig_len) =
+            write_oversized(TruncationPolicy::TruncateToSnap)?;
+        assert_eq!(file_header.snap_length, 64);
+        assert_eq!(include_len, 64);
+        assert_eq!(orig_len, 100);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bytes_written_matches_file_size() -> anyhow::Result<()> {
+        let path = crate::test_helpers::test_target_dir()?.join("sync_writer_bytes_written.pcap");
+        let mut writer = SyncPcapWriter::new(File::create(&path)?, PcapFileHeader::default())?;
+        assert_eq!(writer.bytes_written(), 24);
+        for i in 0..10u8 {
+            writer.write_header(NewPacketHeader::default(), &vec![i; 10 + i as usize])?;
+        }
+        assert_eq!(writer.packets_written(), 10);
+        let bytes_written = writer.bytes_written();
+        writer.finish()?;
+        assert_eq!(bytes_written, std::fs::metadata(&path)?.len());
+        Ok(())
+    }
+
+    #[test]
+    fn nonzero_timezone_round_trips() -> anyhow::Result<()> {
+        // -3600 as stored by some legacy writers
+        let timezone = (-3600i32) as u32;
+        let header = PcapFileHeader::builder()
+            .snap_length(4)
+            .timezone(timezone)
+            .sig_figs(6)
+            .build();
+        let mut writer = SyncPcapWriter::new(Cursor::new(Vec::new()), header)?;
+        // Growing the snap length rewrites the header
+        writer.write_header(NewPacketHeader::default(), &[0xAB; 8])?;
+        let file = writer.into_inner()?.into_inner();
+
+        let reader = SyncPcapReader::new(file.as_slice())?;
+        assert_eq!(reader.file_header().timezone, timezone);
+        assert_eq!(reader.file_header().sig_figs, 6);
+        assert_eq!(reader.file_header().snap_length, 8);
+        Ok(())
+    }
 }
@@ -0,0 +1,105 @@
+//! Writing packets built with `etherparse`
+//!
+//! Requires the `etherparse` feature.
+use std::io::{self, Seek, Write};
+
+use etherparse::{
+    Icmpv4Header, Icmpv6Header, PacketBuilderStep, TcpHeader, UdpHeader,
+    err::packet::BuildVecWriteError,
+};
+
+use super::{NewPacketHeader, SyncPcapWriter};
+use crate::pcap::packet_header::PacketTimestamp;
+
+/// A finished `etherparse` packet builder that can be passed to [`SyncPcapWriter::write_built`]
+///
+/// Implemented for the UDP, TCP, ICMPv4 and ICMPv6 steps of [`etherparse::PacketBuilder`]
+pub trait BuiltPacket {
+    /// Serializes the headers and `payload` to the end of `buffer`
+    fn write_to_vec(self, buffer: &mut Vec<u8>, payload: &[u8]) -> Result<(), BuildVecWriteError>;
+}
+macro_rules! impl_built_packet {
+    ($($header:ty),*) => {
+        $(
+            impl BuiltPacket for PacketBuilderStep<$header> {
+                fn write_to_vec(self, buffer: &mut Vec<u8>, payload: &[u8]) -> Result<(), BuildVecWriteError> {
+                    PacketBuilderStep::<$header>::write_to_vec(self, buffer, payload)
+                }
+            }
+        )*
+    };
+}
+impl_built_packet!(UdpHeader, TcpHeader, Icmpv4Header, Icmpv6Header);
+
+impl<W: Write + Seek> SyncPcapWriter<W> {
+    /// Serializes `builder` with `payload` and writes it as a packet
+    ///
+    /// The packet is built in a buffer owned by the writer and reused between calls.
+    /// Errors from `etherparse` are returned as [`io::ErrorKind::InvalidInput`]
+    pub fn write_built<B: BuiltPacket>(
+        &mut self,
+        timestamp: PacketTimestamp,
+        builder: B,
+        payload: &[u8],
+    ) -> Result<(), io::Error> {
+        let mut buffer = std::mem::take(&mut self.built_buffer);
+        buffer.clear();
+        let result = builder
+            .write_to_vec(&mut buffer, payload)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+            .and_then(|()| {
+                self.write_header(
+                    NewPacketHeader {
+                        timestamp,
+                        orig_len: None,
+                    },
+                    &buffer,
+                )
+            });
+        self.built_buffer = buffer;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use etherparse::PacketBuilder;
+
+    use super::*;
+    use crate::pcap::{file_header::PcapFileHeader, sync::SyncPcapReader};
+
+    #[test]
+    fn write_built_packets() -> anyhow::Result<()> {
+        let mut writer = SyncPcapWriter::new(Cursor::new(Vec::new()), PcapFileHeader::default())?;
+        let mut expected = Vec::with_capacity(100);
+        let builder = || {
+            PacketBuilder::ethernet2([1, 2, 3, 4, 5, 6], [7, 8, 9, 10, 11, 12])
+                .ipv4([192, 168, 1, 1], [192, 168, 1, 2], 20)
+                .udp(21, 1234)
+        };
+        for i in 0..100u32 {
+            let payload = i.to_be_bytes();
+            let mut packet = Vec::new();
+            builder().write(&mut packet, &payload)?;
+            let timestamp = PacketTimestamp {
+                seconds: i,
+                usec: 0,
+            };
+            writer.write_built(timestamp, builder(), &payload)?;
+            expected.push((timestamp, packet));
+        }
+        let file = writer.into_inner()?.into_inner();
+
+        let mut reader = SyncPcapReader::new(file.as_slice())?;
+        let mut expected = expected.into_iter();
+        while let Some((header, data)) = reader.next_packet()? {
+            let (timestamp, packet) = expected.next().expect("More packets than written");
+            assert_eq!(header.timestamp, timestamp);
+            assert_eq!(data, packet.as_slice());
+        }
+        assert!(expected.next().is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+//! Pcap writer that splits output across multiple files
+use std::{
+    fs::File,
+    io::{self, BufWriter},
+    path::PathBuf,
+};
+
+use crate::pcap::{
+    file_header::PcapFileHeader,
+    sync::writer::{NewPacketHeader, SyncPcapWriter},
+};
+
+/// When a [`RotatingPcapWriter`] should start a new file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RotationPolicy {
+    /// Start a new file once the current file holds this many packets
+    MaxPackets(u64),
+    /// Start a new file if writing the next packet would make the current file
+    /// larger than this many bytes (including the file header)
+    ///
+    /// A single packet larger than the limit is still written to its own file.
+    MaxBytes(u64),
+}
+/// Writes packets to a series of pcap files, rotating to a new file based on a [`RotationPolicy`]
+///
+/// Files are named from a path template where `{}` is replaced with the index of
+/// the file, starting at 0. (e.g. `capture-{}.pcap` produces `capture-0.pcap`, `capture-1.pcap`, ...)
+///
+/// Every file starts with its own copy of the file header.
+pub struct RotatingPcapWriter {
+    template: String,
+    header: PcapFileHeader,
+    policy: RotationPolicy,
+    writer: SyncPcapWriter<BufWriter<File>>,
+    files: Vec<PathBuf>,
+}
+impl RotatingPcapWriter {
+    /// Creates the first file and writes its header
+    ///
+    /// Returns an [`io::ErrorKind::InvalidInput`] error if `template` does not contain `{}`
+    pub fn new(
+        template: impl Into<String>,
+        header: PcapFileHeader,
+        policy: RotationPolicy,
+    ) -> Result<Self, io::Error> {
+        let template = template.into();
+        if !template.contains("{}") {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Path template must contain `{}`",
+            ));
+        }
+        let path = PathBuf::from(template.replace("{}", "0"));
+        let writer = SyncPcapWriter::new(BufWriter::new(File::create(&path)?), header)?;
+        Ok(Self {
+            template,
+            header,
+            policy,
+            writer,
+            files: vec![path],
+        })
+    }
+    /// Writes a packet, rotating to a new file first if required by the policy
+    pub fn write_header(
+        &mut self,
+        header: NewPacketHeader,
+        content: &[u8],
+    ) -> Result<(), io::Error> {
+        if self.should_rotate(content.len()) {
+            self.rotate()?;
+        }
+        self.writer.write_header(header, content)
+    }
+    fn should_rotate(&self, content_length: usize) -> bool {
+        if self.writer.packets_written() == 0 {
+            return false;
+        }
+        match self.policy {
+            RotationPolicy::MaxPackets(max) => self.writer.packets_written() >= max,
+            RotationPolicy::MaxBytes(max) => {
+                let packet_size = self.header.resolution().packet_header_length() + content_length;
+                self.writer.bytes_written() + packet_size as u64 > max
+            }
+        }
+    }
+    /// Finishes the current file and starts the next one
+    pub fn rotate(&mut self) -> Result<(), io::Error> {
+        let path = PathBuf::from(self.template.replace("{}", &self.files.len().to_string()));
+        let writer = SyncPcapWriter::new(BufWriter::new(File::create(&path)?), self.header)?;
+        std::mem::replace(&mut self.writer, writer).finish()?;
+        self.files.push(path);
+        Ok(())
+    }
+    /// Paths of every file created so far, in order
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+    /// The writer for the current file
+    pub fn current_writer(&self) -> &SyncPcapWriter<BufWriter<File>> {
+        &self.writer
+    }
+    /// Finishes the current file and returns the paths of every file written
+    pub fn finish(self) -> Result<Vec<PathBuf>, io::Error> {
+        self.writer.finish()?;
+        Ok(self.files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+    use crate::pcap::sync::SyncPcapReader;
+
+    fn count_packets(path: &PathBuf) -> anyhow::Result<usize> {
+        let mut reader = SyncPcapReader::new(File::open(path)?)?;
+        let mut count = 0;
+        while reader.next_packet()?.is_some() {
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    #[test]
+    fn rotate_by_packets() -> anyhow::Result<()> {
+        let template = crate::test_helpers::test_target_dir()?.join("rotating_packets-{}.pcap");
+        let mut writer = RotatingPcapWriter::new(
+            template.to_string_lossy(),
+            PcapFileHeader::default(),
+            RotationPolicy::MaxPackets(4),
+        )?;
+        for i in 0..10u8 {
+            writer.write_header(NewPacketHeader::default(), &[i; 20])?;
+        }
+        let files = writer.finish()?;
+        assert_eq!(files.len(), 3);
+        let counts = files
+            .iter()
+            .map(count_packets)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        assert_eq!(counts, vec![4, 4, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn rotate_by_bytes() -> anyhow::Result<()> {
+        let template = crate::test_helpers::test_target_dir()?.join("rotating_bytes-{}.pcap");
+        // Header (24) + 3 packets of (16 + 20) bytes
+        let max_bytes = 24 + 3 * 36;
+        let mut writer = RotatingPcapWriter::new(
+            template.to_string_lossy(),
+            PcapFileHeader::default(),
+            RotationPolicy::MaxBytes(max_bytes),
+        )?;
+        for i in 0..7u8 {
+            writer.write_header(NewPacketHeader::default(), &[i; 20])?;
+        }
+        let files = writer.finish()?;
+        assert_eq!(files.len(), 3);
+        for file in &files {
+            assert!(std::fs::metadata(file)?.len() <= max_bytes);
+        }
+        let counts = files
+            .iter()
+            .map(count_packets)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        assert_eq!(counts, vec![3, 3, 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn template_requires_placeholder() {
+        let result = RotatingPcapWriter::new(
+            "no-placeholder.pcap",
+            PcapFileHeader::default(),
+            RotationPolicy::MaxPackets(1),
+        );
+        assert!(matches!(result, Err(err) if err.kind() == io::ErrorKind::InvalidInput));
+    }
+}
@@ -2,7 +2,9 @@
 use std::io::{self, Write};
 
 use crate::pcap::{
-    file_header::PcapFileHeader, packet_header::PacketHeader, sync::writer::NewPacketHeader,
+    file_header::{MagicNumber, PcapFileHeader},
+    packet_header::{ExtendedPacketHeader, PacketHeader},
+    sync::writer::NewPacketHeader,
 };
 
 /// A Sync Pcap Writer that does not require Seek
@@ -11,14 +13,24 @@ use crate::pcap::{
 pub struct SeeklessPcapWriter<W: Write> {
     target: W,
     header: PcapFileHeader,
+    /// Total bytes written including the file header
+    bytes_written: u64,
+    packets_written: u64,
 }
 
 impl<W: Write> SeeklessPcapWriter<W> {
     /// Creates a new writer and immediately writes the file header to
     /// `target`.
+    ///
+    /// The header is written as given, including its `timezone` and `sig_figs`.
     pub fn new(mut target: W, header: PcapFileHeader) -> Result<Self, io::Error> {
         header.write(&mut target)?;
-        Ok(Self { target, header })
+        Ok(Self {
+            target,
+            header,
+            bytes_written: PcapFileHeader::SIZE as u64,
+            packets_written: 0,
+        })
     }
 
     /// Writes a packet to the target.
@@ -35,6 +47,8 @@ impl<W: Write> SeeklessPcapWriter<W> {
             timestamp: header.timestamp,
             include_len: content.len() as u32,
             orig_len: header.orig_len.unwrap_or(content.len() as u32),
+            extended: (self.header.resolution() == MagicNumber::Modified)
+                .then(ExtendedPacketHeader::default),
         };
         if new_header.include_len > self.header.snap_length {
             return Err(io::Error::new(
@@ -45,22 +59,38 @@ impl<W: Write> SeeklessPcapWriter<W> {
 
         new_header.write(
             &mut self.target,
-            self.header.magic_number_and_endianness.endianness,
+            self.header.endianness(),
             &self.header.version,
         )?;
         self.target.write_all(content)?;
+        self.bytes_written +=
+            (self.header.resolution().packet_header_length() + content.len()) as u64;
+        self.packets_written += 1;
         Ok(())
     }
+    /// Total number of bytes written to the target, including the file header
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+    /// Number of packets written to the target
+    pub fn packets_written(&self) -> u64 {
+        self.packets_written
+    }
     /// Flushes the target writer.
-    pub fn finish(mut self) -> Result<(), io::Error> {
-        self.target.flush()?;
+    pub fn finish(self) -> Result<(), io::Error> {
+        self.into_inner()?;
         Ok(())
     }
+    /// Flushes the target writer and returns it
+    pub fn into_inner(mut self) -> Result<W, io::Error> {
+        self.target.flush()?;
+        Ok(self.target)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{fs::File, time::Duration};
+    use std::{fs::File, io::Cursor, time::Duration};
 
     use chrono::{TimeZone, Utc};
     use etherparse::PacketBuilder;
@@ -73,7 +103,7 @@ mod tests {
             packet_header::PacketTimestamp,
             sync::{
                 SyncPcapReader,
-                writer::{NewPacketHeader, SyncPcapWriter},
+                writer::{NewPacketHeader, SyncPcapWriter, seekless::SeeklessPcapWriter},
             },
         },
     };
@@ -149,4 +179,35 @@ mod tests {
         crate::test_helpers::do_files_match(actual, expected)?;
         Ok(())
     }
+
+    #[test]
+    fn test_bytes_written_matches_file_size() -> anyhow::Result<()> {
+        let path =
+            crate::test_helpers::test_target_dir()?.join("seekless_writer_bytes_written.pcap");
+        let mut writer = SeeklessPcapWriter::new(File::create(&path)?, PcapFileHeader::default())?;
+        assert_eq!(writer.bytes_written(), 24);
+        for i in 0..10u8 {
+            writer.write_header(NewPacketHeader::default(), &vec![i; 10 + i as usize])?;
+        }
+        assert_eq!(writer.packets_written(), 10);
+        let bytes_written = writer.bytes_written();
+        writer.finish()?;
+        assert_eq!(bytes_written, std::fs::metadata(&path)?.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_into_inner() -> anyhow::Result<()> {
+        let mut writer =
+            SeeklessPcapWriter::new(Cursor::new(Vec::new()), PcapFileHeader::default())?;
+        writer.write_header(NewPacketHeader::default(), &[1, 2, 3, 4])?;
+        let file = writer.into_inner()?.into_inner();
+        assert_eq!(file.len(), 24 + 16 + 4);
+
+        let mut reader = SyncPcapReader::new(file.as_slice())?;
+        let (_, data) = reader.next_packet()?.expect("one packet");
+        assert_eq!(data, &[1, 2, 3, 4]);
+        assert!(reader.next_packet()?.is_none());
+        Ok(())
+    }
 }
@@ -2,7 +2,10 @@
 //!
 //! Requires the `tokio-async` feature
 mod reader;
-pub use reader::AsyncPcapReader;
+pub use reader::{AsyncPcapReader, SpawnedPacket};
+
+mod writer;
+pub use writer::AsyncPcapWriter;
 
 mod pooled_reader;
 pub use pooled_reader::AsyncPooledPcapReader;
@@ -3,7 +3,12 @@ use std::num::NonZeroUsize;
 
 use crate::{
     Version,
-    pcap::{PcapParseError, file_header::PcapFileHeader, packet_header::PacketHeader},
+    byte_order::tokio_async::AsyncReadExt as _,
+    pcap::{
+        PcapParseError,
+        file_header::{MagicNumber, PcapFileHeader},
+        packet_header::{ExtendedPacketHeader, PacketHeader},
+    },
 };
 use tokio::io::{AsyncRead, AsyncReadExt, BufReader};
 
@@ -36,6 +41,7 @@ impl<R: AsyncRead + Unpin> AsyncPooledPcapReader<BufReader<R>> {
         let mut file_header = [0u8; 24];
         reader.read_exact(&mut file_header).await?;
         let file_header = PcapFileHeader::try_from(&file_header)?;
+        file_header.validate_snap_length()?;
         let pool = BufferPool::new(pool_size, file_header.snap_length);
         let reader = BufReader::with_capacity(file_header.snap_length as usize + 16, reader);
         Ok(Self {
@@ -54,6 +60,7 @@ impl<R: AsyncRead + Unpin> AsyncPooledPcapReader<BufReader<R>> {
         let mut file_header = [0u8; 24];
         reader.read_exact(&mut file_header).await?;
         let file_header = PcapFileHeader::try_from(&file_header)?;
+        file_header.validate_snap_length()?;
         let pool = BufferPool::new(pool_size, file_header.snap_length);
         Ok(Self {
             reader,
@@ -82,6 +89,7 @@ impl<R: AsyncRead + Unpin> AsyncPooledPcapReader<R> {
         let mut file_header = [0u8; 24];
         reader.read_exact(&mut file_header).await?;
         let file_header = PcapFileHeader::try_from(&file_header)?;
+        file_header.validate_snap_length()?;
         let pool = BufferPool::new(pool_size, file_header.snap_length);
         Ok(Self {
             reader,
@@ -95,6 +103,13 @@ impl<R: AsyncRead + Unpin> AsyncPooledPcapReader<R> {
     pub fn file_header(&self) -> &PcapFileHeader {
         &self.file_header
     }
+    /// Consumes the reader and returns the underlying source
+    ///
+    /// The source is positioned after the last packet read. Readers created with `new` wrap the source
+    /// in a [`BufReader`] which may hold bytes that have been read from the source but not parsed yet
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
 
     /// Returns the version of the pcap file.
     pub fn version(&self) -> &Version {
@@ -123,11 +138,18 @@ impl<R: AsyncRead + Unpin> AsyncPooledPcapReader<R> {
             }
         }
 
-        let packet_header = PacketHeader::parse_bytes(
+        let mut packet_header = PacketHeader::parse_bytes(
             &self.header_buffer,
-            self.file_header.magic_number_and_endianness.endianness,
+            self.file_header.endianness(),
             &self.file_header.version,
         )?;
+        if self.file_header.resolution() == MagicNumber::Modified {
+            let extended = self.reader.read_bytes::<8>().await?;
+            packet_header.extended = Some(ExtendedPacketHeader::parse_bytes(
+                &extended,
+                self.file_header.endianness(),
+            ));
+        }
 
         if packet_header.include_len > self.file_header.snap_length {
             return Err(PcapParseError::InvalidPacketLength {
@@ -1,9 +1,17 @@
 //! Asynchronous reader for PCAP files
 use crate::{
     Version,
-    pcap::{PcapParseError, file_header::PcapFileHeader, packet_header::PacketHeader},
+    byte_order::tokio_async::AsyncReadExt as _,
+    pcap::{
+        PcapParseError,
+        file_header::{MagicNumber, PcapFileHeader},
+        packet_header::{ExtendedPacketHeader, PacketHeader},
+    },
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, BufReader},
+    sync::mpsc,
 };
-use tokio::io::{AsyncRead, AsyncReadExt, BufReader};
 #[derive(Debug)]
 pub struct AsyncPcapReader<R: AsyncRead + Unpin> {
     reader: R,
@@ -23,6 +31,7 @@ impl<R: AsyncRead + Unpin> AsyncPcapReader<BufReader<R>> {
         let mut file_header = [0u8; 24];
         reader.read_exact(&mut file_header).await?;
         let file_header = PcapFileHeader::try_from(&file_header)?;
+        file_header.validate_snap_length()?;
         let buffer = vec![0u8; file_header.snap_length as usize].into_boxed_slice();
         let reader = BufReader::with_capacity(file_header.snap_length as usize + 16, reader);
         Ok(Self {
@@ -39,6 +48,7 @@ impl<R: AsyncRead + Unpin> AsyncPcapReader<BufReader<R>> {
         let mut file_header = [0u8; 24];
         reader.read_exact(&mut file_header).await?;
         let file_header = PcapFileHeader::try_from(&file_header)?;
+        file_header.validate_snap_length()?;
         let buffer = vec![0u8; file_header.snap_length as usize].into_boxed_slice();
         Ok(Self {
             reader,
@@ -60,6 +70,7 @@ impl<R: AsyncRead + Unpin> AsyncPcapReader<R> {
         let mut file_header = [0u8; 24];
         reader.read_exact(&mut file_header).await?;
         let file_header = PcapFileHeader::try_from(&file_header)?;
+        file_header.validate_snap_length()?;
         let buffer = vec![0u8; file_header.snap_length as usize].into_boxed_slice();
         Ok(Self {
             reader,
@@ -82,6 +93,13 @@ impl<R: AsyncRead + Unpin> AsyncPcapReader<R> {
     pub fn file_header(&self) -> &PcapFileHeader {
         &self.file_header
     }
+    /// Consumes the reader and returns the underlying source
+    ///
+    /// The source is positioned after the last packet read. Readers created with `new` wrap the source
+    /// in a [`BufReader`] which may hold bytes that have been read from the source but not parsed yet
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
     /// Returns the version of the pcap file
     pub fn version(&self) -> &Version {
         &self.file_header.version
@@ -99,11 +117,18 @@ impl<R: AsyncRead + Unpin> AsyncPcapReader<R> {
                 return Err(PcapParseError::IO(err));
             }
         }
-        let packet_header = PacketHeader::parse_bytes(
+        let mut packet_header = PacketHeader::parse_bytes(
             &self.header_buffer,
-            self.file_header.magic_number_and_endianness.endianness,
+            self.file_header.endianness(),
             &self.file_header.version,
         )?;
+        if self.file_header.resolution() == MagicNumber::Modified {
+            let extended = self.reader.read_bytes::<8>().await?;
+            packet_header.extended = Some(ExtendedPacketHeader::parse_bytes(
+                &extended,
+                self.file_header.endianness(),
+            ));
+        }
         // Check if the included length is greater than the snap length
         // This is a sanity check to prevent reading more data than allocated
         if packet_header.include_len > self.file_header.snap_length {
@@ -123,12 +148,41 @@ impl<R: AsyncRead + Unpin> AsyncPcapReader<R> {
         )))
     }
 }
+/// A packet sent by [`AsyncPcapReader::spawn_reader`]
+pub type SpawnedPacket = Result<(PacketHeader, Vec<u8>), PcapParseError>;
+impl<R: AsyncRead + Unpin + Send + 'static> AsyncPcapReader<R> {
+    /// Moves the reader into a tokio task that sends every packet into a channel holding up to `buffer` packets
+    ///
+    /// Reading continues while the receiver keeps up, decoupling I/O from processing. Each packet is copied into its own `Vec`.
+    /// The channel closes at the end of the file or after the first error, which is sent before closing.
+    /// The task stops early if the receiver is dropped.
+    ///
+    /// Must be called from within a tokio runtime. Panics if `buffer` is 0
+    pub fn spawn_reader(mut self, buffer: usize) -> mpsc::Receiver<SpawnedPacket> {
+        let (sender, receiver) = mpsc::channel(buffer);
+        tokio::spawn(async move {
+            loop {
+                let packet = match self.next_packet().await {
+                    Ok(Some((header, data))) => Ok((header, data.to_vec())),
+                    Ok(None) => break,
+                    Err(err) => Err(err),
+                };
+                let failed = packet.is_err();
+                if sender.send(packet).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+        receiver
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use etherparse::{NetSlice, SlicedPacket};
 
     use super::*;
+    use crate::pcap::SyncPcapReader;
     #[tokio::test]
     async fn read_packets_from_file() {
         let file = tokio::fs::File::open("test_data/test.pcap")
@@ -160,4 +214,34 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn spawn_reader_sends_every_packet() -> anyhow::Result<()> {
+        let file = std::fs::read("test_data/test.pcap")?;
+        let expected = SyncPcapReader::new(file.as_slice())?.read_all()?;
+
+        let reader = AsyncPcapReader::new(std::io::Cursor::new(file)).await?;
+        let mut receiver = reader.spawn_reader(4);
+        let mut packets = Vec::new();
+        while let Some(packet) = receiver.recv().await {
+            packets.push(packet?);
+        }
+        assert_eq!(packets.len(), expected.len());
+        assert_eq!(packets, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn spawn_reader_sends_error() -> anyhow::Result<()> {
+        let mut file = std::fs::read("test_data/test.pcap")?;
+        file.truncate(file.len() - 1);
+        let reader = AsyncPcapReader::new(std::io::Cursor::new(file)).await?;
+        let mut receiver = reader.spawn_reader(1);
+        let mut last = None;
+        while let Some(packet) = receiver.recv().await {
+            last = Some(packet);
+        }
+        assert!(matches!(last, Some(Err(PcapParseError::IO(_)))));
+        Ok(())
+    }
 }
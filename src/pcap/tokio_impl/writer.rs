@@ -0,0 +1,137 @@
+//! Asynchronous writer for PCAP files
+use std::io;
+
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::pcap::{
+    file_header::{MagicNumber, PcapFileHeader},
+    packet_header::{ExtendedPacketHeader, PacketHeader},
+    writer::NewPacketHeader,
+};
+
+/// An Async Pcap Writer
+///
+/// Like [`SeeklessPcapWriter`](crate::pcap::writer::seekless::SeeklessPcapWriter) the file header
+/// is never rewritten so packets larger than the snap length return an error.
+pub struct AsyncPcapWriter<W: AsyncWrite + Unpin> {
+    target: W,
+    header: PcapFileHeader,
+    /// Reused buffer for serializing packet headers
+    header_buffer: Vec<u8>,
+    /// Total bytes written including the file header
+    bytes_written: u64,
+    packets_written: u64,
+}
+impl<W: AsyncWrite + Unpin> AsyncPcapWriter<W> {
+    /// Creates a new writer and immediately writes the file header to
+    /// `target`.
+    ///
+    /// The header is written as given, including its `timezone` and `sig_figs`.
+    pub async fn new(mut target: W, header: PcapFileHeader) -> Result<Self, io::Error> {
+        target.write_all(&header.to_bytes()).await?;
+        Ok(Self {
+            target,
+            header,
+            header_buffer: Vec::with_capacity(24),
+            bytes_written: PcapFileHeader::SIZE as u64,
+            packets_written: 0,
+        })
+    }
+    /// Returns the file header that was written
+    pub fn file_header(&self) -> &PcapFileHeader {
+        &self.header
+    }
+    /// Writes a packet to the target.
+    ///
+    /// Returns an [`io::ErrorKind::InvalidInput`] error if `content.len()`
+    /// exceeds the configured `snap_length`.
+    pub async fn write_header(
+        &mut self,
+        header: NewPacketHeader,
+        content: &[u8],
+    ) -> Result<(), io::Error> {
+        let new_header = PacketHeader {
+            timestamp: header.timestamp,
+            include_len: content.len() as u32,
+            orig_len: header.orig_len.unwrap_or(content.len() as u32),
+            extended: (self.header.resolution() == MagicNumber::Modified)
+                .then(ExtendedPacketHeader::default),
+        };
+        if new_header.include_len > self.header.snap_length {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Packet length exceeds snap length",
+            ));
+        }
+        self.header_buffer.clear();
+        new_header.write(
+            &mut self.header_buffer,
+            self.header.endianness(),
+            &self.header.version,
+        )?;
+        self.target.write_all(&self.header_buffer).await?;
+        self.target.write_all(content).await?;
+        self.bytes_written += (self.header_buffer.len() + content.len()) as u64;
+        self.packets_written += 1;
+        Ok(())
+    }
+    /// Total number of bytes written to the target, including the file header
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+    /// Number of packets written to the target
+    pub fn packets_written(&self) -> u64 {
+        self.packets_written
+    }
+    /// Flushes the target writer.
+    pub async fn finish(mut self) -> Result<(), io::Error> {
+        self.target.flush().await?;
+        Ok(())
+    }
+    /// Consumes the writer and returns the underlying target
+    pub fn into_inner(self) -> W {
+        self.target
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pcap::{AsyncPcapReader, packet_header::PacketTimestamp};
+
+    #[tokio::test]
+    async fn write_read_round_trip() -> anyhow::Result<()> {
+        let header = PcapFileHeader::builder().snap_length(8).build();
+        let mut writer = AsyncPcapWriter::new(Vec::new(), header).await?;
+        let timestamp = PacketTimestamp {
+            seconds: 5,
+            usec: 10,
+        };
+        writer
+            .write_header(
+                NewPacketHeader {
+                    timestamp,
+                    orig_len: Some(60),
+                },
+                &[1, 2, 3],
+            )
+            .await?;
+        let err = writer
+            .write_header(NewPacketHeader::default(), &[0; 9])
+            .await
+            .expect_err("packet is larger than the snap length");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(writer.packets_written(), 1);
+        assert_eq!(writer.bytes_written(), 24 + 16 + 3);
+        let file = writer.into_inner();
+        assert_eq!(file.len(), 24 + 16 + 3);
+
+        let mut reader = AsyncPcapReader::new(file.as_slice()).await?;
+        let (packet_header, data) = reader.next_packet().await?.expect("one packet");
+        assert_eq!(packet_header.timestamp, timestamp);
+        assert_eq!(packet_header.orig_len, 60);
+        assert_eq!(data, &[1, 2, 3]);
+        assert!(reader.next_packet().await?.is_none());
+        Ok(())
+    }
+}
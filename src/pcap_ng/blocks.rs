@@ -1,9 +1,14 @@
 //! Block Types for pcap-ng files
-use std::io::{Read, Write};
+use alloc::{vec, vec::Vec};
 
 use crate::{
     byte_order::{ByteOrder, Endianness, ReadExt, UnexpectedSize},
-    pcap_ng::PcapNgParseError,
+    io::{Read, Write},
+    pcap_ng::{
+        PcapNgParseError,
+        options::{BlockOptions, OptionParseError},
+        padding::pad_to_32,
+    },
 };
 
 mod custom;
@@ -13,19 +18,27 @@ mod generic;
 mod header;
 mod interface;
 mod interface_statistics;
+mod legacy_packet;
 mod name_resolution;
 mod simple_packet;
 pub use custom::{
     CUSTOM_BLOCK_COPYABLE, CUSTOM_BLOCK_DO_NOT_COPY, CustomBlock, is_custom_block_id,
 };
 pub use decryption_secrets::DecryptionSecretsBlock;
-pub use enhanced_packet::EnhancedPacket;
+#[cfg(feature = "std")]
+pub(crate) use enhanced_packet::apply_ts_offset;
+pub(crate) use enhanced_packet::timestamp_from_raw;
+pub use enhanced_packet::{
+    EnhancedPacket, EnhancedPacketOptionCodes, EpbFlags, HashAlgorithm, PacketDirection,
+    ReceptionType,
+};
 
 pub use generic::GenericBlock;
 pub use header::{SHBOptionCodes, SectionHeaderBlock};
-pub use interface::{InterfaceDescriptionBlock, InterfaceOptionCodes};
+pub use interface::{InterfaceDescriptionBlock, InterfaceOptionCodes, TsResolution};
 pub use interface_statistics::{ISBOptionCodes, InterfaceStatisticsBlock};
-pub use name_resolution::NameResolutionBlock;
+pub use legacy_packet::LegacyPacketBlock;
+pub use name_resolution::{NameResolutionBlock, Record, Records, ResolvedName};
 pub use simple_packet::SimplePacket;
 /// Common interface for pcap-ng block types.
 ///
@@ -86,6 +99,16 @@ pub trait Block<'b> {
     {
         Self::read_with_header(reader, header, Some(byte_order), packet_buffer)
     }
+    /// Writes the complete block, including the block header and trailing length, in `byte_order`
+    ///
+    /// `block_length` and any other length fields are ignored and computed from the data being written.
+    fn write<W: Write>(
+        &self,
+        writer: &mut W,
+        byte_order: Endianness,
+    ) -> Result<(), crate::io::Error>
+    where
+        Self: Sized;
 }
 #[cfg(feature = "tokio-async")]
 mod tokio_block {
@@ -99,7 +122,8 @@ mod tokio_block {
             blocks::{
                 Block, BlockHeader, CustomBlock, DecryptionSecretsBlock, EnhancedPacket,
                 GenericBlock, InterfaceDescriptionBlock, InterfaceStatisticsBlock,
-                NameResolutionBlock, PcapNgBlock, SectionHeaderBlock, SimplePacket,
+                LegacyPacketBlock, NameResolutionBlock, PcapNgBlock, SectionHeaderBlock,
+                SimplePacket,
             },
         },
     };
@@ -125,6 +149,7 @@ mod tokio_block {
                 let determined_byte_order = byte_order
                     .or(header.endianness_from_block::<Self>())
                     .ok_or(UndertminedByteOrder)?;
+                header.check_minimum_size::<Self>(determined_byte_order)?;
                 let block_length = header.block_length_as_u32(determined_byte_order) as usize - 8;
                 let mut content = vec![0u8; block_length];
                 reader.read_exact(&mut content).await?;
@@ -136,6 +161,9 @@ mod tokio_block {
     impl<'b> PcapNgBlock<'b> {
         /// Async counterpart to [`PcapNgBlock::read`]: dispatches on the
         /// block ID and reads the appropriate variant from `reader`.
+        ///
+        /// Returns [`PcapNgParseError::MinimumSizeNotMet`] if the block length is less than [`Block::minimum_size`]
+        /// of the block type.
         pub async fn read_async<R: AsyncRead + Unpin>(
             reader: &mut R,
             header: &BlockHeader,
@@ -153,60 +181,90 @@ mod tokio_block {
                     )
                     .await?,
                 )),
-                1 => Ok(PcapNgBlock::InterfaceDescription(
-                    InterfaceDescriptionBlock::async_read_with_header(
-                        reader,
-                        header,
-                        Some(byte_order),
-                        packet_buffer,
-                    )
-                    .await?,
-                )),
-                3 => Ok(PcapNgBlock::SimplePacket(
-                    SimplePacket::async_read_with_header(
-                        reader,
-                        header,
-                        Some(byte_order),
-                        packet_buffer,
-                    )
-                    .await?,
-                )),
-                4 => Ok(PcapNgBlock::NameResolution(
-                    NameResolutionBlock::async_read_with_header(
-                        reader,
-                        header,
-                        Some(byte_order),
-                        packet_buffer,
-                    )
-                    .await?,
-                )),
-                6 => Ok(PcapNgBlock::EnhancedPacket(
-                    EnhancedPacket::async_read_with_header(
-                        reader,
-                        header,
-                        Some(byte_order),
-                        packet_buffer,
-                    )
-                    .await?,
-                )),
-                5 => Ok(PcapNgBlock::InterfaceStatistics(
-                    InterfaceStatisticsBlock::async_read_with_header(
-                        reader,
-                        header,
-                        Some(byte_order),
-                        packet_buffer,
-                    )
-                    .await?,
-                )),
-                0x0000_000A => Ok(PcapNgBlock::DecryptionSecrets(
-                    DecryptionSecretsBlock::async_read_with_header(
-                        reader,
-                        header,
-                        Some(byte_order),
-                        packet_buffer,
-                    )
-                    .await?,
-                )),
+                1 => {
+                    header.check_minimum_size::<InterfaceDescriptionBlock>(byte_order)?;
+                    Ok(PcapNgBlock::InterfaceDescription(
+                        InterfaceDescriptionBlock::async_read_with_header(
+                            reader,
+                            header,
+                            Some(byte_order),
+                            packet_buffer,
+                        )
+                        .await?,
+                    ))
+                }
+                3 => {
+                    header.check_minimum_size::<SimplePacket>(byte_order)?;
+                    Ok(PcapNgBlock::SimplePacket(
+                        SimplePacket::async_read_with_header(
+                            reader,
+                            header,
+                            Some(byte_order),
+                            packet_buffer,
+                        )
+                        .await?,
+                    ))
+                }
+                4 => {
+                    header.check_minimum_size::<NameResolutionBlock>(byte_order)?;
+                    Ok(PcapNgBlock::NameResolution(
+                        NameResolutionBlock::async_read_with_header(
+                            reader,
+                            header,
+                            Some(byte_order),
+                            packet_buffer,
+                        )
+                        .await?,
+                    ))
+                }
+                6 => {
+                    header.check_minimum_size::<EnhancedPacket>(byte_order)?;
+                    Ok(PcapNgBlock::EnhancedPacket(
+                        EnhancedPacket::async_read_with_header(
+                            reader,
+                            header,
+                            Some(byte_order),
+                            packet_buffer,
+                        )
+                        .await?,
+                    ))
+                }
+                5 => {
+                    header.check_minimum_size::<InterfaceStatisticsBlock>(byte_order)?;
+                    Ok(PcapNgBlock::InterfaceStatistics(
+                        InterfaceStatisticsBlock::async_read_with_header(
+                            reader,
+                            header,
+                            Some(byte_order),
+                            packet_buffer,
+                        )
+                        .await?,
+                    ))
+                }
+                0x0000_000A => {
+                    header.check_minimum_size::<DecryptionSecretsBlock>(byte_order)?;
+                    Ok(PcapNgBlock::DecryptionSecrets(
+                        DecryptionSecretsBlock::async_read_with_header(
+                            reader,
+                            header,
+                            Some(byte_order),
+                            packet_buffer,
+                        )
+                        .await?,
+                    ))
+                }
+                2 => {
+                    header.check_minimum_size::<LegacyPacketBlock>(byte_order)?;
+                    Ok(PcapNgBlock::LegacyPacket(
+                        LegacyPacketBlock::async_read_with_header(
+                            reader,
+                            header,
+                            Some(byte_order),
+                            packet_buffer,
+                        )
+                        .await?,
+                    ))
+                }
                 id if super::custom::is_custom_block_id(id) => Ok(PcapNgBlock::Custom(
                     CustomBlock::async_read_with_header_no_block_check(reader, header, byte_order)
                         .await?,
@@ -278,13 +336,29 @@ impl BlockHeader {
         }
         Ok(())
     }
+    /// Checks that the block length is at least [`Block::minimum_size`] of the given block type
+    ///
+    /// Readers subtract the fixed fields from the block length, so a shorter block would underflow.
+    pub(crate) fn check_minimum_size<'b, B: Block<'b>>(
+        &self,
+        byte_order: Endianness,
+    ) -> Result<(), PcapNgParseError> {
+        let block_length = self.block_length_as_u32(byte_order) as usize;
+        if block_length < B::minimum_size() {
+            return Err(PcapNgParseError::MinimumSizeNotMet(
+                B::minimum_size(),
+                block_length,
+            ));
+        }
+        Ok(())
+    }
     /// Will panic if the block ID does not match the expected block ID for the given block type
     pub(crate) fn endianness_from_block<'b, B: Block<'b>>(&self) -> Option<Endianness> {
         debug_assert_ne!(
             B::block_id_be(),
             B::block_id_le(),
             "Unable to determine endianness for {}",
-            std::any::type_name::<B>()
+            core::any::type_name::<B>()
         );
         if self.block_id == B::block_id_le() {
             Some(Endianness::LittleEndian)
@@ -294,13 +368,81 @@ impl BlockHeader {
             None
         }
     }
+    /// Checks the trailing block length read after the block body against the block length in this header
+    ///
+    /// A mismatch means the body was not parsed to exactly its end.
+    pub(crate) fn check_trailing_length(
+        &self,
+        trailing_length: [u8; 4],
+    ) -> Result<(), PcapNgParseError> {
+        if trailing_length != self.block_length {
+            return Err(PcapNgParseError::TrailingLengthMismatch {
+                block_length: self.block_length,
+                trailing_length,
+            });
+        }
+        Ok(())
+    }
     /// Writes the 8-byte block header to the writer.
-    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), std::io::Error> {
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), crate::io::Error> {
         writer.write_all(&self.block_id)?;
         writer.write_all(&self.block_length)?;
         Ok(())
     }
 }
+/// Returns the block at the start of `bytes` without its block header for the `parse_from_slice` functions
+///
+/// The returned slice ends after the trailing block length.
+pub(crate) fn block_body_from_slice<'a, 'b, B: Block<'b>>(
+    bytes: &'a [u8],
+    block_length: u32,
+) -> Result<&'a [u8], PcapNgParseError> {
+    block_body_with_minimum_size(bytes, block_length, B::minimum_size())
+}
+/// Like [`block_body_from_slice`] for block types that do not implement [`Block`]
+pub(crate) fn block_body_with_minimum_size(
+    bytes: &[u8],
+    block_length: u32,
+    minimum_size: usize,
+) -> Result<&[u8], PcapNgParseError> {
+    let block_length = block_length as usize;
+    if block_length < minimum_size {
+        return Err(PcapNgParseError::MinimumSizeNotMet(
+            minimum_size,
+            block_length,
+        ));
+    }
+    bytes
+        .get(8..block_length)
+        .ok_or(PcapNgParseError::UnexpectedSize(UnexpectedSize {
+            name: "Block",
+            expected: block_length,
+            got: bytes.len(),
+        }))
+}
+/// Writes a complete block: the block header, the body and the trailing block length
+///
+/// The body is buffered by `write_body` so the block length is known before the header is written.
+/// The body is padded to a multiple of 4 bytes.
+pub(crate) fn write_block<W, F>(
+    writer: &mut W,
+    block_id: u32,
+    byte_order: Endianness,
+    write_body: F,
+) -> Result<(), crate::io::Error>
+where
+    W: Write,
+    F: FnOnce(&mut Vec<u8>) -> Result<(), crate::io::Error>,
+{
+    let mut body = Vec::new();
+    write_body(&mut body)?;
+    body.resize(pad_to_32(body.len()), 0);
+    let block_length = byte_order.u32_to_bytes((body.len() + 12) as u32);
+    BlockHeader::new(byte_order.u32_to_bytes(block_id), block_length).write(writer)?;
+    writer.write_all(&body)?;
+    writer.write_all(&block_length)?;
+    Ok(())
+}
 /// One of the recognized pcap-ng block types, plus a fallback for any block
 /// type the library does not parse natively.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -321,12 +463,122 @@ pub enum PcapNgBlock<'b> {
     Custom(CustomBlock),
     /// Decryption Secrets Block (DSB).
     DecryptionSecrets(DecryptionSecretsBlock),
+    /// Packet Block (obsolete). Deprecated by the spec in favor of the Enhanced Packet Block.
+    LegacyPacket(LegacyPacketBlock<'b>),
     /// Any block type not specifically modeled, retained as raw bytes.
     Generic(GenericBlock),
 }
 impl<'b> PcapNgBlock<'b> {
+    /// Copies any borrowed packet content so the block no longer borrows the reader's buffer
+    pub fn into_owned(self) -> PcapNgBlock<'static> {
+        match self {
+            PcapNgBlock::SectionHeader(block) => PcapNgBlock::SectionHeader(block),
+            PcapNgBlock::InterfaceDescription(block) => PcapNgBlock::InterfaceDescription(block),
+            PcapNgBlock::SimplePacket(block) => PcapNgBlock::SimplePacket(block.into_owned()),
+            PcapNgBlock::EnhancedPacket(block) => PcapNgBlock::EnhancedPacket(block.into_owned()),
+            PcapNgBlock::NameResolution(block) => PcapNgBlock::NameResolution(block),
+            PcapNgBlock::InterfaceStatistics(block) => PcapNgBlock::InterfaceStatistics(block),
+            PcapNgBlock::Custom(block) => PcapNgBlock::Custom(block),
+            PcapNgBlock::DecryptionSecrets(block) => PcapNgBlock::DecryptionSecrets(block),
+            PcapNgBlock::LegacyPacket(block) => PcapNgBlock::LegacyPacket(block.into_owned()),
+            PcapNgBlock::Generic(block) => PcapNgBlock::Generic(block),
+        }
+    }
+    /// Returns the block id of the block
+    ///
+    /// Custom and generic blocks return their stored block id
+    pub fn block_type(&self) -> u32 {
+        match self {
+            PcapNgBlock::SectionHeader(_) => SectionHeaderBlock::block_id(),
+            PcapNgBlock::InterfaceDescription(_) => InterfaceDescriptionBlock::block_id(),
+            PcapNgBlock::SimplePacket(_) => SimplePacket::block_id(),
+            PcapNgBlock::EnhancedPacket(_) => EnhancedPacket::block_id(),
+            PcapNgBlock::NameResolution(_) => NameResolutionBlock::block_id(),
+            PcapNgBlock::InterfaceStatistics(_) => InterfaceStatisticsBlock::block_id(),
+            PcapNgBlock::Custom(block) => block.block_id,
+            PcapNgBlock::DecryptionSecrets(_) => DecryptionSecretsBlock::block_id(),
+            PcapNgBlock::LegacyPacket(_) => LegacyPacketBlock::block_id(),
+            PcapNgBlock::Generic(block) => block.block_id,
+        }
+    }
+    /// Returns the options of the block if the block type has options and any were present
+    pub fn options(&self) -> Option<&BlockOptions> {
+        match self {
+            PcapNgBlock::SectionHeader(block) => block.options.as_ref(),
+            PcapNgBlock::InterfaceDescription(block) => block.options.as_ref(),
+            PcapNgBlock::EnhancedPacket(block) => block.options.as_ref(),
+            PcapNgBlock::NameResolution(block) => block.options.as_ref(),
+            PcapNgBlock::InterfaceStatistics(block) => block.options.as_ref(),
+            PcapNgBlock::DecryptionSecrets(block) => block.options.as_ref(),
+            PcapNgBlock::LegacyPacket(block) => block.options.as_ref(),
+            PcapNgBlock::SimplePacket(_) | PcapNgBlock::Custom(_) | PcapNgBlock::Generic(_) => None,
+        }
+    }
+    /// Checks that the options of the block that must be UTF-8 strings are valid UTF-8
+    ///
+    /// Checks comments and custom UTF-8 options of every block, the `shb_hardware`, `shb_os` and `shb_userappl`
+    /// options of section headers and the `if_name`, `if_description`, `if_os` and `if_hardware` options of interfaces.
+    /// See [`BlockOptions::validate_utf8`]
+    pub fn validate_utf8_options(&self) -> Result<(), OptionParseError> {
+        let Some(options) = self.options() else {
+            return Ok(());
+        };
+        let utf8_codes: &[u16] = match self {
+            PcapNgBlock::SectionHeader(_) => &[
+                SHBOptionCodes::Hardware as u16,
+                SHBOptionCodes::OS as u16,
+                SHBOptionCodes::UserApplication as u16,
+            ],
+            PcapNgBlock::InterfaceDescription(_) => &[
+                InterfaceOptionCodes::IfName as u16,
+                InterfaceOptionCodes::IfDescription as u16,
+                InterfaceOptionCodes::IfOS as u16,
+                InterfaceOptionCodes::IfHardware as u16,
+            ],
+            _ => &[],
+        };
+        options.validate_utf8(utf8_codes)
+    }
+    /// Returns true if the block contains packet data (enhanced, simple or legacy packets)
+    pub fn is_packet(&self) -> bool {
+        matches!(
+            self,
+            PcapNgBlock::EnhancedPacket(_)
+                | PcapNgBlock::SimplePacket(_)
+                | PcapNgBlock::LegacyPacket(_)
+        )
+    }
+    /// Writes the block in `byte_order` by dispatching to the variant's `write`
+    ///
+    /// See [`Block::write`]
+    pub fn write<W: Write>(
+        &self,
+        writer: &mut W,
+        byte_order: Endianness,
+    ) -> Result<(), crate::io::Error> {
+        match self {
+            PcapNgBlock::SectionHeader(block) => block.write(writer, byte_order),
+            PcapNgBlock::InterfaceDescription(block) => block.write(writer, byte_order),
+            PcapNgBlock::SimplePacket(block) => block.write(writer, byte_order),
+            PcapNgBlock::EnhancedPacket(block) => block.write(writer, byte_order),
+            PcapNgBlock::NameResolution(block) => block.write(writer, byte_order),
+            PcapNgBlock::InterfaceStatistics(block) => block.write(writer, byte_order),
+            PcapNgBlock::Custom(block) => block.write(writer, byte_order),
+            PcapNgBlock::DecryptionSecrets(block) => block.write(writer, byte_order),
+            PcapNgBlock::LegacyPacket(block) => block.write(writer, byte_order),
+            PcapNgBlock::Generic(block) => block.write(writer, byte_order),
+        }
+    }
     /// Reads the block whose `header` has already been parsed, dispatching
     /// to the appropriate variant by block ID.
+    ///
+    /// Blocks other than packets and section headers are read whole into a buffer and parsed with their
+    /// `parse_from_slice` function, so a block that fails to parse still leaves `reader` at the start of the next block.
+    /// Packets are streamed to avoid copying their content twice and section headers are streamed because
+    /// their byte order comes from their body.
+    ///
+    /// Returns [`PcapNgParseError::MinimumSizeNotMet`] if the block length is less than [`Block::minimum_size`]
+    /// of the block type. Section headers check their length once their byte order is known.
     pub fn read<R: Read>(
         reader: &mut R,
         header: &BlockHeader,
@@ -335,22 +587,39 @@ impl<'b> PcapNgBlock<'b> {
     ) -> Result<Self, PcapNgParseError> {
         let block_id = header.block_id_as_u32(byte_order);
         match block_id {
-            6 => Ok(PcapNgBlock::EnhancedPacket(
-                EnhancedPacket::read_with_header_no_block_check(
-                    reader,
-                    header,
-                    byte_order,
-                    packet_buffer,
-                )?,
-            )),
-            3 => Ok(PcapNgBlock::SimplePacket(
-                SimplePacket::read_with_header_no_block_check(
-                    reader,
-                    header,
-                    byte_order,
-                    packet_buffer,
-                )?,
-            )),
+            6 => {
+                header.check_minimum_size::<EnhancedPacket>(byte_order)?;
+                Ok(PcapNgBlock::EnhancedPacket(
+                    EnhancedPacket::read_with_header_no_block_check(
+                        reader,
+                        header,
+                        byte_order,
+                        packet_buffer,
+                    )?,
+                ))
+            }
+            3 => {
+                header.check_minimum_size::<SimplePacket>(byte_order)?;
+                Ok(PcapNgBlock::SimplePacket(
+                    SimplePacket::read_with_header_no_block_check(
+                        reader,
+                        header,
+                        byte_order,
+                        packet_buffer,
+                    )?,
+                ))
+            }
+            2 => {
+                header.check_minimum_size::<LegacyPacketBlock>(byte_order)?;
+                Ok(PcapNgBlock::LegacyPacket(
+                    LegacyPacketBlock::read_with_header_no_block_check(
+                        reader,
+                        header,
+                        byte_order,
+                        packet_buffer,
+                    )?,
+                ))
+            }
             168627466 => Ok(PcapNgBlock::SectionHeader(
                 SectionHeaderBlock::read_with_header(
                     reader,
@@ -359,52 +628,65 @@ impl<'b> PcapNgBlock<'b> {
                     packet_buffer,
                 )?,
             )),
-            1 => Ok(PcapNgBlock::InterfaceDescription(
-                InterfaceDescriptionBlock::read_with_header(
-                    reader,
-                    header,
-                    Some(byte_order),
-                    packet_buffer,
-                )?,
-            )),
-
-            4 => Ok(PcapNgBlock::NameResolution(
-                NameResolutionBlock::read_with_header(
-                    reader,
-                    header,
-                    Some(byte_order),
-                    packet_buffer,
-                )?,
-            )),
-            5 => Ok(PcapNgBlock::InterfaceStatistics(
-                InterfaceStatisticsBlock::read_with_header(
-                    reader,
-                    header,
-                    Some(byte_order),
-                    packet_buffer,
-                )?,
-            )),
-            0x0000_000A => Ok(PcapNgBlock::DecryptionSecrets(
-                DecryptionSecretsBlock::read_with_header(
-                    reader,
-                    header,
-                    Some(byte_order),
-                    packet_buffer,
-                )?,
-            )),
-            id if custom::is_custom_block_id(id) => Ok(PcapNgBlock::Custom(
-                CustomBlock::read_with_header_no_block_check(reader, header, byte_order)?,
-            )),
-
-            _ => Ok(PcapNgBlock::Generic(GenericBlock::read_with_header(
-                reader, header, byte_order,
-            )?)),
+            _ => {
+                let block = read_full_block(reader, header, byte_order)?;
+                Self::parse_full_block(&block, header, byte_order)
+            }
         }
     }
+    /// Parses a block that is neither a packet nor a section header with its `parse_from_slice` function
+    ///
+    /// `block` is the complete block returned by [`read_full_block`].
+    fn parse_full_block(
+        block: &[u8],
+        header: &BlockHeader,
+        byte_order: Endianness,
+    ) -> Result<Self, PcapNgParseError> {
+        let block = match header.block_id_as_u32(byte_order) {
+            1 => PcapNgBlock::InterfaceDescription(
+                InterfaceDescriptionBlock::parse_from_slice(block, byte_order)?.0,
+            ),
+            4 => PcapNgBlock::NameResolution(
+                NameResolutionBlock::parse_from_slice(block, byte_order)?.0,
+            ),
+            5 => PcapNgBlock::InterfaceStatistics(
+                InterfaceStatisticsBlock::parse_from_slice(block, byte_order)?.0,
+            ),
+            0x0000_000A => PcapNgBlock::DecryptionSecrets(
+                DecryptionSecretsBlock::parse_from_slice(block, byte_order)?.0,
+            ),
+            id if custom::is_custom_block_id(id) => {
+                PcapNgBlock::Custom(CustomBlock::parse_from_slice(block, byte_order)?.0)
+            }
+            _ => PcapNgBlock::Generic(GenericBlock::parse_from_slice(block, byte_order)?.0),
+        };
+        Ok(block)
+    }
+}
+/// Reads the block following `header` into a buffer that starts with the header's 8 bytes
+///
+/// The buffer holds the complete block so it can be given to the `parse_from_slice` functions.
+/// Returns [`PcapNgParseError::MinimumSizeNotMet`] if the block length is less than the 12 bytes of the header and trailing length
+pub(crate) fn read_full_block<R: Read>(
+    reader: &mut R,
+    header: &BlockHeader,
+    byte_order: Endianness,
+) -> Result<Vec<u8>, PcapNgParseError> {
+    let block_length = header.block_length_as_u32(byte_order) as usize;
+    if block_length < 12 {
+        return Err(PcapNgParseError::MinimumSizeNotMet(12, block_length));
+    }
+    let mut block = vec![0u8; block_length];
+    block[..4].copy_from_slice(&header.block_id);
+    block[4..8].copy_from_slice(&header.block_length);
+    reader.read_exact(&mut block[8..])?;
+    Ok(block)
 }
 
 #[cfg(test)]
 mod tests {
+    use std::borrow::Cow;
+
     use super::*;
     #[cfg(debug_assertions)]
     #[test]
@@ -436,4 +718,465 @@ mod tests {
             Some(Endianness::BigEndian)
         );
     }
+    /// Sets the block length that reading the written block is expected to produce
+    fn with_block_length(mut block: PcapNgBlock<'_>, length: u32) -> PcapNgBlock<'_> {
+        match &mut block {
+            PcapNgBlock::SectionHeader(block) => block.block_length = length,
+            PcapNgBlock::InterfaceDescription(block) => block.block_length = length,
+            PcapNgBlock::SimplePacket(block) => block.block_length = length,
+            PcapNgBlock::EnhancedPacket(block) => block.block_length = length,
+            PcapNgBlock::NameResolution(block) => block.block_length = length,
+            PcapNgBlock::InterfaceStatistics(block) => block.block_length = length,
+            PcapNgBlock::Custom(block) => block.block_length = length,
+            PcapNgBlock::DecryptionSecrets(block) => block.block_length = length,
+            PcapNgBlock::LegacyPacket(block) => block.block_length = length,
+            PcapNgBlock::Generic(block) => block.block_length = length,
+        }
+        block
+    }
+    #[test]
+    fn block_type() {
+        let content = [0u8; 4];
+        let expected = [
+            (
+                PcapNgBlock::SectionHeader(SectionHeaderBlock::new(Endianness::LittleEndian)),
+                168627466,
+                false,
+            ),
+            (
+                PcapNgBlock::InterfaceDescription(InterfaceDescriptionBlock::new(
+                    crate::link_type::LinkType::Ethernet,
+                    0,
+                )),
+                1,
+                false,
+            ),
+            (
+                PcapNgBlock::SimplePacket(SimplePacket {
+                    block_length: 0,
+                    original_length: 4,
+                    content: Cow::Borrowed(&content),
+                }),
+                3,
+                true,
+            ),
+            (
+                PcapNgBlock::NameResolution(NameResolutionBlock {
+                    block_length: 0,
+                    records: Default::default(),
+                    options: None,
+                }),
+                4,
+                false,
+            ),
+            (
+                PcapNgBlock::InterfaceStatistics(InterfaceStatisticsBlock {
+                    block_length: 0,
+                    interface_id: 0,
+                    timestamp_high: 0,
+                    timestamp_low: 0,
+                    options: None,
+                }),
+                5,
+                false,
+            ),
+            (
+                PcapNgBlock::EnhancedPacket(EnhancedPacket {
+                    block_length: 0,
+                    interface_id: 0,
+                    timestamp_high: 0,
+                    timestamp_low: 0,
+                    captured_length: 4,
+                    original_length: 4,
+                    content: Cow::Borrowed(&content),
+                    options: None,
+                }),
+                6,
+                true,
+            ),
+            (
+                PcapNgBlock::LegacyPacket(LegacyPacketBlock {
+                    block_length: 0,
+                    interface_id: 0,
+                    drops_count: 0,
+                    timestamp_high: 0,
+                    timestamp_low: 0,
+                    captured_length: 4,
+                    original_length: 4,
+                    content: Cow::Borrowed(&content),
+                    options: None,
+                }),
+                2,
+                true,
+            ),
+            (
+                PcapNgBlock::DecryptionSecrets(DecryptionSecretsBlock {
+                    block_length: 0,
+                    secrets_type: 0,
+                    secrets_length: 0,
+                    secrets_data: Vec::new(),
+                    options: None,
+                }),
+                0x0000_000A,
+                false,
+            ),
+            (
+                PcapNgBlock::Custom(CustomBlock {
+                    block_length: 0,
+                    block_id: CUSTOM_BLOCK_DO_NOT_COPY,
+                    pen: 0,
+                    custom_data: Vec::new(),
+                }),
+                CUSTOM_BLOCK_DO_NOT_COPY,
+                false,
+            ),
+            (
+                PcapNgBlock::Generic(GenericBlock::new(0x0000_0BAC, None)),
+                0x0000_0BAC,
+                false,
+            ),
+        ];
+        for (block, block_type, is_packet) in expected {
+            assert_eq!(block.block_type(), block_type, "{block:?}");
+            assert_eq!(block.is_packet(), is_packet, "{block:?}");
+        }
+    }
+    #[test]
+    fn write_round_trip() -> anyhow::Result<()> {
+        use name_resolution::{Record, Records};
+
+        use crate::{link_type::LinkType, pcap_ng::options::OptionsBuilder};
+
+        let options = OptionsBuilder::new().comment("round trip").build()?;
+        let content = [1u8, 2, 3, 4, 5];
+        for byte_order in [Endianness::LittleEndian, Endianness::BigEndian] {
+            let mut section = SectionHeaderBlock::new(byte_order);
+            section.options = Some(options.clone());
+            let mut interface = InterfaceDescriptionBlock::new(LinkType::Ethernet, 1500);
+            interface.options = Some(options.clone());
+            let blocks = [
+                PcapNgBlock::SectionHeader(section),
+                PcapNgBlock::InterfaceDescription(interface),
+                // Simple packets have no captured length so padding can only be told apart when the packet was not truncated
+                PcapNgBlock::SimplePacket(SimplePacket {
+                    block_length: 0,
+                    original_length: content.len() as u32,
+                    content: Cow::Borrowed(&content),
+                }),
+                PcapNgBlock::EnhancedPacket(EnhancedPacket {
+                    block_length: 0,
+                    interface_id: 0,
+                    timestamp_high: 1,
+                    timestamp_low: 2,
+                    captured_length: content.len() as u32,
+                    original_length: 60,
+                    content: Cow::Borrowed(&content),
+                    options: Some(options.clone()),
+                }),
+                PcapNgBlock::LegacyPacket(LegacyPacketBlock {
+                    block_length: 0,
+                    interface_id: 1,
+                    drops_count: 7,
+                    timestamp_high: 1,
+                    timestamp_low: 2,
+                    captured_length: content.len() as u32,
+                    original_length: 60,
+                    content: Cow::Borrowed(&content),
+                    options: Some(options.clone()),
+                }),
+                PcapNgBlock::NameResolution(NameResolutionBlock {
+                    block_length: 0,
+                    records: Records(vec![Record {
+                        record_type: 1,
+                        record_length: 13,
+                        record_data: b"\x7f\0\0\x01localhost".to_vec(),
+                    }]),
+                    options: Some(options.clone()),
+                }),
+                PcapNgBlock::InterfaceStatistics(InterfaceStatisticsBlock {
+                    block_length: 0,
+                    interface_id: 0,
+                    timestamp_high: 3,
+                    timestamp_low: 4,
+                    options: None,
+                }),
+                PcapNgBlock::Custom(CustomBlock {
+                    block_length: 0,
+                    block_id: CUSTOM_BLOCK_COPYABLE,
+                    pen: 32473,
+                    custom_data: vec![9, 8, 7, 6],
+                }),
+                PcapNgBlock::DecryptionSecrets(DecryptionSecretsBlock {
+                    block_length: 0,
+                    secrets_type: 0x544c_534b,
+                    secrets_length: 3,
+                    secrets_data: vec![1, 2, 3],
+                    options: None,
+                }),
+                PcapNgBlock::Generic(GenericBlock::new(0x0000_0BAC, Some(vec![1, 2, 3, 4]))),
+            ];
+            for block in blocks {
+                let mut written = Vec::new();
+                block.write(&mut written, byte_order)?;
+                assert_eq!(written.len() % 4, 0);
+                assert_eq!(written[4..8], written[written.len() - 4..]);
+
+                let mut reader = written.as_slice();
+                let header = BlockHeader::read(&mut reader)?;
+                let mut buffer = Vec::new();
+                let read = PcapNgBlock::read(&mut reader, &header, byte_order, &mut buffer)?;
+                assert!(reader.is_empty(), "the whole block must be read");
+                assert_eq!(read.block_type(), header.block_id_as_u32(byte_order));
+                assert_eq!(read, with_block_length(block, written.len() as u32));
+            }
+        }
+        Ok(())
+    }
+    #[test]
+    fn full_block_matches_streaming() -> anyhow::Result<()> {
+        use crate::{link_type::LinkType, pcap_ng::options::OptionsBuilder};
+
+        let options = OptionsBuilder::new().comment("full block").build()?;
+        let mut interface = InterfaceDescriptionBlock::new(LinkType::Ethernet, 1500);
+        interface.options = Some(options.clone());
+        let blocks = [
+            PcapNgBlock::InterfaceDescription(interface),
+            PcapNgBlock::InterfaceStatistics(InterfaceStatisticsBlock {
+                block_length: 0,
+                interface_id: 0,
+                timestamp_high: 3,
+                timestamp_low: 4,
+                options: Some(options.clone()),
+            }),
+            PcapNgBlock::NameResolution(NameResolutionBlock {
+                block_length: 0,
+                records: Records(vec![Record {
+                    record_type: Record::IPV4,
+                    record_length: 11,
+                    record_data: b"\x0a\0\0\x01router\0".to_vec(),
+                }]),
+                options: Some(options),
+            }),
+            PcapNgBlock::DecryptionSecrets(DecryptionSecretsBlock {
+                block_length: 0,
+                secrets_type: 0x544c_534b,
+                secrets_length: 3,
+                secrets_data: vec![1, 2, 3],
+                options: None,
+            }),
+            PcapNgBlock::Custom(CustomBlock {
+                block_length: 0,
+                block_id: CUSTOM_BLOCK_DO_NOT_COPY,
+                pen: 32473,
+                custom_data: vec![9, 8, 7],
+            }),
+            PcapNgBlock::Generic(GenericBlock::new(0x0000_0BAC, Some(vec![1, 2, 3, 4]))),
+        ];
+        for byte_order in [Endianness::LittleEndian, Endianness::BigEndian] {
+            for block in &blocks {
+                let mut written = Vec::new();
+                block.write(&mut written, byte_order)?;
+
+                let mut reader = written.as_slice();
+                let header = BlockHeader::read(&mut reader)?;
+                let mut buffer = Vec::new();
+                let full = PcapNgBlock::read(&mut reader, &header, byte_order, &mut buffer)?;
+                assert!(reader.is_empty());
+
+                let mut reader = &written[8..];
+                let streamed =
+                    match block {
+                        PcapNgBlock::InterfaceDescription(_) => PcapNgBlock::InterfaceDescription(
+                            InterfaceDescriptionBlock::read_with_header(
+                                &mut reader,
+                                &header,
+                                Some(byte_order),
+                                &mut Vec::new(),
+                            )?,
+                        ),
+                        PcapNgBlock::InterfaceStatistics(_) => PcapNgBlock::InterfaceStatistics(
+                            InterfaceStatisticsBlock::read_with_header(
+                                &mut reader,
+                                &header,
+                                Some(byte_order),
+                                &mut Vec::new(),
+                            )?,
+                        ),
+                        PcapNgBlock::NameResolution(_) => {
+                            PcapNgBlock::NameResolution(NameResolutionBlock::read_with_header(
+                                &mut reader,
+                                &header,
+                                Some(byte_order),
+                                &mut Vec::new(),
+                            )?)
+                        }
+                        PcapNgBlock::DecryptionSecrets(_) => PcapNgBlock::DecryptionSecrets(
+                            DecryptionSecretsBlock::read_with_header(
+                                &mut reader,
+                                &header,
+                                Some(byte_order),
+                                &mut Vec::new(),
+                            )?,
+                        ),
+                        PcapNgBlock::Custom(_) => PcapNgBlock::Custom(
+                            CustomBlock::read_with_header(&mut reader, &header, Some(byte_order))?,
+                        ),
+                        _ => PcapNgBlock::Generic(GenericBlock::read_with_header(
+                            &mut reader,
+                            &header,
+                            byte_order,
+                        )?),
+                    };
+                assert!(reader.is_empty());
+                assert_eq!(full, streamed);
+            }
+        }
+        Ok(())
+    }
+    #[test]
+    fn full_block_error_keeps_alignment() -> anyhow::Result<()> {
+        let mut file = Vec::new();
+        InterfaceDescriptionBlock::new(crate::link_type::LinkType::Ethernet, 0)
+            .write(&mut file, Endianness::LittleEndian)?;
+        // An unknown link type fails right after the first field of the body
+        file[8..10].copy_from_slice(&[0xFF, 0xFF]);
+        let block_length = file.len();
+        GenericBlock::new(0x0000_0BAC, None).write(&mut file, Endianness::LittleEndian)?;
+
+        let mut reader = file.as_slice();
+        let header = BlockHeader::read(&mut reader)?;
+        let mut buffer = Vec::new();
+        assert!(
+            PcapNgBlock::read(&mut reader, &header, Endianness::LittleEndian, &mut buffer).is_err()
+        );
+        assert_eq!(reader.len(), file.len() - block_length);
+        let header = BlockHeader::read(&mut reader)?;
+        let next = PcapNgBlock::read(&mut reader, &header, Endianness::LittleEndian, &mut buffer)?;
+        assert_eq!(next.block_type(), 0x0000_0BAC);
+
+        let short = BlockHeader {
+            block_id: 0x0000_0BACu32.to_le_bytes(),
+            block_length: 8u32.to_le_bytes(),
+        };
+        let err = PcapNgBlock::read(
+            &mut [0u8; 4].as_slice(),
+            &short,
+            Endianness::LittleEndian,
+            &mut buffer,
+        )
+        .expect_err("block length is too small");
+        assert!(matches!(err, PcapNgParseError::MinimumSizeNotMet(12, 8)));
+        Ok(())
+    }
+    #[test]
+    fn block_below_minimum_size() -> anyhow::Result<()> {
+        let mut buffer = Vec::new();
+        let enhanced_packet = BlockHeader {
+            block_id: 6u32.to_le_bytes(),
+            block_length: 20u32.to_le_bytes(),
+        };
+        let body = [0u8; 12];
+        let mut reader = body.as_slice();
+        let err = PcapNgBlock::read(
+            &mut reader,
+            &enhanced_packet,
+            Endianness::LittleEndian,
+            &mut buffer,
+        )
+        .expect_err("enhanced packet is shorter than its fixed fields");
+        assert!(
+            matches!(err, PcapNgParseError::MinimumSizeNotMet(32, 20)),
+            "unexpected error {err:?}"
+        );
+        assert_eq!(reader.len(), body.len());
+
+        // Blocks read whole are still consumed so the reader stays aligned
+        let interface = BlockHeader {
+            block_id: 1u32.to_be_bytes(),
+            block_length: 16u32.to_be_bytes(),
+        };
+        let mut reader = [0u8; 8].as_slice();
+        let err = PcapNgBlock::read(&mut reader, &interface, Endianness::BigEndian, &mut buffer)
+            .expect_err("interface description is shorter than its fixed fields");
+        assert!(
+            matches!(err, PcapNgParseError::MinimumSizeNotMet(20, 16)),
+            "unexpected error {err:?}"
+        );
+        assert!(reader.is_empty());
+        Ok(())
+    }
+    #[test]
+    fn parse_from_slice_matches_reader() -> anyhow::Result<()> {
+        use crate::{link_type::LinkType, pcap_ng::options::OptionsBuilder};
+
+        let options = OptionsBuilder::new().comment("slice").build()?;
+        let content = [1u8, 2, 3, 4, 5];
+        for byte_order in [Endianness::LittleEndian, Endianness::BigEndian] {
+            let mut section = SectionHeaderBlock::new(byte_order);
+            section.options = Some(options.clone());
+            let mut interface = InterfaceDescriptionBlock::new(LinkType::Ethernet, 1500);
+            interface.options = Some(options.clone());
+            let blocks = [
+                PcapNgBlock::SectionHeader(section),
+                PcapNgBlock::InterfaceDescription(interface),
+                PcapNgBlock::SimplePacket(SimplePacket {
+                    block_length: 0,
+                    original_length: 60,
+                    content: Cow::Borrowed(&content),
+                }),
+                PcapNgBlock::EnhancedPacket(EnhancedPacket {
+                    block_length: 0,
+                    interface_id: 0,
+                    timestamp_high: 1,
+                    timestamp_low: 2,
+                    captured_length: content.len() as u32,
+                    original_length: 60,
+                    content: Cow::Borrowed(&content),
+                    options: Some(options.clone()),
+                }),
+            ];
+            for block in blocks {
+                let mut written = Vec::new();
+                block.write(&mut written, byte_order)?;
+                let block_length = written.len();
+
+                let mut reader = written.as_slice();
+                let header = BlockHeader::read(&mut reader)?;
+                let mut buffer = Vec::new();
+                let expected = PcapNgBlock::read(&mut reader, &header, byte_order, &mut buffer)?;
+
+                // Trailing bytes belong to the next block and must not be consumed
+                let mut bytes = written.clone();
+                bytes.extend_from_slice(&[0xFF; 8]);
+                let (parsed, consumed) = match &block {
+                    PcapNgBlock::SectionHeader(_) => {
+                        let (block, consumed) = SectionHeaderBlock::parse_from_slice(&bytes)?;
+                        (PcapNgBlock::SectionHeader(block), consumed)
+                    }
+                    PcapNgBlock::InterfaceDescription(_) => {
+                        let (block, consumed) =
+                            InterfaceDescriptionBlock::parse_from_slice(&bytes, byte_order)?;
+                        (PcapNgBlock::InterfaceDescription(block), consumed)
+                    }
+                    PcapNgBlock::SimplePacket(_) => {
+                        let (block, consumed) = SimplePacket::parse_from_slice(&bytes, byte_order)?;
+                        (PcapNgBlock::SimplePacket(block), consumed)
+                    }
+                    PcapNgBlock::EnhancedPacket(_) => {
+                        let (block, consumed) =
+                            EnhancedPacket::parse_from_slice(&bytes, byte_order)?;
+                        (PcapNgBlock::EnhancedPacket(block), consumed)
+                    }
+                    _ => unreachable!(),
+                };
+                assert_eq!(consumed, block_length);
+                assert_eq!(parsed, expected);
+
+                let truncated = &written[..block_length - 4];
+                assert!(EnhancedPacket::parse_from_slice(truncated, byte_order).is_err());
+                assert!(SectionHeaderBlock::parse_from_slice(truncated).is_err());
+            }
+        }
+        Ok(())
+    }
 }
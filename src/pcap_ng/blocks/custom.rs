@@ -11,11 +11,15 @@
 //! parser does not attempt to split the body: everything between the PEN and
 //! the trailing block-length is preserved verbatim as `custom_data`.
 //! Consumers that know the PEN-specific layout can interpret it themselves.
-use std::io::Read;
+use alloc::{vec, vec::Vec};
 
 use crate::{
-    byte_order::{Endianness, ReadExt, UndertminedByteOrder},
-    pcap_ng::{PcapNgParseError, blocks::BlockHeader},
+    byte_order::{Endianness, ReadExt, UndertminedByteOrder, WriteExt},
+    io::{Read, Write},
+    pcap_ng::{
+        PcapNgParseError,
+        blocks::{BlockHeader, block_body_with_minimum_size, write_block},
+    },
 };
 
 /// pcapng block-ID for a Custom Block that may be copied.
@@ -84,7 +88,7 @@ impl CustomBlock {
         let body_len = (block_length as usize).saturating_sub(16);
         let mut custom_data = vec![0u8; body_len];
         reader.read_exact(&mut custom_data)?;
-        reader.read_bytes::<4>()?;
+        header.check_trailing_length(reader.read_bytes::<4>()?)?;
         Ok(Self {
             block_length,
             block_id,
@@ -92,6 +96,33 @@ impl CustomBlock {
             custom_data,
         })
     }
+    /// Parses the block at the start of `bytes` without checking its block-ID
+    ///
+    /// Returns the block and the number of bytes consumed (the block length)
+    pub fn parse_from_slice(
+        bytes: &[u8],
+        byte_order: Endianness,
+    ) -> Result<(Self, usize), PcapNgParseError> {
+        let header = BlockHeader::parse_from_bytes(bytes)?;
+        let block_length = header.block_length_as_u32(byte_order);
+        // 8 (BlockHeader) + 4 (PEN) + 4 (trailing length)
+        let mut body = block_body_with_minimum_size(bytes, block_length, 16)?;
+        let block = Self::read_with_header_no_block_check(&mut body, &header, byte_order)?;
+        Ok((block, block_length as usize))
+    }
+    /// Writes the complete custom block in `byte_order`
+    ///
+    /// `block_length` is ignored and computed from `custom_data`, which is padded to 32 bits.
+    pub fn write<W: Write>(
+        &self,
+        writer: &mut W,
+        byte_order: Endianness,
+    ) -> Result<(), crate::io::Error> {
+        write_block(writer, self.block_id, byte_order, |body| {
+            body.write_u32(self.pen, byte_order)?;
+            body.write_all(&self.custom_data)
+        })
+    }
 }
 
 /// Resolves the section byte-order from a Custom Block's 4-byte block-ID.
@@ -138,7 +169,7 @@ mod tokio_async {
             let body_len = (block_length as usize).saturating_sub(16);
             let mut custom_data = vec![0u8; body_len];
             reader.read_exact(&mut custom_data).await?;
-            reader.read_bytes::<4>().await?;
+            header.check_trailing_length(reader.read_bytes::<4>().await?)?;
             Ok(Self {
                 block_length,
                 block_id,
@@ -1,13 +1,14 @@
 //! Decryption Secrets Block (DSB)
-use std::io::Read;
+use alloc::{vec, vec::Vec};
 
 use crate::{
-    byte_order::{Endianness, ReadExt, UndertminedByteOrder},
+    byte_order::{Endianness, ReadExt, UndertminedByteOrder, WriteExt},
+    io::{Read, Write},
     pcap_ng::{
         PcapNgParseError,
-        blocks::{Block, BlockHeader},
+        blocks::{Block, BlockHeader, block_body_from_slice, write_block},
         options::BlockOptions,
-        pad_length_to_32_bytes,
+        padding::pad_to_32,
     },
 };
 
@@ -61,7 +62,7 @@ impl<'b> Block<'b> for DecryptionSecretsBlock {
         let secrets_length = reader.read_u32(byte_order)?;
         let block_length = header.block_length_as_u32(byte_order);
 
-        let padded_secrets_len = pad_length_to_32_bytes(secrets_length as usize);
+        let padded_secrets_len = pad_to_32(secrets_length as usize);
         let mut secrets_data = vec![0u8; padded_secrets_len];
         reader.read_exact(&mut secrets_data)?;
         secrets_data.truncate(secrets_length as usize);
@@ -71,7 +72,7 @@ impl<'b> Block<'b> for DecryptionSecretsBlock {
             .saturating_sub(padded_secrets_len);
         let options = BlockOptions::read_bounded_option(reader, byte_order, options_budget)?;
 
-        reader.read_bytes::<4>()?;
+        header.check_trailing_length(reader.read_bytes::<4>()?)?;
         Ok(Self {
             block_length,
             secrets_type,
@@ -80,6 +81,37 @@ impl<'b> Block<'b> for DecryptionSecretsBlock {
             options,
         })
     }
+    fn write<W: Write>(
+        &self,
+        writer: &mut W,
+        byte_order: Endianness,
+    ) -> Result<(), crate::io::Error> {
+        write_block(writer, Self::block_id(), byte_order, |body| {
+            body.write_u32(self.secrets_type, byte_order)?;
+            body.write_u32(self.secrets_data.len() as u32, byte_order)?;
+            body.write_all(&self.secrets_data)?;
+            body.resize(pad_to_32(body.len()), 0);
+            if let Some(options) = &self.options {
+                options.write(body, byte_order)?;
+            }
+            Ok(())
+        })
+    }
+}
+impl DecryptionSecretsBlock {
+    /// Parses the block at the start of `bytes`
+    ///
+    /// Returns the block and the number of bytes consumed (the block length)
+    pub fn parse_from_slice(
+        bytes: &[u8],
+        byte_order: Endianness,
+    ) -> Result<(Self, usize), PcapNgParseError> {
+        let header = BlockHeader::parse_from_bytes(bytes)?;
+        let block_length = header.block_length_as_u32(byte_order);
+        let mut body = block_body_from_slice::<Self>(bytes, block_length)?;
+        let block = Self::read_with_header(&mut body, &header, Some(byte_order), &mut Vec::new())?;
+        Ok((block, block_length as usize))
+    }
 }
 #[cfg(feature = "tokio-async")]
 mod tokio_async {
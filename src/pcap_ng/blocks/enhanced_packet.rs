@@ -1,15 +1,145 @@
 //! Enhanced Packet Block (EPB)
-use std::io::{Cursor, Read, Write};
+use alloc::{borrow::Cow, vec::Vec};
 
 use crate::{
-    byte_order::{ByteOrder, Endianness, ReadExt, UndertminedByteOrder, WriteExt},
+    byte_order::{ByteOrder, Endianness, ReadExt, UndertminedByteOrder, UnexpectedSize, WriteExt},
+    io::{Read, Write},
+    pcap::{file_header::MagicNumber, packet_header::PacketTimestamp},
     pcap_ng::{
         PcapNgParseError,
-        blocks::{Block, BlockHeader},
-        options::BlockOptions,
-        pad_length_to_32_bytes,
+        blocks::{
+            Block, BlockHeader, InterfaceDescriptionBlock, TsResolution, block_body_from_slice,
+        },
+        options::{BlockOption, BlockOptions, define_options_enum},
+        padding::pad_to_32,
     },
 };
+define_options_enum! {
+    /// Options for the Enhanced Packet Block
+    enum EnhancedPacketOptionCodes {
+        /// The epb_flags option is a 32-bit flags word containing link-layer information. See [`EpbFlags`]
+        Flags = 2,
+        /// The epb_hash option contains a hash of the packet. The first byte specifies the hashing algorithm
+        Hash = 3,
+        /// The epb_dropcount option is a 64-bit unsigned integer value specifying the number of packets lost between this packet and the preceding one
+        DropCount = 4,
+        /// The epb_packetid option is a 64-bit unsigned integer that uniquely identifies the packet
+        PacketId = 5,
+        /// The epb_queue option is a 32-bit unsigned integer that identifies on which queue of the interface the specific packet was received
+        Queue = 6,
+        /// The epb_verdict option stores a verdict of the packet
+        Verdict = 7,
+    }
+}
+/// Direction of a packet from [`EpbFlags::direction`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketDirection {
+    /// Information not available
+    NotAvailable,
+    /// Inbound packet
+    Inbound,
+    /// Outbound packet
+    Outbound,
+    /// Both direction bits were set; not valid per the spec
+    Invalid,
+}
+/// Reception type of a packet from [`EpbFlags::reception_type`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReceptionType {
+    /// Not specified
+    NotSpecified,
+    Unicast,
+    Multicast,
+    Broadcast,
+    Promiscuous,
+    /// A reception type not defined by the spec
+    Unknown(u8),
+}
+/// The `epb_flags` option of an Enhanced Packet Block
+///
+/// [Enhanced Packet Block Flags Word](https://www.ietf.org/archive/id/draft-tuexen-opsawg-pcapng-03.html#name-enhanced-packet-block-flags)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EpbFlags(pub u32);
+impl EpbFlags {
+    /// Bits 0-1: Inbound / Outbound packet
+    pub fn direction(&self) -> PacketDirection {
+        match self.0 & 0b11 {
+            0b00 => PacketDirection::NotAvailable,
+            0b01 => PacketDirection::Inbound,
+            0b10 => PacketDirection::Outbound,
+            _ => PacketDirection::Invalid,
+        }
+    }
+    /// Bits 2-4: Reception type
+    pub fn reception_type(&self) -> ReceptionType {
+        match ((self.0 >> 2) & 0b111) as u8 {
+            0 => ReceptionType::NotSpecified,
+            1 => ReceptionType::Unicast,
+            2 => ReceptionType::Multicast,
+            3 => ReceptionType::Broadcast,
+            4 => ReceptionType::Promiscuous,
+            other => ReceptionType::Unknown(other),
+        }
+    }
+    /// Bits 5-8: FCS length in bytes, or `None` if not available
+    pub fn fcs_length(&self) -> Option<u8> {
+        match ((self.0 >> 5) & 0b1111) as u8 {
+            0 => None,
+            length => Some(length),
+        }
+    }
+    /// Bits 16-31: Link-layer-dependent errors
+    pub fn link_layer_errors(&self) -> u16 {
+        (self.0 >> 16) as u16
+    }
+    /// Bit 24: CRC error
+    pub fn checksum_errors(&self) -> bool {
+        self.0 & (1 << 24) != 0
+    }
+}
+/// Algorithm used for an `epb_hash` option
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    TwosComplement,
+    Xor,
+    Crc32,
+    Md5,
+    Sha1,
+    Toeplitz,
+    /// An algorithm identifier not defined by the spec
+    Unknown(u8),
+}
+impl From<u8> for HashAlgorithm {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::TwosComplement,
+            1 => Self::Xor,
+            2 => Self::Crc32,
+            3 => Self::Md5,
+            4 => Self::Sha1,
+            5 => Self::Toeplitz,
+            other => Self::Unknown(other),
+        }
+    }
+}
+impl From<HashAlgorithm> for u8 {
+    fn from(value: HashAlgorithm) -> Self {
+        match value {
+            HashAlgorithm::TwosComplement => 0,
+            HashAlgorithm::Xor => 1,
+            HashAlgorithm::Crc32 => 2,
+            HashAlgorithm::Md5 => 3,
+            HashAlgorithm::Sha1 => 4,
+            HashAlgorithm::Toeplitz => 5,
+            HashAlgorithm::Unknown(other) => other,
+        }
+    }
+}
+impl From<u32> for EpbFlags {
+    fn from(value: u32) -> Self {
+        Self(value)
+    }
+}
 
 /// A pcap-ng Enhanced Packet Block (EPB).
 ///
@@ -35,46 +165,181 @@ pub struct EnhancedPacket<'b> {
     // 24..28
     pub original_length: u32,
 
-    /// Captured packet bytes, borrowed from the reader's packet buffer.
-    pub content: &'b [u8],
+    /// Captured packet bytes, normally borrowed from the reader's packet buffer.
+    pub content: Cow<'b, [u8]>,
 
     /// Optional block options associated with this packet.
     pub options: Option<BlockOptions>,
 }
 impl<'b> EnhancedPacket<'b> {
-    /// Writes the enhanced packet block to the given writer using the specified byte order.
+    /// Parses the block at the start of `bytes` without copying the packet data
+    ///
+    /// Returns the packet borrowing its content from `bytes` and the number of bytes consumed (the block length)
+    pub fn parse_from_slice(
+        bytes: &'b [u8],
+        byte_order: Endianness,
+    ) -> Result<(Self, usize), PcapNgParseError> {
+        let header = BlockHeader::parse_from_bytes(bytes)?;
+        header.matches_block_id::<Self>()?;
+        let block_length = header.block_length_as_u32(byte_order);
+        let mut body = block_body_from_slice::<Self>(bytes, block_length)?;
+        let mut packet = EnhancedPacket::read_fixed_fields(&mut body, &header, byte_order)?;
+        let captured_length = packet.captured_length as usize;
+        let padded_length = pad_to_32(captured_length);
+        let Some((content, mut rest)) = body.split_at_checked(padded_length) else {
+            return Err(PcapNgParseError::UnexpectedSize(UnexpectedSize {
+                name: "EnhancedPacket content",
+                expected: padded_length,
+                got: body.len(),
+            }));
+        };
+        packet.read_options_and_footer(&mut rest, &header, byte_order)?;
+        packet.content = Cow::Borrowed(&content[..captured_length]);
+        Ok((packet, block_length as usize))
+    }
+    /// Copies the content if it is borrowed so the packet no longer borrows the reader's buffer
+    pub fn into_owned(self) -> EnhancedPacket<'static> {
+        EnhancedPacket {
+            block_length: self.block_length,
+            interface_id: self.interface_id,
+            timestamp_high: self.timestamp_high,
+            timestamp_low: self.timestamp_low,
+            captured_length: self.captured_length,
+            original_length: self.original_length,
+            content: Cow::Owned(self.content.into_owned()),
+            options: self.options,
+        }
+    }
+    /// Returns the 64 bit timestamp in units of the interface's [`TsResolution`]
+    pub fn timestamp_raw(&self) -> u64 {
+        ((self.timestamp_high as u64) << 32) | self.timestamp_low as u64
+    }
+    /// Converts the timestamp to a [`PacketTimestamp`]
+    ///
+    /// `resolution` should come from the interface the packet was captured on
+    /// ([`InterfaceDescriptionBlock::ts_resolution`](crate::pcap_ng::blocks::InterfaceDescriptionBlock::ts_resolution)).
     ///
-    /// Currently, this is unstable and may change in the future.
-    pub fn write<W: Write, B: ByteOrder>(
+    /// `usec` of the result is in the unit of [`TsResolution::pcap_magic_number`]:
+    /// microseconds for resolutions up to microseconds, nanoseconds for finer resolutions.
+    pub fn timestamp(&self, resolution: TsResolution) -> PacketTimestamp {
+        timestamp_from_raw(self.timestamp_raw(), resolution)
+    }
+    /// Converts the timestamp to an absolute [`PacketTimestamp`] using the interface the packet was captured on
+    ///
+    /// Applies both the interface's [`TsResolution`] and its `if_tsoffset`.
+    /// `byte_order` must be the byte order of the section the interface was read from
+    pub fn timestamp_for_interface<B: ByteOrder>(
         &self,
-        writer: &mut W,
+        interface: &InterfaceDescriptionBlock,
         byte_order: B,
-    ) -> Result<(), std::io::Error> {
-        let mut buffer = Cursor::new(Vec::with_capacity(Self::minimum_size()));
-
-        buffer.write_u32(self.interface_id, byte_order)?;
-        buffer.write_u32(self.timestamp_high, byte_order)?;
-        buffer.write_u32(self.timestamp_low, byte_order)?;
-        buffer.write_u32(self.captured_length, byte_order)?;
-        buffer.write_u32(self.original_length, byte_order)?;
-        buffer.write_all(self.content)?;
-        let padding = pad_length_to_32_bytes(self.content.len()) - self.content.len();
-        if padding > 0 {
-            buffer.write_all(&vec![0u8; padding])?;
-        }
-        if let Some(options) = &self.options {
-            options.write(&mut buffer, byte_order)?;
-        }
-        let packet_length = (buffer.get_ref().len() as u32) + 12;
-        let block_header = BlockHeader::new(
-            byte_order.u32_to_bytes(Self::block_id()),
-            byte_order.u32_to_bytes(packet_length),
-        );
-
-        block_header.write(writer)?;
-        writer.write_all(buffer.get_ref())?;
-        writer.write_u32(Self::block_id(), byte_order)?;
-
+    ) -> PacketTimestamp {
+        apply_ts_offset(
+            self.timestamp(interface.ts_resolution()),
+            interface.ts_offset_secs(byte_order).unwrap_or(0),
+        )
+    }
+    /// Parses the `epb_flags` option if present
+    ///
+    /// `byte_order` must be the byte order of the section the packet was read from
+    pub fn flags<B: ByteOrder>(&self, byte_order: B) -> Option<EpbFlags> {
+        self.options_with_code(EnhancedPacketOptionCodes::Flags)
+            .next()?
+            .as_u32(byte_order)
+            .map(EpbFlags)
+    }
+    /// Parses the `epb_dropcount` option if present
+    ///
+    /// `byte_order` must be the byte order of the section the packet was read from
+    pub fn drop_count<B: ByteOrder>(&self, byte_order: B) -> Option<u64> {
+        self.options_with_code(EnhancedPacketOptionCodes::DropCount)
+            .next()?
+            .as_u64(byte_order)
+    }
+    /// Parses the `epb_packetid` option if present
+    ///
+    /// `byte_order` must be the byte order of the section the packet was read from
+    pub fn packet_id<B: ByteOrder>(&self, byte_order: B) -> Option<u64> {
+        self.options_with_code(EnhancedPacketOptionCodes::PacketId)
+            .next()?
+            .as_u64(byte_order)
+    }
+    /// Parses the `epb_queue` option if present
+    ///
+    /// `byte_order` must be the byte order of the section the packet was read from
+    pub fn queue_id<B: ByteOrder>(&self, byte_order: B) -> Option<u32> {
+        self.options_with_code(EnhancedPacketOptionCodes::Queue)
+            .next()?
+            .as_u32(byte_order)
+    }
+    /// Returns every `epb_hash` option as the algorithm and the hash bytes
+    pub fn hashes(&self) -> Vec<(HashAlgorithm, Vec<u8>)> {
+        self.options_with_code(EnhancedPacketOptionCodes::Hash)
+            .filter_map(|option| {
+                let (algorithm, hash) = option.value.split_first()?;
+                Some((HashAlgorithm::from(*algorithm), hash.to_vec()))
+            })
+            .collect()
+    }
+    fn options_with_code(
+        &self,
+        code: EnhancedPacketOptionCodes,
+    ) -> impl Iterator<Item = &BlockOption> {
+        self.options
+            .iter()
+            .flat_map(move |options| options.get_all(code as u16))
+    }
+}
+impl EnhancedPacket<'static> {
+    /// Reads the block like [`Block::read_with_header_no_block_check`] but skips the packet data
+    ///
+    /// The returned packet has no `content`. `captured_length` is the length of the skipped data.
+    #[cfg(feature = "std")]
+    pub(crate) fn read_skipping_content<R: Read>(
+        reader: &mut R,
+        header: &BlockHeader,
+        byte_order: Endianness,
+    ) -> Result<Self, PcapNgParseError> {
+        let mut packet = Self::read_fixed_fields(reader, header, byte_order)?;
+        reader.skip_exact(pad_to_32(packet.captured_length as usize) as u64)?;
+        packet.read_options_and_footer(reader, header, byte_order)?;
+        Ok(packet)
+    }
+    /// Reads the fields before the packet data
+    fn read_fixed_fields<R: Read>(
+        reader: &mut R,
+        header: &BlockHeader,
+        byte_order: Endianness,
+    ) -> Result<Self, PcapNgParseError> {
+        let bytes = reader.read_bytes::<20>()?;
+        Ok(Self {
+            block_length: header.block_length_as_u32(byte_order),
+            interface_id: byte_order.u32_from_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            timestamp_high: byte_order.u32_from_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+            timestamp_low: byte_order.u32_from_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]),
+            captured_length: byte_order
+                .u32_from_bytes([bytes[12], bytes[13], bytes[14], bytes[15]]),
+            original_length: byte_order
+                .u32_from_bytes([bytes[16], bytes[17], bytes[18], bytes[19]]),
+            content: Cow::Borrowed(&[]),
+            options: None,
+        })
+    }
+}
+impl EnhancedPacket<'_> {
+    /// Reads the options and trailing block length that follow the packet data
+    fn read_options_and_footer<R: Read>(
+        &mut self,
+        reader: &mut R,
+        header: &BlockHeader,
+        byte_order: Endianness,
+    ) -> Result<(), PcapNgParseError> {
+        // EPB minimum_size = 32 = 8 (BlockHeader) + 20 (fixed) + 4 (trailing length).
+        // Remaining for options = block_length - minimum_size - captured-payload (padded).
+        let options_budget = (self.block_length as usize)
+            .saturating_sub(Self::minimum_size())
+            .saturating_sub(pad_to_32(self.captured_length as usize));
+        self.options = BlockOptions::read_bounded_option(reader, byte_order, options_budget)?;
+        header.check_trailing_length(reader.read_bytes::<4>()?)?;
         Ok(())
     }
 }
@@ -110,42 +375,43 @@ impl<'b> Block<'b> for EnhancedPacket<'b> {
     where
         Self: Sized + 'b,
     {
-        let bytes = reader.read_bytes::<20>()?;
-        let interface_id = byte_order.u32_from_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
-        let timestamp_high = byte_order.u32_from_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
-        let timestamp_low = byte_order.u32_from_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]);
-        let captured_length =
-            byte_order.u32_from_bytes([bytes[12], bytes[13], bytes[14], bytes[15]]);
-        let original_length =
-            byte_order.u32_from_bytes([bytes[16], bytes[17], bytes[18], bytes[19]]);
-
-        let block_length = header.block_length_as_u32(byte_order);
-        let padded_length = pad_length_to_32_bytes(captured_length as usize);
+        let mut packet = EnhancedPacket::read_fixed_fields(reader, header, byte_order)?;
+        let padded_length = pad_to_32(packet.captured_length as usize);
         // Ensure buffer is large enough
         if buffer.len() < padded_length {
             buffer.resize(padded_length, 0);
         }
         reader.read_exact(&mut buffer[..padded_length])?;
-
-        // EPB minimum_size = 32 = 8 (BlockHeader) + 20 (fixed) + 4 (trailing length).
-        // Remaining for options = block_length - minimum_size - captured-payload (padded).
-        let options_budget = (block_length as usize)
-            .saturating_sub(Self::minimum_size())
-            .saturating_sub(padded_length);
-        let options = BlockOptions::read_bounded_option(reader, byte_order, options_budget)?;
-        // Read the footer (4 bytes)
-        reader.read_bytes::<4>()?;
+        packet.read_options_and_footer(reader, header, byte_order)?;
         Ok(Self {
-            block_length,
-            interface_id,
-            timestamp_high,
-            timestamp_low,
-            captured_length,
-            original_length,
-            content: &buffer[..captured_length as usize],
-            options,
+            content: Cow::Borrowed(&buffer[..packet.captured_length as usize]),
+            ..packet
         })
     }
+    fn write<W: Write>(
+        &self,
+        writer: &mut W,
+        byte_order: Endianness,
+    ) -> Result<(), crate::io::Error> {
+        // The block length is known up front, so the packet content is written without buffering it
+        let padded_length = pad_to_32(self.content.len());
+        let options_length = self.options.as_ref().map_or(0, BlockOptions::byte_len);
+        let block_length =
+            byte_order.u32_to_bytes((12 + 20 + padded_length + options_length) as u32);
+        BlockHeader::new(byte_order.u32_to_bytes(Self::block_id()), block_length).write(writer)?;
+        writer.write_u32(self.interface_id, byte_order)?;
+        writer.write_u32(self.timestamp_high, byte_order)?;
+        writer.write_u32(self.timestamp_low, byte_order)?;
+        writer.write_u32(self.content.len() as u32, byte_order)?;
+        writer.write_u32(self.original_length, byte_order)?;
+        writer.write_all(&self.content)?;
+        writer.write_all(&[0; 3][..padded_length - self.content.len()])?;
+        if let Some(options) = &self.options {
+            options.write(writer, byte_order)?;
+        }
+        writer.write_all(&block_length)?;
+        Ok(())
+    }
 }
 
 #[cfg(feature = "tokio-async")]
@@ -155,12 +421,308 @@ mod tokio_async {
     impl<'b> TokioAsyncBlock<'b> for EnhancedPacket<'b> {}
 }
 
+/// Converts a raw pcap-ng timestamp in units of `resolution` to a [`PacketTimestamp`]
+///
+/// See [`EnhancedPacket::timestamp`]
+pub(crate) fn timestamp_from_raw(raw: u64, resolution: TsResolution) -> PacketTimestamp {
+    let raw = raw as u128;
+    let units_per_second = resolution.units_per_second().max(1);
+    let subsecond_units: u128 = match resolution.pcap_magic_number() {
+        MagicNumber::Nanosecond => 1_000_000_000,
+        _ => 1_000_000,
+    };
+    PacketTimestamp {
+        seconds: (raw / units_per_second) as u32,
+        usec: ((raw % units_per_second) * subsecond_units / units_per_second) as u32,
+    }
+}
+/// Adds an `if_tsoffset` in seconds to `timestamp`
+///
+/// Saturates at the range of [`PacketTimestamp::seconds`]
+pub(crate) fn apply_ts_offset(timestamp: PacketTimestamp, offset_secs: i64) -> PacketTimestamp {
+    PacketTimestamp {
+        seconds: (timestamp.seconds as i64)
+            .saturating_add(offset_secs)
+            .clamp(0, u32::MAX as i64) as u32,
+        usec: timestamp.usec,
+    }
+}
 #[cfg(test)]
 mod tests {
-    use crate::byte_order::{Endianness, LittleEndian};
+    use crate::{
+        byte_order::{BigEndian, Endianness},
+        pcap_ng::options::OptionsBuilder,
+    };
 
     use super::*;
 
+    fn packet_with_timestamp(raw: u64) -> EnhancedPacket<'static> {
+        EnhancedPacket {
+            block_length: 0,
+            interface_id: 0,
+            timestamp_high: (raw >> 32) as u32,
+            timestamp_low: raw as u32,
+            captured_length: 0,
+            original_length: 0,
+            content: Cow::Borrowed(&[]),
+            options: None,
+        }
+    }
+
+    #[test]
+    fn test_timestamp() {
+        // 2025-11-27T10:30:00.123456Z in microseconds
+        let raw = 1_764_239_400_123_456;
+        let packet = packet_with_timestamp(raw);
+        assert_eq!(packet.timestamp_high, 0x0006_4490);
+        assert_eq!(packet.timestamp_raw(), raw);
+        assert_eq!(
+            packet.timestamp(TsResolution::MICROSECONDS),
+            PacketTimestamp {
+                seconds: 1_764_239_400,
+                usec: 123_456
+            }
+        );
+        let packet = packet_with_timestamp(1_764_239_400_123_456_789);
+        assert_eq!(
+            packet.timestamp(TsResolution::NANOSECONDS),
+            PacketTimestamp {
+                seconds: 1_764_239_400,
+                usec: 123_456_789
+            }
+        );
+        // 1.5 seconds at 2^-10
+        let packet = packet_with_timestamp(1024 + 512);
+        assert_eq!(
+            packet.timestamp(TsResolution::Power2(10)),
+            PacketTimestamp {
+                seconds: 1,
+                usec: 500_000
+            }
+        );
+    }
+
+    #[test]
+    fn test_timestamp_for_interface() -> anyhow::Result<()> {
+        let packet = packet_with_timestamp(1_500_000);
+        let mut interface = InterfaceDescriptionBlock::new(crate::link_type::LinkType::Ethernet, 0);
+        assert_eq!(
+            packet.timestamp_for_interface(&interface, Endianness::LittleEndian),
+            PacketTimestamp {
+                seconds: 1,
+                usec: 500_000
+            }
+        );
+        interface.options = Some(BlockOptions(vec![BlockOption::new(
+            crate::pcap_ng::blocks::InterfaceOptionCodes::IfTsOffset as u16,
+            None,
+            1_700_000_000i64.to_le_bytes(),
+        )?]));
+        assert_eq!(
+            packet.timestamp_for_interface(&interface, Endianness::LittleEndian),
+            PacketTimestamp {
+                seconds: 1_700_000_001,
+                usec: 500_000
+            }
+        );
+        assert_eq!(
+            apply_ts_offset(
+                PacketTimestamp {
+                    seconds: 5,
+                    usec: 1
+                },
+                -10
+            ),
+            PacketTimestamp {
+                seconds: 0,
+                usec: 1
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_epb_flags() {
+        // Outbound, unicast, 4 byte FCS and a CRC error
+        let flags = EpbFlags(0b10 | (1 << 2) | (4 << 5) | (1 << 24));
+        assert_eq!(flags.direction(), PacketDirection::Outbound);
+        assert_eq!(flags.reception_type(), ReceptionType::Unicast);
+        assert_eq!(flags.fcs_length(), Some(4));
+        assert!(flags.checksum_errors());
+        assert_eq!(flags.link_layer_errors(), 1 << 8);
+
+        let flags = EpbFlags::default();
+        assert_eq!(flags.direction(), PacketDirection::NotAvailable);
+        assert_eq!(flags.reception_type(), ReceptionType::NotSpecified);
+        assert_eq!(flags.fcs_length(), None);
+        assert!(!flags.checksum_errors());
+    }
+
+    #[test]
+    fn test_enhanced_packet_flags_round_trip() -> anyhow::Result<()> {
+        let content = [1u8; 10];
+        let outbound = EpbFlags(0b10);
+        let options = BlockOptions(vec![BlockOption::new(
+            EnhancedPacketOptionCodes::Flags as u16,
+            None,
+            BigEndian.u32_to_bytes(outbound.0),
+        )?]);
+        let packet = EnhancedPacket {
+            block_length: 0,
+            interface_id: 0,
+            timestamp_high: 0,
+            timestamp_low: 0,
+            captured_length: content.len() as u32,
+            original_length: content.len() as u32,
+            content: Cow::Borrowed(&content),
+            options: Some(options),
+        };
+        let mut buffer = Vec::new();
+        packet.write(&mut buffer, Endianness::BigEndian)?;
+
+        let mut reader = std::io::Cursor::new(&buffer);
+        let header = BlockHeader::read(&mut reader)?;
+        let mut content_buffer = Vec::new();
+        let packet = EnhancedPacket::read_with_header(
+            &mut reader,
+            &header,
+            Some(Endianness::BigEndian),
+            &mut content_buffer,
+        )?;
+        let flags = packet.flags(Endianness::BigEndian).expect("flags option");
+        assert_eq!(flags, outbound);
+        assert_eq!(flags.direction(), PacketDirection::Outbound);
+        Ok(())
+    }
+
+    fn write_with_options(
+        options: BlockOptions,
+        byte_order: Endianness,
+    ) -> anyhow::Result<Vec<u8>> {
+        let content = [2u8; 7];
+        let packet = EnhancedPacket {
+            block_length: 0,
+            interface_id: 0,
+            timestamp_high: 0,
+            timestamp_low: 0,
+            captured_length: content.len() as u32,
+            original_length: content.len() as u32,
+            content: Cow::Borrowed(&content),
+            options: Some(options),
+        };
+        let mut buffer = Vec::new();
+        packet.write(&mut buffer, byte_order)?;
+        Ok(buffer)
+    }
+
+    #[test]
+    fn test_enhanced_packet_drop_count_round_trip() -> anyhow::Result<()> {
+        for byte_order in [Endianness::LittleEndian, Endianness::BigEndian] {
+            let drop_count: u64 = 0x0102_0304_0506_0708;
+            let value = match byte_order {
+                Endianness::LittleEndian => drop_count.to_le_bytes(),
+                Endianness::BigEndian => drop_count.to_be_bytes(),
+            };
+            let options = BlockOptions(vec![BlockOption::new(
+                EnhancedPacketOptionCodes::DropCount as u16,
+                None,
+                value,
+            )?]);
+            let buffer = write_with_options(options, byte_order)?;
+            let mut content_buffer = Vec::new();
+            let mut reader = std::io::Cursor::new(&buffer);
+            let header = BlockHeader::read(&mut reader)?;
+            let packet = EnhancedPacket::read_with_header(
+                &mut reader,
+                &header,
+                Some(byte_order),
+                &mut content_buffer,
+            )?;
+            assert_eq!(packet.drop_count(byte_order), Some(drop_count));
+            assert!(packet.hashes().is_empty());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_enhanced_packet_id_and_queue_round_trip() -> anyhow::Result<()> {
+        for byte_order in [Endianness::LittleEndian, Endianness::BigEndian] {
+            let packet_id: u64 = 0x1122_3344_5566_7788;
+            let queue: u32 = 0x0A0B_0C0D;
+            let options = BlockOptions(vec![
+                BlockOption::new(
+                    EnhancedPacketOptionCodes::PacketId as u16,
+                    None,
+                    byte_order.u64_to_bytes(packet_id),
+                )?,
+                BlockOption::new(
+                    EnhancedPacketOptionCodes::Queue as u16,
+                    None,
+                    byte_order.u32_to_bytes(queue),
+                )?,
+            ]);
+            let buffer = write_with_options(options, byte_order)?;
+            let mut content_buffer = Vec::new();
+            let mut reader = std::io::Cursor::new(&buffer);
+            let header = BlockHeader::read(&mut reader)?;
+            let packet = EnhancedPacket::read_with_header(
+                &mut reader,
+                &header,
+                Some(byte_order),
+                &mut content_buffer,
+            )?;
+            assert_eq!(packet.packet_id(byte_order), Some(packet_id));
+            assert_eq!(packet.queue_id(byte_order), Some(queue));
+        }
+        assert_eq!(packet_with_timestamp(0).packet_id(BigEndian), None);
+        assert_eq!(packet_with_timestamp(0).queue_id(BigEndian), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_enhanced_packet_hash_round_trip() -> anyhow::Result<()> {
+        let crc = [0xDE, 0xAD, 0xBE, 0xEF];
+        let mut value = vec![u8::from(HashAlgorithm::Crc32)];
+        value.extend_from_slice(&crc);
+        let options = BlockOptions(vec![BlockOption::new(
+            EnhancedPacketOptionCodes::Hash as u16,
+            None,
+            value,
+        )?]);
+        let buffer = write_with_options(options, Endianness::LittleEndian)?;
+        let mut content_buffer = Vec::new();
+        let mut reader = std::io::Cursor::new(&buffer);
+        let header = BlockHeader::read(&mut reader)?;
+        let packet = EnhancedPacket::read_with_header(
+            &mut reader,
+            &header,
+            Some(Endianness::LittleEndian),
+            &mut content_buffer,
+        )?;
+        assert_eq!(packet.hashes(), vec![(HashAlgorithm::Crc32, crc.to_vec())]);
+        assert_eq!(packet.drop_count(Endianness::LittleEndian), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_enhanced_packet_write_length_from_byte_len() -> anyhow::Result<()> {
+        for byte_order in [Endianness::LittleEndian, Endianness::BigEndian] {
+            let options = OptionsBuilder::new().comment("odd").build()?;
+            let options_length = options.byte_len();
+            let buffer = write_with_options(options, byte_order)?;
+            // 7 content bytes padded to 8
+            assert_eq!(buffer.len(), 12 + 20 + 8 + options_length);
+            let block_length =
+                byte_order.u32_from_bytes([buffer[4], buffer[5], buffer[6], buffer[7]]);
+            assert_eq!(block_length as usize, buffer.len());
+            assert_eq!(buffer[4..8], buffer[buffer.len() - 4..]);
+            let (packet, consumed) = EnhancedPacket::parse_from_slice(&buffer, byte_order)?;
+            assert_eq!(consumed, buffer.len());
+            assert_eq!(packet.content, &[2u8; 7][..]);
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_enhanced_packet_write() {
         let content = vec![1; 2048];
@@ -172,11 +734,11 @@ mod tests {
             timestamp_low: 0,
             captured_length: content.len() as u32,
             original_length: content.len() as u32,
-            content: &content,
+            content: Cow::Borrowed(&content),
             options,
         };
         let mut buffer = Vec::new();
-        packet.write(&mut buffer, LittleEndian).unwrap();
+        packet.write(&mut buffer, Endianness::LittleEndian).unwrap();
 
         assert!(!buffer.is_empty());
         let mut reader = std::io::Cursor::new(&buffer);
@@ -195,5 +757,7 @@ mod tests {
         assert_eq!(packet.captured_length, content.len() as u32);
         assert_eq!(packet.original_length, content.len() as u32);
         assert!(packet.options.is_none());
+        // The trailing block length must match the leading block length
+        assert_eq!(buffer[4..8], buffer[buffer.len() - 4..]);
     }
 }
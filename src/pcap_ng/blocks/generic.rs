@@ -1,9 +1,13 @@
 //! Generic block fallback for unknown block types
-use std::io::Read;
+use alloc::{vec, vec::Vec};
 
 use crate::{
     byte_order::{Endianness, ReadExt},
-    pcap_ng::{PcapNgParseError, blocks::BlockHeader},
+    io::{Read, Write},
+    pcap_ng::{
+        PcapNgParseError,
+        blocks::{BlockHeader, block_body_with_minimum_size, write_block},
+    },
 };
 /// A Generic Block in the PCAP-NG format
 ///
@@ -46,18 +50,43 @@ impl GenericBlock {
             None
         };
 
-        reader.read_bytes::<4>()?;
+        header.check_trailing_length(reader.read_bytes::<4>()?)?;
         Ok(Self {
             block_id: header.block_id_as_u32(byte_order),
             block_length,
             data,
         })
     }
+    /// Parses the block at the start of `bytes`
+    ///
+    /// Returns the block and the number of bytes consumed (the block length)
+    pub fn parse_from_slice(
+        bytes: &[u8],
+        byte_order: Endianness,
+    ) -> Result<(Self, usize), PcapNgParseError> {
+        let header = BlockHeader::parse_from_bytes(bytes)?;
+        let block_length = header.block_length_as_u32(byte_order);
+        let mut body = block_body_with_minimum_size(bytes, block_length, 12)?;
+        let block = Self::read_with_header(&mut body, &header, byte_order)?;
+        Ok((block, block_length as usize))
+    }
     /// Reads the entire generic block, including its 8-byte header.
     pub fn read<R: Read>(reader: &mut R, byte_order: Endianness) -> Result<Self, PcapNgParseError> {
         let header = BlockHeader::read(reader)?;
         Self::read_with_header(reader, &header, byte_order)
     }
+    /// Writes the block back with its `block_id` and `data`
+    ///
+    /// `data` is padded to a multiple of 4 bytes. `block_length` is ignored and computed from the padded data
+    pub fn write<W: Write>(
+        &self,
+        writer: &mut W,
+        byte_order: Endianness,
+    ) -> Result<(), crate::io::Error> {
+        write_block(writer, self.block_id, byte_order, |body| {
+            body.write_all(self.data.as_deref().unwrap_or_default())
+        })
+    }
 }
 
 #[cfg(feature = "tokio-async")]
@@ -88,7 +117,7 @@ mod tokio_async {
                 None
             };
 
-            reader.read_bytes::<4>().await?;
+            header.check_trailing_length(reader.read_bytes::<4>().await?)?;
             Ok(Self {
                 block_id: header.block_id_as_u32(byte_order),
                 block_length,
@@ -97,3 +126,43 @@ mod tokio_async {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::byte_order::ByteOrder;
+
+    #[test]
+    fn write_round_trip() -> anyhow::Result<()> {
+        for byte_order in [Endianness::LittleEndian, Endianness::BigEndian] {
+            let mut original = Vec::new();
+            original.extend_from_slice(&byte_order.u32_to_bytes(0x0000_0BAC));
+            original.extend_from_slice(&byte_order.u32_to_bytes(20));
+            original.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+            original.extend_from_slice(&byte_order.u32_to_bytes(20));
+
+            let block = GenericBlock::read(&mut original.as_slice(), byte_order)?;
+            assert_eq!(block.block_id, 0x0000_0BAC);
+            let mut written = Vec::new();
+            block.write(&mut written, byte_order)?;
+            assert_eq!(written, original);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn write_pads_data() -> anyhow::Result<()> {
+        let block = GenericBlock::new(0x0000_0BAC, Some(vec![1, 2, 3]));
+        let mut written = Vec::new();
+        block.write(&mut written, Endianness::LittleEndian)?;
+        assert_eq!(written.len(), 16);
+        assert_eq!(written[4..8], 16u32.to_le_bytes());
+        assert_eq!(written[8..12], [1, 2, 3, 0]);
+        assert_eq!(written[12..], 16u32.to_le_bytes());
+
+        let read = GenericBlock::read(&mut written.as_slice(), Endianness::LittleEndian)?;
+        assert_eq!(read.block_length, 16);
+        assert_eq!(read.data, Some(vec![1, 2, 3, 0]));
+        Ok(())
+    }
+}
@@ -1,12 +1,13 @@
 //! Section Header Block (SHB)
-use std::io::Read;
+use alloc::vec::Vec;
 
 use crate::{
     Version,
-    byte_order::{ByteOrder, Endianness, ReadExt},
+    byte_order::{ByteOrder, Endianness, ReadExt, UnexpectedSize, WriteExt},
+    io::{Read, Write},
     pcap_ng::{
         PCAP_NG_MAGIC, PcapNgParseError,
-        blocks::{Block, BlockHeader},
+        blocks::{Block, BlockHeader, block_body_from_slice, write_block},
         options::{BlockOptions, define_options_enum},
     },
 };
@@ -65,7 +66,94 @@ impl<'b> Block<'b> for SectionHeaderBlock {
             header_data[2],
             header_data[3],
         ])?;
+        let block_length = Self::block_length_for_byte_order(header, byte_order)?;
+        Self::read_after_fixed_fields(reader, header, byte_order, block_length, header_data)
+    }
+    /// Writes the section header block
+    ///
+    /// `byte_order` is used instead of [`SectionHeaderBlock::byte_order`] so they should normally match.
+    /// Every following block in the section must be written in the same byte order.
+    fn write<W: Write>(
+        &self,
+        writer: &mut W,
+        byte_order: Endianness,
+    ) -> Result<(), crate::io::Error> {
+        write_block(writer, Self::block_id(), byte_order, |body| {
+            body.write_u32(Self::BYTE_ORDER_MAGIC, byte_order)?;
+            self.version.write(body, byte_order)?;
+            body.write_u64(self.section_length.unwrap_or(u64::MAX), byte_order)?;
+            if let Some(options) = &self.options {
+                options.write(body, byte_order)?;
+            }
+            Ok(())
+        })
+    }
+}
+impl SectionHeaderBlock {
+    /// Byte-order magic written at the start of the block body
+    pub const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+    /// Creates a version 1.0 section header with no section length and no options
+    pub fn new(byte_order: Endianness) -> Self {
+        Self {
+            block_length: 0,
+            byte_order,
+            version: Version { major: 1, minor: 0 },
+            section_length: None,
+            options: None,
+        }
+    }
+    /// Section headers this large are treated as a byte order mismatch when the other byte order gives a valid length
+    const MAX_PLAUSIBLE_LENGTH: u32 = 1 << 24;
+    /// Decodes the block length using the byte order from the body's magic
+    ///
+    /// The section header block id is the same in both byte orders so the block header carries no byte order of its own.
+    /// Instead the block length is cross-checked: if it is only a plausible section header length when read in the
+    /// opposite byte order the header and body disagree and [`PcapNgParseError::SectionByteOrderMismatch`] is returned.
+    pub(crate) fn block_length_for_byte_order(
+        header: &BlockHeader,
+        byte_order: Endianness,
+    ) -> Result<u32, PcapNgParseError> {
+        let is_valid = |length: u32| length >= 28 && length.is_multiple_of(4);
+        let block_length = header.block_length_as_u32(byte_order);
+        let opposite = match byte_order {
+            Endianness::LittleEndian => Endianness::BigEndian,
+            Endianness::BigEndian => Endianness::LittleEndian,
+        };
+        let opposite_length = header.block_length_as_u32(opposite);
+        let opposite_is_plausible =
+            is_valid(opposite_length) && opposite_length < Self::MAX_PLAUSIBLE_LENGTH;
+        if opposite_is_plausible
+            && (!is_valid(block_length) || block_length >= Self::MAX_PLAUSIBLE_LENGTH)
+        {
+            return Err(PcapNgParseError::SectionByteOrderMismatch {
+                byte_order,
+                block_length,
+            });
+        }
+        Ok(block_length)
+    }
+    /// Reads the block body in `byte_order` without checking the byte-order magic
+    ///
+    /// See [`SyncPcapNgReader::new_with_forced_byte_order`](crate::pcap_ng::SyncPcapNgReader::new_with_forced_byte_order)
+    #[cfg(feature = "std")]
+    pub(crate) fn read_with_forced_byte_order<R: Read>(
+        reader: &mut R,
+        header: &BlockHeader,
+        byte_order: Endianness,
+    ) -> Result<Self, PcapNgParseError> {
+        header.matches_block_id::<Self>()?;
+        let header_data = reader.read_bytes::<16>()?;
         let block_length = header.block_length_as_u32(byte_order);
+        Self::read_after_fixed_fields(reader, header, byte_order, block_length, header_data)
+    }
+    /// Parses the 16 bytes of fixed fields in `header_data` then reads the options and trailing length
+    fn read_after_fixed_fields<R: Read>(
+        reader: &mut R,
+        header: &BlockHeader,
+        byte_order: Endianness,
+        block_length: u32,
+        header_data: [u8; 16],
+    ) -> Result<Self, PcapNgParseError> {
         let version = Version::parse(&header_data[4..8], byte_order);
         let section_length: [u8; 8] = header_data[8..16].try_into()?;
         let section_length = if section_length == [0xFF; 8] {
@@ -77,7 +165,7 @@ impl<'b> Block<'b> for SectionHeaderBlock {
         // SHB layout: 8 (BlockHeader) + 16 (fixed) + options + 4 (trailing length)
         let options_budget = (block_length as usize).saturating_sub(8 + 16 + 4);
         let options = BlockOptions::read_bounded_option(reader, byte_order, options_budget)?;
-        reader.read_bytes::<4>()?;
+        header.check_trailing_length(reader.read_bytes::<4>()?)?;
         let result = Self {
             block_length,
             byte_order,
@@ -87,8 +175,27 @@ impl<'b> Block<'b> for SectionHeaderBlock {
         };
         Ok(result)
     }
-}
-impl SectionHeaderBlock {
+    /// Parses the block at the start of `bytes`
+    ///
+    /// The byte order is taken from the block's byte-order magic.
+    /// Returns the block and the number of bytes consumed (the block length)
+    pub fn parse_from_slice(bytes: &[u8]) -> Result<(Self, usize), PcapNgParseError> {
+        let header = BlockHeader::parse_from_bytes(bytes)?;
+        header.matches_block_id::<Self>()?;
+        let magic: [u8; 4] = bytes
+            .get(8..12)
+            .ok_or(UnexpectedSize {
+                name: "SectionHeaderBlock",
+                expected: 12,
+                got: bytes.len(),
+            })?
+            .try_into()?;
+        let byte_order = Endianness::from_pcap_ng_bytes(&magic)?;
+        let block_length = Self::block_length_for_byte_order(&header, byte_order)?;
+        let mut body = block_body_from_slice::<Self>(bytes, block_length)?;
+        let block = Self::read_with_header(&mut body, &header, Some(byte_order), &mut Vec::new())?;
+        Ok((block, block_length as usize))
+    }
     /// Reads the entire block from the reader
     pub fn read_from_reader<R: Read>(
         reader: &mut R,
@@ -129,7 +236,7 @@ mod tokio_async {
                 header_data[2],
                 header_data[3],
             ])?;
-            let block_length = header.block_length_as_u32(byte_order);
+            let block_length = SectionHeaderBlock::block_length_for_byte_order(header, byte_order)?;
             let version = Version::parse(&header_data[4..8], byte_order);
             let section_length: [u8; 8] = header_data[8..16].try_into()?;
             let section_length = if section_length == [0xFF; 8] {
@@ -142,7 +249,7 @@ mod tokio_async {
             let options_budget = (block_length as usize).saturating_sub(8 + 16 + 4);
             let options =
                 BlockOptions::read_async_bounded_option(reader, byte_order, options_budget).await?;
-            reader.read_bytes::<4>().await?;
+            header.check_trailing_length(reader.read_bytes::<4>().await?)?;
             let result = Self {
                 block_length,
                 byte_order,
@@ -157,6 +264,58 @@ mod tokio_async {
 #[cfg(test)]
 mod tests {
     use super::*;
+    const TEST_001_SHB: [u8; 96] = [
+        10, 13, 13, 10, 96, 0, 0, 0, 77, 60, 43, 26, 1, 0, 0, 0, 255, 255, 255, 255, 255, 255, 255,
+        255, 2, 0, 9, 0, 65, 112, 112, 108, 101, 32, 77, 66, 80, 0, 0, 0, 3, 0, 12, 0, 79, 83, 45,
+        88, 32, 49, 48, 46, 49, 48, 46, 53, 4, 0, 15, 0, 112, 99, 97, 112, 95, 119, 114, 105, 116,
+        101, 114, 46, 108, 117, 97, 0, 1, 0, 7, 0, 116, 101, 115, 116, 48, 48, 49, 0, 0, 0, 0, 0,
+        96, 0, 0, 0,
+    ];
+    #[test]
+    fn test_write_matches_parsed() -> anyhow::Result<()> {
+        let block =
+            SectionHeaderBlock::read_from_reader(&mut TEST_001_SHB.as_slice(), &mut Vec::new())?;
+        let mut written = Vec::new();
+        block.write(&mut written, block.byte_order)?;
+        assert_eq!(written, TEST_001_SHB);
+        Ok(())
+    }
+    #[test]
+    fn byte_order_mismatch() -> anyhow::Result<()> {
+        // Little endian body with the block length written big endian
+        let mut mismatched = TEST_001_SHB;
+        mismatched[4..8].copy_from_slice(&96u32.to_be_bytes());
+        let err = SectionHeaderBlock::read_from_reader(&mut mismatched.as_slice(), &mut Vec::new())
+            .expect_err("block length is big endian");
+        assert!(matches!(
+            err,
+            PcapNgParseError::SectionByteOrderMismatch {
+                byte_order: Endianness::LittleEndian,
+                block_length: 0x6000_0000,
+            }
+        ));
+        // A length that is not a multiple of 4 in either byte order is left to the options parser
+        let header = BlockHeader::new(PCAP_NG_MAGIC, [0x1D, 0, 0, 0x1D]);
+        assert_eq!(
+            SectionHeaderBlock::block_length_for_byte_order(&header, Endianness::LittleEndian)?,
+            0x1D00_001D
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_write_big_endian_round_trip() -> anyhow::Result<()> {
+        let mut block = SectionHeaderBlock::new(Endianness::BigEndian);
+        block.section_length = Some(1024);
+        let mut written = Vec::new();
+        block.write(&mut written, block.byte_order)?;
+        assert_eq!(written.len(), 28);
+        assert_eq!(written[8..12], [0x1A, 0x2B, 0x3C, 0x4D]);
+
+        let read = SectionHeaderBlock::read_from_reader(&mut written.as_slice(), &mut Vec::new())?;
+        block.block_length = 28;
+        assert_eq!(read, block);
+        Ok(())
+    }
     #[test]
     fn test_parse() -> anyhow::Result<()> {
         let content = [
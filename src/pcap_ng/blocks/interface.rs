@@ -1,13 +1,15 @@
 //! Interface Description Block (IDB)
-use std::io::{Cursor, Read};
+use alloc::{string::String, vec::Vec};
 
 use crate::{
-    byte_order::{Endianness, ReadExt, UndertminedByteOrder},
+    byte_order::{ByteOrder, Endianness, ReadExt, UndertminedByteOrder, WriteExt},
+    io::{Read, Write},
     link_type::LinkType,
+    pcap::file_header::MagicNumber,
     pcap_ng::{
         PcapNgParseError,
-        blocks::{Block, BlockHeader},
-        options::{BlockOptions, define_options_enum},
+        blocks::{Block, BlockHeader, block_body_from_slice, write_block},
+        options::{BlockOption, BlockOptions, define_options_enum},
     },
 };
 define_options_enum! {
@@ -25,7 +27,11 @@ define_options_enum! {
         IfSpeed = 8,
         IfTimestampResolution = 9,
         IfTZone = 10,
+        /// The if_filter option identifies the filter used to capture traffic.
+        /// The first byte is the filter type (0 for a libpcap filter string) followed by the filter.
         IfFilter = 11,
+        /// The if_os option is a UTF-8 string containing the name of the operating system of the machine
+        /// on which this interface is installed. The string is not zero-terminated.
         IfOS = 12,
         IfFcsLength = 13,
         IfTsOffset = 14,
@@ -35,6 +41,64 @@ define_options_enum! {
     }
 }
 
+/// Timestamp resolution of an interface from the `if_tsresol` option
+///
+/// If the option is not present the resolution is microseconds (`Power10(6)`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TsResolution {
+    /// Timestamps are in units of 10^-n seconds
+    Power10(u8),
+    /// Timestamps are in units of 2^-n seconds
+    Power2(u8),
+}
+impl Default for TsResolution {
+    fn default() -> Self {
+        Self::MICROSECONDS
+    }
+}
+impl TsResolution {
+    /// The default resolution
+    pub const MICROSECONDS: Self = Self::Power10(6);
+    /// Nanosecond resolution
+    pub const NANOSECONDS: Self = Self::Power10(9);
+    /// Parses the single byte value of the `if_tsresol` option
+    ///
+    /// If the most significant bit is set the remaining bits are a negative power of 2
+    /// otherwise they are a negative power of 10
+    pub fn from_byte(value: u8) -> Self {
+        if value & 0x80 != 0 {
+            Self::Power2(value & 0x7F)
+        } else {
+            Self::Power10(value)
+        }
+    }
+    /// Returns the value of the `if_tsresol` option for this resolution
+    pub fn to_byte(&self) -> u8 {
+        match self {
+            Self::Power10(exponent) => *exponent & 0x7F,
+            Self::Power2(exponent) => 0x80 | (*exponent & 0x7F),
+        }
+    }
+    /// Number of timestamp units per second
+    ///
+    /// Saturates at `u128::MAX` for resolutions that do not fit
+    pub fn units_per_second(&self) -> u128 {
+        match self {
+            Self::Power10(exponent) => 10u128.saturating_pow(*exponent as u32),
+            Self::Power2(exponent) => 2u128.saturating_pow(*exponent as u32),
+        }
+    }
+    /// Returns the pcap [`MagicNumber`] whose sub-second unit can represent this resolution
+    ///
+    /// Resolutions finer than microseconds use [`MagicNumber::Nanosecond`]
+    pub fn pcap_magic_number(&self) -> MagicNumber {
+        if self.units_per_second() > 1_000_000 {
+            MagicNumber::Nanosecond
+        } else {
+            MagicNumber::Microsecond
+        }
+    }
+}
 /// Describes a single capture interface within a section.
 ///
 /// Subsequent Enhanced Packet Blocks reference an interface by its index in
@@ -80,7 +144,8 @@ impl<'b> Block<'b> for InterfaceDescriptionBlock {
         let byte_order = byte_order
             .or(header.endianness_from_block::<Self>())
             .ok_or(UndertminedByteOrder)?;
-        let mut cursor = Cursor::new(reader.read_bytes::<8>()?);
+        let fixed = reader.read_bytes::<8>()?;
+        let mut cursor = &fixed[..];
 
         let link_type = LinkType::try_from(cursor.read_u16(byte_order)?)?;
         let reserved = cursor.read_bytes::<2>()?;
@@ -91,7 +156,7 @@ impl<'b> Block<'b> for InterfaceDescriptionBlock {
         let options_budget = (block_length as usize).saturating_sub(Self::minimum_size());
         let options = BlockOptions::read_bounded_option(reader, byte_order, options_budget)?;
 
-        reader.read_bytes::<4>()?;
+        header.check_trailing_length(reader.read_bytes::<4>()?)?;
         Ok(Self {
             block_length,
             link_type,
@@ -100,6 +165,105 @@ impl<'b> Block<'b> for InterfaceDescriptionBlock {
             options,
         })
     }
+    fn write<W: Write>(
+        &self,
+        writer: &mut W,
+        byte_order: Endianness,
+    ) -> Result<(), crate::io::Error> {
+        write_block(writer, Self::block_id(), byte_order, |body| {
+            body.write_u16(self.link_type as u16, byte_order)?;
+            body.write_all(&self.reserved)?;
+            body.write_u32(self.snap_length, byte_order)?;
+            if let Some(options) = &self.options {
+                options.write(body, byte_order)?;
+            }
+            Ok(())
+        })
+    }
+}
+impl InterfaceDescriptionBlock {
+    /// Creates an interface description with no options
+    pub fn new(link_type: LinkType, snap_length: u32) -> Self {
+        Self {
+            block_length: 0,
+            link_type,
+            reserved: [0, 0],
+            snap_length,
+            options: None,
+        }
+    }
+    /// Parses the block at the start of `bytes`
+    ///
+    /// Returns the block and the number of bytes consumed (the block length)
+    pub fn parse_from_slice(
+        bytes: &[u8],
+        byte_order: Endianness,
+    ) -> Result<(Self, usize), PcapNgParseError> {
+        let header = BlockHeader::parse_from_bytes(bytes)?;
+        let block_length = header.block_length_as_u32(byte_order);
+        let mut body = block_body_from_slice::<Self>(bytes, block_length)?;
+        let block = Self::read_with_header(&mut body, &header, Some(byte_order), &mut Vec::new())?;
+        Ok((block, block_length as usize))
+    }
+    /// Returns the timestamp resolution from the `if_tsresol` option
+    ///
+    /// Defaults to microseconds if the option is not present
+    pub fn ts_resolution(&self) -> TsResolution {
+        self.option(InterfaceOptionCodes::IfTimestampResolution)
+            .and_then(|option| option.value.first())
+            .map(|value| TsResolution::from_byte(*value))
+            .unwrap_or_default()
+    }
+    /// Parses the `if_speed` option in bits per second if present
+    ///
+    /// `byte_order` must be the byte order of the section the interface was read from
+    pub fn speed<B: ByteOrder>(&self, byte_order: B) -> Option<u64> {
+        self.option(InterfaceOptionCodes::IfSpeed)?
+            .as_u64(byte_order)
+    }
+    /// Parses the `if_tsoffset` option if present
+    ///
+    /// The offset is in seconds and must be added to every packet timestamp of this interface
+    /// to get the absolute time.
+    ///
+    /// `byte_order` must be the byte order of the section the interface was read from
+    pub fn ts_offset_secs<B: ByteOrder>(&self, byte_order: B) -> Option<i64> {
+        self.option(InterfaceOptionCodes::IfTsOffset)?
+            .as_i64(byte_order)
+    }
+    /// Returns the filter type and filter from the `if_filter` option if present
+    ///
+    /// A filter type of 0 is a libpcap filter string. See [`Self::capture_filter_string`]
+    pub fn capture_filter(&self) -> Option<(u8, Vec<u8>)> {
+        let (filter_type, filter) = self
+            .option(InterfaceOptionCodes::IfFilter)?
+            .value
+            .split_first()?;
+        Some((*filter_type, filter.to_vec()))
+    }
+    /// Returns the libpcap filter string from the `if_filter` option
+    ///
+    /// Returns `None` if the option is not present, is not a libpcap filter string or is not valid UTF-8
+    pub fn capture_filter_string(&self) -> Option<String> {
+        match self
+            .option(InterfaceOptionCodes::IfFilter)?
+            .value
+            .split_first()?
+        {
+            (0, filter) => String::from_utf8(filter.to_vec()).ok(),
+            _ => None,
+        }
+    }
+    /// Returns the operating system of the capturing machine from the `if_os` option
+    ///
+    /// Returns `None` if the option is not present or is not valid UTF-8
+    pub fn os(&self) -> Option<String> {
+        String::from_utf8(self.option(InterfaceOptionCodes::IfOS)?.value.clone()).ok()
+    }
+    /// Returns the first option with `code`
+    fn option(&self, code: InterfaceOptionCodes) -> Option<&BlockOption> {
+        self.options.as_ref()?.get(code as u16)
+    }
 }
 #[cfg(feature = "tokio-async")]
 mod tokio_async {
@@ -119,8 +283,12 @@ mod tests {
 
     use crate::{
         byte_order::Endianness,
-        pcap_ng::blocks::{
-            Block, BlockHeader, InterfaceOptionCodes, interface::InterfaceDescriptionBlock,
+        pcap_ng::{
+            blocks::{
+                Block, BlockHeader, InterfaceOptionCodes, TsResolution,
+                interface::InterfaceDescriptionBlock,
+            },
+            options::{BlockOption, BlockOptions},
         },
     };
     #[test]
@@ -157,4 +325,116 @@ mod tests {
         assert_eq!(reader.position(), 52);
         Ok(())
     }
+
+    #[test]
+    fn ts_resolution() -> anyhow::Result<()> {
+        assert_eq!(TsResolution::from_byte(6), TsResolution::MICROSECONDS);
+        assert_eq!(TsResolution::from_byte(0x8A), TsResolution::Power2(10));
+        assert_eq!(TsResolution::Power2(10).to_byte(), 0x8A);
+        assert_eq!(TsResolution::Power2(10).units_per_second(), 1024);
+        assert_eq!(TsResolution::NANOSECONDS.units_per_second(), 1_000_000_000);
+
+        let mut interface = InterfaceDescriptionBlock {
+            block_length: 0,
+            link_type: crate::link_type::LinkType::Ethernet,
+            reserved: [0, 0],
+            snap_length: 0,
+            options: None,
+        };
+        assert_eq!(interface.ts_resolution(), TsResolution::MICROSECONDS);
+        interface.options = Some(BlockOptions(vec![BlockOption::new(
+            InterfaceOptionCodes::IfTimestampResolution as u16,
+            None,
+            [9],
+        )?]));
+        assert_eq!(interface.ts_resolution(), TsResolution::NANOSECONDS);
+        Ok(())
+    }
+
+    #[test]
+    fn speed_and_ts_offset() -> anyhow::Result<()> {
+        let mut interface = InterfaceDescriptionBlock::new(crate::link_type::LinkType::Ethernet, 0);
+        assert_eq!(interface.speed(Endianness::LittleEndian), None);
+        assert_eq!(interface.ts_offset_secs(Endianness::LittleEndian), None);
+        interface.options = Some(BlockOptions(vec![
+            BlockOption::new(
+                InterfaceOptionCodes::IfSpeed as u16,
+                None,
+                1_000_000_000u64.to_be_bytes(),
+            )?,
+            BlockOption::new(
+                InterfaceOptionCodes::IfTsOffset as u16,
+                None,
+                (-3600i64).to_be_bytes(),
+            )?,
+        ]));
+        assert_eq!(interface.speed(Endianness::BigEndian), Some(1_000_000_000));
+        assert_eq!(interface.ts_offset_secs(Endianness::BigEndian), Some(-3600));
+        Ok(())
+    }
+
+    #[test]
+    fn capture_filter() -> anyhow::Result<()> {
+        let mut interface = InterfaceDescriptionBlock::new(crate::link_type::LinkType::Ethernet, 0);
+        assert_eq!(interface.capture_filter(), None);
+        assert_eq!(interface.capture_filter_string(), None);
+
+        interface.options = Some(BlockOptions(vec![BlockOption::new(
+            InterfaceOptionCodes::IfFilter as u16,
+            None,
+            b"\0tcp port 80".to_vec(),
+        )?]));
+        let mut written = Vec::new();
+        interface.write(&mut written, Endianness::LittleEndian)?;
+        let mut reader = written.as_slice();
+        let header = BlockHeader::read(&mut reader)?;
+        let interface = InterfaceDescriptionBlock::read_with_header(
+            &mut reader,
+            &header,
+            Some(Endianness::LittleEndian),
+            &mut Vec::new(),
+        )?;
+        assert_eq!(
+            interface.capture_filter(),
+            Some((0, b"tcp port 80".to_vec()))
+        );
+        assert_eq!(
+            interface.capture_filter_string().as_deref(),
+            Some("tcp port 80")
+        );
+
+        // A BPF program is not a filter string
+        let mut interface = interface;
+        interface.options = Some(BlockOptions(vec![BlockOption::new(
+            InterfaceOptionCodes::IfFilter as u16,
+            None,
+            [1, 0x28, 0, 0, 0],
+        )?]));
+        assert_eq!(interface.capture_filter(), Some((1, vec![0x28, 0, 0, 0])));
+        assert_eq!(interface.capture_filter_string(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn os() -> anyhow::Result<()> {
+        let mut interface = InterfaceDescriptionBlock::new(crate::link_type::LinkType::Ethernet, 0);
+        assert_eq!(interface.os(), None);
+        interface.options = Some(BlockOptions(vec![BlockOption::new(
+            InterfaceOptionCodes::IfOS as u16,
+            None,
+            b"Linux 6.1".to_vec(),
+        )?]));
+        let mut written = Vec::new();
+        interface.write(&mut written, Endianness::BigEndian)?;
+        let mut reader = written.as_slice();
+        let header = BlockHeader::read(&mut reader)?;
+        let interface = InterfaceDescriptionBlock::read_with_header(
+            &mut reader,
+            &header,
+            Some(Endianness::BigEndian),
+            &mut Vec::new(),
+        )?;
+        assert_eq!(interface.os().as_deref(), Some("Linux 6.1"));
+        Ok(())
+    }
 }
@@ -1,11 +1,12 @@
 //! Interface Statistics Block (ISB)
-use std::io::Read;
+use alloc::vec::Vec;
 
 use crate::{
-    byte_order::{Endianness, ReadExt, UndertminedByteOrder},
+    byte_order::{Endianness, ReadExt, UndertminedByteOrder, WriteExt},
+    io::{Read, Write},
     pcap_ng::{
         PcapNgParseError,
-        blocks::{Block, BlockHeader},
+        blocks::{Block, BlockHeader, block_body_from_slice, write_block},
         options::{BlockOptions, define_options_enum},
     },
 };
@@ -74,7 +75,7 @@ impl<'b> Block<'b> for InterfaceStatisticsBlock {
         let options_budget = (block_length as usize).saturating_sub(Self::minimum_size());
         let options = BlockOptions::read_bounded_option(reader, byte_order, options_budget)?;
 
-        reader.read_bytes::<4>()?;
+        header.check_trailing_length(reader.read_bytes::<4>()?)?;
         Ok(Self {
             block_length,
             interface_id,
@@ -83,6 +84,36 @@ impl<'b> Block<'b> for InterfaceStatisticsBlock {
             options,
         })
     }
+    fn write<W: Write>(
+        &self,
+        writer: &mut W,
+        byte_order: Endianness,
+    ) -> Result<(), crate::io::Error> {
+        write_block(writer, Self::block_id(), byte_order, |body| {
+            body.write_u32(self.interface_id, byte_order)?;
+            body.write_u32(self.timestamp_high, byte_order)?;
+            body.write_u32(self.timestamp_low, byte_order)?;
+            if let Some(options) = &self.options {
+                options.write(body, byte_order)?;
+            }
+            Ok(())
+        })
+    }
+}
+impl InterfaceStatisticsBlock {
+    /// Parses the block at the start of `bytes`
+    ///
+    /// Returns the block and the number of bytes consumed (the block length)
+    pub fn parse_from_slice(
+        bytes: &[u8],
+        byte_order: Endianness,
+    ) -> Result<(Self, usize), PcapNgParseError> {
+        let header = BlockHeader::parse_from_bytes(bytes)?;
+        let block_length = header.block_length_as_u32(byte_order);
+        let mut body = block_body_from_slice::<Self>(bytes, block_length)?;
+        let block = Self::read_with_header(&mut body, &header, Some(byte_order), &mut Vec::new())?;
+        Ok((block, block_length as usize))
+    }
 }
 #[cfg(feature = "tokio-async")]
 mod tokio_async {
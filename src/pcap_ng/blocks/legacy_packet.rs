@@ -0,0 +1,204 @@
+//! Packet Block (obsolete block type 2)
+use alloc::{borrow::Cow, vec::Vec};
+
+use crate::{
+    byte_order::{Endianness, ReadExt, UndertminedByteOrder, WriteExt},
+    io::{Read, Write},
+    pcap::packet_header::PacketTimestamp,
+    pcap_ng::{
+        PcapNgParseError,
+        blocks::{Block, BlockHeader, TsResolution, timestamp_from_raw, write_block},
+        options::BlockOptions,
+        padding::pad_to_32,
+    },
+};
+
+/// A pcap-ng Packet Block, the predecessor of the [`EnhancedPacket`](crate::pcap_ng::blocks::EnhancedPacket)
+///
+/// Deprecated by the spec and should not be written to new files. It is still found in older captures.
+///
+/// [Packet Block (obsolete!)](https://www.ietf.org/archive/id/draft-tuexen-opsawg-pcapng-03.html#name-packet-block-obsolete)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LegacyPacketBlock<'b> {
+    /// Total block length in bytes, including header and footer.
+    pub block_length: u32,
+    /// Identifier of the interface this packet was captured on.
+    // 8..10
+    pub interface_id: u16,
+    /// Packets dropped by the interface between this packet and the preceding one. `0xFFFF` if unknown.
+    // 10..12
+    pub drops_count: u16,
+    /// Upper 32 bits of the packet timestamp.
+    // 12..16
+    pub timestamp_high: u32,
+    /// Lower 32 bits of the packet timestamp.
+    // 16..20
+    pub timestamp_low: u32,
+    /// Number of bytes captured (may be less than `original_length`).
+    // 20..24
+    pub captured_length: u32,
+    /// Length of the packet on the wire.
+    // 24..28
+    pub original_length: u32,
+    /// Captured packet bytes, normally borrowed from the reader's packet buffer.
+    pub content: Cow<'b, [u8]>,
+    /// Optional block options. `pack_flags` and `pack_hash` use the codes of the EPB flags and hash options.
+    pub options: Option<BlockOptions>,
+}
+impl LegacyPacketBlock<'_> {
+    /// Copies the content if it is borrowed so the packet no longer borrows the reader's buffer
+    pub fn into_owned(self) -> LegacyPacketBlock<'static> {
+        LegacyPacketBlock {
+            content: Cow::Owned(self.content.into_owned()),
+            ..self
+        }
+    }
+    /// Returns the 64 bit timestamp in units of the interface's [`TsResolution`]
+    pub fn timestamp_raw(&self) -> u64 {
+        ((self.timestamp_high as u64) << 32) | self.timestamp_low as u64
+    }
+    /// Converts the timestamp to a [`PacketTimestamp`]
+    ///
+    /// See [`EnhancedPacket::timestamp`](crate::pcap_ng::blocks::EnhancedPacket::timestamp)
+    pub fn timestamp(&self, resolution: TsResolution) -> PacketTimestamp {
+        timestamp_from_raw(self.timestamp_raw(), resolution)
+    }
+}
+impl<'b> Block<'b> for LegacyPacketBlock<'b> {
+    fn block_id() -> u32 {
+        2
+    }
+
+    fn minimum_size() -> usize {
+        32
+    }
+    fn read_with_header<R: Read>(
+        reader: &mut R,
+        header: &BlockHeader,
+        byte_order: Option<Endianness>,
+        buffer: &'b mut Vec<u8>,
+    ) -> Result<Self, PcapNgParseError>
+    where
+        Self: Sized + 'b,
+    {
+        header.matches_block_id::<Self>()?;
+        let byte_order = byte_order
+            .or(header.endianness_from_block::<Self>())
+            .ok_or(UndertminedByteOrder)?;
+        Self::read_with_header_no_block_check(reader, header, byte_order, buffer)
+    }
+    fn read_with_header_no_block_check<R: Read>(
+        reader: &mut R,
+        header: &BlockHeader,
+        byte_order: Endianness,
+        buffer: &'b mut Vec<u8>,
+    ) -> Result<Self, PcapNgParseError>
+    where
+        Self: Sized + 'b,
+    {
+        let block_length = header.block_length_as_u32(byte_order);
+        let interface_id = reader.read_u16(byte_order)?;
+        let drops_count = reader.read_u16(byte_order)?;
+        let timestamp_high = reader.read_u32(byte_order)?;
+        let timestamp_low = reader.read_u32(byte_order)?;
+        let captured_length = reader.read_u32(byte_order)?;
+        let original_length = reader.read_u32(byte_order)?;
+
+        let padded_length = pad_to_32(captured_length as usize);
+        if buffer.len() < padded_length {
+            buffer.resize(padded_length, 0);
+        }
+        reader.read_exact(&mut buffer[..padded_length])?;
+        let options_budget = (block_length as usize)
+            .saturating_sub(Self::minimum_size())
+            .saturating_sub(padded_length);
+        let options = BlockOptions::read_bounded_option(reader, byte_order, options_budget)?;
+        header.check_trailing_length(reader.read_bytes::<4>()?)?;
+        Ok(Self {
+            block_length,
+            interface_id,
+            drops_count,
+            timestamp_high,
+            timestamp_low,
+            captured_length,
+            original_length,
+            content: Cow::Borrowed(&buffer[..captured_length as usize]),
+            options,
+        })
+    }
+    fn write<W: Write>(
+        &self,
+        writer: &mut W,
+        byte_order: Endianness,
+    ) -> Result<(), crate::io::Error> {
+        write_block(writer, Self::block_id(), byte_order, |body| {
+            body.write_u16(self.interface_id, byte_order)?;
+            body.write_u16(self.drops_count, byte_order)?;
+            body.write_u32(self.timestamp_high, byte_order)?;
+            body.write_u32(self.timestamp_low, byte_order)?;
+            body.write_u32(self.content.len() as u32, byte_order)?;
+            body.write_u32(self.original_length, byte_order)?;
+            body.write_all(&self.content)?;
+            body.resize(20 + pad_to_32(self.content.len()), 0);
+            if let Some(options) = &self.options {
+                options.write(body, byte_order)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(feature = "tokio-async")]
+mod tokio_async {
+    use crate::pcap_ng::blocks::{LegacyPacketBlock, tokio_block::TokioAsyncBlock};
+
+    impl<'b> TokioAsyncBlock<'b> for LegacyPacketBlock<'b> {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pcap_ng::blocks::PcapNgBlock;
+
+    #[test]
+    fn parse_synthetic_block() -> anyhow::Result<()> {
+        let block = [
+            0x02, 0x00, 0x00, 0x00, // block type 2
+            0x28, 0x00, 0x00, 0x00, // block length 40
+            0x01, 0x00, // interface id
+            0x03, 0x00, // drops count
+            0x00, 0x00, 0x00, 0x00, // timestamp high
+            0x40, 0x42, 0x0F, 0x00, // timestamp low, 1 second in microseconds
+            0x05, 0x00, 0x00, 0x00, // captured length
+            0x3C, 0x00, 0x00, 0x00, // original length
+            0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0x00, 0x00, 0x00, // packet data and padding
+            0x28, 0x00, 0x00, 0x00, // trailing block length
+        ];
+        let mut reader = block.as_slice();
+        let header = BlockHeader::read(&mut reader)?;
+        let mut buffer = Vec::new();
+        let read = PcapNgBlock::read(&mut reader, &header, Endianness::LittleEndian, &mut buffer)?;
+        assert!(reader.is_empty());
+        let PcapNgBlock::LegacyPacket(packet) = read else {
+            panic!("expected a legacy packet block, got {read:?}");
+        };
+        assert_eq!(packet.block_length, 40);
+        assert_eq!(packet.interface_id, 1);
+        assert_eq!(packet.drops_count, 3);
+        assert_eq!(packet.original_length, 60);
+        assert_eq!(packet.content, &[0xAA, 0xBB, 0xCC, 0xDD, 0xEE][..]);
+        assert!(packet.options.is_none());
+        assert_eq!(
+            packet.timestamp(TsResolution::MICROSECONDS),
+            PacketTimestamp {
+                seconds: 1,
+                usec: 0
+            }
+        );
+
+        let mut written = Vec::new();
+        packet.write(&mut written, Endianness::LittleEndian)?;
+        assert_eq!(written, block);
+        Ok(())
+    }
+}
@@ -1,13 +1,16 @@
 //! Name Resolution Block (NRB)
-use std::io::Read;
+use alloc::{string::String, vec, vec::Vec};
+
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
 use crate::{
-    byte_order::{ByteOrder, Endianness, UndertminedByteOrder},
+    byte_order::{ByteOrder, Endianness, ReadExt, UndertminedByteOrder, WriteExt},
+    io::{Read, Write},
     pcap_ng::{
         PcapNgParseError,
-        blocks::{Block, BlockHeader},
+        blocks::{Block, BlockHeader, block_body_from_slice, write_block},
         options::BlockOptions,
-        pad_length_to_32_bytes,
+        padding::{pad_to_32, padding_bytes},
     },
 };
 /// A single name resolution record (e.g. IPv4-to-name, IPv6-to-name).
@@ -20,6 +23,43 @@ pub struct Record {
     /// Raw record bytes; interpretation depends on `record_type`.
     pub record_data: Vec<u8>,
 }
+impl Record {
+    /// `nrb_record_ipv4`: an IPv4 address followed by names
+    pub const IPV4: u16 = 1;
+    /// `nrb_record_ipv6`: an IPv6 address followed by names
+    pub const IPV6: u16 = 2;
+    /// Parses an IPv4 or IPv6 record into its address and names
+    ///
+    /// Returns `None` for other record types or if the record is too short to hold its address.
+    /// Names are the NUL terminated strings following the address. Invalid UTF-8 is replaced
+    pub fn resolved_name(&self) -> Option<ResolvedName> {
+        let (address, names) = match self.record_type {
+            Self::IPV4 => {
+                let (address, names) = self.record_data.split_first_chunk::<4>()?;
+                (IpAddr::V4(Ipv4Addr::from(*address)), names)
+            }
+            Self::IPV6 => {
+                let (address, names) = self.record_data.split_first_chunk::<16>()?;
+                (IpAddr::V6(Ipv6Addr::from(*address)), names)
+            }
+            _ => return None,
+        };
+        let names = names
+            .split(|byte| *byte == 0)
+            .filter(|name| !name.is_empty())
+            .map(|name| String::from_utf8_lossy(name).into_owned())
+            .collect();
+        Some(ResolvedName { address, names })
+    }
+}
+/// An address and the names it resolves to, parsed from a [`Record`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedName {
+    /// The resolved address
+    pub address: IpAddr,
+    /// Names for `address`
+    pub names: Vec<String>,
+}
 /// Wrapper around the list of resolution records contained in a Name
 /// Resolution Block.
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
@@ -47,7 +87,7 @@ impl Records {
                 break; // No more records
             }
             let length = record_length as usize;
-            let padded_length = pad_length_to_32_bytes(length);
+            let padded_length = pad_to_32(length);
             let mut data = vec![0u8; padded_length];
             reader.read_exact(&mut data)?;
             total_length += padded_length;
@@ -60,6 +100,50 @@ impl Records {
         }
         Ok((Self(records), total_length))
     }
+    /// Writes every record followed by the `nrb_record_end` marker
+    ///
+    /// Each record's length is taken from its `record_data` and the data is padded to 32 bits.
+    /// Returns the number of bytes written, or [`crate::io::ErrorKind::InvalidInput`] if a record
+    /// is longer than `u16::MAX` bytes
+    pub fn write<W: Write, B: ByteOrder>(
+        &self,
+        writer: &mut W,
+        byte_order: B,
+    ) -> Result<usize, crate::io::Error> {
+        let mut total_length = 0;
+        for record in &self.0 {
+            let length = u16::try_from(record.record_data.len()).map_err(|_| {
+                crate::io::Error::new(
+                    crate::io::ErrorKind::InvalidInput,
+                    "Name resolution record is longer than 65535 bytes",
+                )
+            })?;
+            writer.write_u16(record.record_type, byte_order)?;
+            writer.write_u16(length, byte_order)?;
+            writer.write_all(&record.record_data)?;
+            writer.write_all(&[0u8; 3][..padding_bytes(record.record_data.len())])?;
+            total_length += 4 + pad_to_32(record.record_data.len());
+        }
+        // nrb_record_end
+        writer.write_u32(0, byte_order)?;
+        Ok(total_length + 4)
+    }
+    /// Iterates over the IPv4 and IPv6 records as [`ResolvedName`]s
+    ///
+    /// Records of other types are skipped. See [`Record::resolved_name`]
+    pub fn iter(&self) -> impl Iterator<Item = ResolvedName> + '_ {
+        self.0.iter().filter_map(Record::resolved_name)
+    }
+}
+impl IntoIterator for Records {
+    type Item = ResolvedName;
+    type IntoIter =
+        core::iter::FilterMap<alloc::vec::IntoIter<Record>, fn(Record) -> Option<ResolvedName>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.0
+            .into_iter()
+            .filter_map(|record| record.resolved_name())
+    }
 }
 
 /// Maps numeric network addresses (IPv4/IPv6) to human-readable names.
@@ -100,13 +184,41 @@ impl<'b> Block<'b> for NameResolutionBlock {
             .saturating_sub(Self::minimum_size())
             .saturating_sub(bytes_read);
         let options = BlockOptions::read_bounded_option(reader, byte_order, options_budget)?;
-        reader.read_exact(&mut [0u8; 4])?; // Read the footer (4 bytes)
+        header.check_trailing_length(reader.read_bytes::<4>()?)?;
         Ok(Self {
             block_length,
             records,
             options,
         })
     }
+    fn write<W: Write>(
+        &self,
+        writer: &mut W,
+        byte_order: Endianness,
+    ) -> Result<(), crate::io::Error> {
+        write_block(writer, Self::block_id(), byte_order, |body| {
+            self.records.write(body, byte_order)?;
+            if let Some(options) = &self.options {
+                options.write(body, byte_order)?;
+            }
+            Ok(())
+        })
+    }
+}
+impl NameResolutionBlock {
+    /// Parses the block at the start of `bytes`
+    ///
+    /// Returns the block and the number of bytes consumed (the block length)
+    pub fn parse_from_slice(
+        bytes: &[u8],
+        byte_order: Endianness,
+    ) -> Result<(Self, usize), PcapNgParseError> {
+        let header = BlockHeader::parse_from_bytes(bytes)?;
+        let block_length = header.block_length_as_u32(byte_order);
+        let mut body = block_body_from_slice::<Self>(bytes, block_length)?;
+        let block = Self::read_with_header(&mut body, &header, Some(byte_order), &mut Vec::new())?;
+        Ok((block, block_length as usize))
+    }
 }
 #[cfg(feature = "tokio-async")]
 mod tokio_async {
@@ -120,3 +232,134 @@ mod tokio_async {
 //        Self::read_with_header::<_>(reader, &header, Some(byte_order))
 //    }
 //}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pcap_ng::options::OptionsBuilder;
+
+    #[test]
+    fn records_and_options() -> anyhow::Result<()> {
+        let block = NameResolutionBlock {
+            block_length: 0,
+            records: Records(vec![
+                Record {
+                    record_type: 1,
+                    record_length: 13,
+                    record_data: b"\x7f\0\0\x01localhost".to_vec(),
+                },
+                Record {
+                    record_type: 1,
+                    record_length: 11,
+                    record_data: b"\x0a\0\0\x01gateway".to_vec(),
+                },
+            ]),
+            options: Some(OptionsBuilder::new().comment("resolved").build()?),
+        };
+        for byte_order in [Endianness::LittleEndian, Endianness::BigEndian] {
+            let mut written = Vec::new();
+            block.write(&mut written, byte_order)?;
+
+            let mut reader = written.as_slice();
+            let header = BlockHeader::read(&mut reader)?;
+            let read = NameResolutionBlock::read_with_header(
+                &mut reader,
+                &header,
+                Some(byte_order),
+                &mut Vec::new(),
+            )?;
+            assert!(reader.is_empty(), "the whole block must be read");
+            assert_eq!(read.records, block.records);
+            assert_eq!(read.options, block.options);
+
+            // Grow the first record so the records run into the options
+            let mut corrupt = written.clone();
+            let length_offset = 8 + 2;
+            corrupt[length_offset..length_offset + 2].copy_from_slice(&byte_order.u16_to_bytes(17));
+            let mut reader = corrupt.as_slice();
+            let header = BlockHeader::read(&mut reader)?;
+            let result = NameResolutionBlock::read_with_header(
+                &mut reader,
+                &header,
+                Some(byte_order),
+                &mut Vec::new(),
+            );
+            assert!(result.is_err(), "{result:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn write_records() -> anyhow::Result<()> {
+        let records = Records(vec![
+            Record {
+                record_type: Record::IPV4,
+                record_length: 14,
+                record_data: b"\x7f\0\0\x01localhost\0".to_vec(),
+            },
+            Record {
+                record_type: Record::IPV4,
+                record_length: 12,
+                record_data: b"\x0a\0\0\x01gateway\0".to_vec(),
+            },
+        ]);
+        for byte_order in [Endianness::LittleEndian, Endianness::BigEndian] {
+            let mut written = Vec::new();
+            let length = records.write(&mut written, byte_order)?;
+            // 4 + 16, 4 + 12 and the end marker
+            assert_eq!(length, 40);
+            assert_eq!(written.len(), length);
+            assert_eq!(written[length - 4..], [0; 4]);
+
+            let (read, read_length) =
+                Records::read_from_reader(&mut written.as_slice(), byte_order)?;
+            assert_eq!(read, records);
+            assert_eq!(read_length, length);
+        }
+
+        let too_long = Records(vec![Record {
+            record_type: Record::IPV4,
+            record_length: 0,
+            record_data: vec![0; u16::MAX as usize + 1],
+        }]);
+        let err = too_long
+            .write(&mut Vec::new(), Endianness::LittleEndian)
+            .expect_err("record is too long");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        Ok(())
+    }
+
+    #[test]
+    fn iterate_records() {
+        let mut ipv6 = Ipv6Addr::LOCALHOST.octets().to_vec();
+        ipv6.extend_from_slice(b"ip6-localhost\0ip6-loopback\0");
+        let records = Records(vec![
+            Record {
+                record_type: Record::IPV4,
+                record_length: 13,
+                record_data: b"\x7f\0\0\x01localhost\0".to_vec(),
+            },
+            Record {
+                record_type: 3,
+                record_length: 6,
+                record_data: vec![0; 6],
+            },
+            Record {
+                record_type: Record::IPV6,
+                record_length: ipv6.len() as u16,
+                record_data: ipv6,
+            },
+        ]);
+        let expected = vec![
+            ResolvedName {
+                address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+                names: vec!["localhost".to_owned()],
+            },
+            ResolvedName {
+                address: IpAddr::V6(Ipv6Addr::LOCALHOST),
+                names: vec!["ip6-localhost".to_owned(), "ip6-loopback".to_owned()],
+            },
+        ];
+        assert_eq!(records.iter().collect::<Vec<_>>(), expected);
+        assert_eq!(records.into_iter().collect::<Vec<_>>(), expected);
+    }
+}
@@ -1,11 +1,12 @@
 //! Simple Packet Block (SPB)
-use std::io::Read;
+use alloc::{borrow::Cow, vec::Vec};
 
 use crate::{
-    byte_order::{Endianness, ReadExt, UndertminedByteOrder},
+    byte_order::{Endianness, ReadExt, UndertminedByteOrder, WriteExt},
+    io::{Read, Write},
     pcap_ng::{
         PcapNgParseError,
-        blocks::{Block, BlockHeader},
+        blocks::{Block, BlockHeader, block_body_from_slice, write_block},
     },
 };
 
@@ -20,8 +21,66 @@ pub struct SimplePacket<'b> {
     pub block_length: u32,
     /// Length of the packet on the wire.
     pub original_length: u32,
-    /// Captured packet bytes, borrowed from the reader's packet buffer.
-    pub content: &'b [u8],
+    /// Captured packet bytes, normally borrowed from the reader's packet buffer.
+    pub content: Cow<'b, [u8]>,
+}
+impl SimplePacket<'static> {
+    /// Reads the block like [`Block::read_with_header_no_block_check`] but skips the packet data
+    ///
+    /// The returned packet has no `content`. The length of the skipped packet data is returned with it.
+    #[cfg(feature = "std")]
+    pub(crate) fn read_skipping_content<R: Read>(
+        reader: &mut R,
+        header: &BlockHeader,
+        byte_order: Endianness,
+    ) -> Result<(Self, usize), PcapNgParseError> {
+        let original_length = reader.read_u32(byte_order)?;
+        let block_length = header.block_length_as_u32(byte_order);
+        let captured_padded = (block_length as usize).saturating_sub(16);
+        reader.skip_exact(captured_padded as u64)?;
+        header.check_trailing_length(reader.read_bytes::<4>()?)?;
+        let packet = Self {
+            block_length,
+            original_length,
+            content: Cow::Borrowed(&[]),
+        };
+        Ok((packet, captured_padded.min(original_length as usize)))
+    }
+}
+impl<'b> SimplePacket<'b> {
+    /// Parses the block at the start of `bytes` without copying the packet data
+    ///
+    /// Returns the packet borrowing its content from `bytes` and the number of bytes consumed (the block length)
+    pub fn parse_from_slice(
+        bytes: &'b [u8],
+        byte_order: Endianness,
+    ) -> Result<(Self, usize), PcapNgParseError> {
+        let header = BlockHeader::parse_from_bytes(bytes)?;
+        header.matches_block_id::<Self>()?;
+        let block_length = header.block_length_as_u32(byte_order);
+        let mut body = block_body_from_slice::<Self>(bytes, block_length)?;
+        let original_length = body.read_u32(byte_order)?;
+        // Same layout as `read_with_header_no_block_check`: the body ends with the 4 byte footer
+        let captured_padded = body.len() - 4;
+        header.check_trailing_length(body[captured_padded..].try_into()?)?;
+        let content_len = captured_padded.min(original_length as usize);
+        Ok((
+            Self {
+                block_length,
+                original_length,
+                content: Cow::Borrowed(&body[..content_len]),
+            },
+            block_length as usize,
+        ))
+    }
+    /// Copies the content if it is borrowed so the packet no longer borrows the reader's buffer
+    pub fn into_owned(self) -> SimplePacket<'static> {
+        SimplePacket {
+            block_length: self.block_length,
+            original_length: self.original_length,
+            content: Cow::Owned(self.content.into_owned()),
+        }
+    }
 }
 impl<'b> Block<'b> for SimplePacket<'b> {
     fn block_id() -> u32 {
@@ -75,12 +134,21 @@ impl<'b> Block<'b> for SimplePacket<'b> {
         // the IDB's snap_length).
         let content_len = captured_padded.min(original_length as usize);
 
-        // Read the footer (4 bytes)
-        reader.read_bytes::<4>()?;
+        header.check_trailing_length(reader.read_bytes::<4>()?)?;
         Ok(Self {
             block_length,
             original_length,
-            content: &buffer[..content_len],
+            content: Cow::Borrowed(&buffer[..content_len]),
+        })
+    }
+    fn write<W: Write>(
+        &self,
+        writer: &mut W,
+        byte_order: Endianness,
+    ) -> Result<(), crate::io::Error> {
+        write_block(writer, Self::block_id(), byte_order, |body| {
+            body.write_u32(self.original_length, byte_order)?;
+            body.write_all(&self.content)
         })
     }
 }
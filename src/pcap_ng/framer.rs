@@ -0,0 +1,133 @@
+//! Parsing pcap-ng blocks from message based transports
+use alloc::vec::Vec;
+
+use crate::{
+    byte_order::{Endianness, UnexpectedSize},
+    pcap_ng::{
+        PcapNgParseError,
+        blocks::{BlockHeader, PcapNgBlock},
+    },
+};
+
+/// Parses pcap-ng blocks that arrive one block per message
+///
+/// Unlike the streaming readers, each message must contain exactly one complete block.
+/// Bytes after the block are an error instead of the start of the next block.
+///
+/// Section header blocks set the byte order used for the blocks that follow them.
+#[derive(Debug, Clone)]
+pub struct BlockFramer {
+    /// Byte order of the current section
+    byte_order: Endianness,
+    /// Reusable scratch buffer for packet contents
+    buffer: Vec<u8>,
+}
+impl BlockFramer {
+    /// Creates a framer for blocks in `byte_order`
+    ///
+    /// The byte order is replaced by the byte order of any section header block parsed
+    pub fn new(byte_order: Endianness) -> Self {
+        Self {
+            byte_order,
+            buffer: Vec::new(),
+        }
+    }
+    /// Byte order of the current section
+    pub fn byte_order(&self) -> Endianness {
+        self.byte_order
+    }
+    /// Parses the single block in `message`
+    ///
+    /// Returns [`PcapNgParseError::UnexpectedSize`] if bytes remain after the block.
+    /// A message shorter than its block returns an [`crate::io::ErrorKind::UnexpectedEof`] error
+    pub fn parse<'b>(&'b mut self, message: &[u8]) -> Result<PcapNgBlock<'b>, PcapNgParseError> {
+        let header = BlockHeader::parse_from_bytes(message)?;
+        let mut body = &message[8..];
+        let block = PcapNgBlock::read(&mut body, &header, self.byte_order, &mut self.buffer)?;
+        if !body.is_empty() {
+            return Err(PcapNgParseError::UnexpectedSize(UnexpectedSize {
+                name: "Block",
+                expected: message.len() - body.len(),
+                got: message.len(),
+            }));
+        }
+        if let PcapNgBlock::SectionHeader(section) = &block {
+            self.byte_order = section.byte_order;
+        }
+        Ok(block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+    use crate::pcap_ng::blocks::{Block, EnhancedPacket, SectionHeaderBlock};
+
+    fn enhanced_packet(byte_order: Endianness) -> anyhow::Result<Vec<u8>> {
+        let mut message = Vec::new();
+        EnhancedPacket {
+            block_length: 0,
+            interface_id: 0,
+            timestamp_high: 0,
+            timestamp_low: 1,
+            captured_length: 3,
+            original_length: 3,
+            content: Cow::Borrowed(&[1, 2, 3]),
+            options: None,
+        }
+        .write(&mut message, byte_order)?;
+        Ok(message)
+    }
+
+    #[test]
+    fn parse_exact_block() -> anyhow::Result<()> {
+        let mut framer = BlockFramer::new(Endianness::LittleEndian);
+        let message = enhanced_packet(Endianness::LittleEndian)?;
+        let PcapNgBlock::EnhancedPacket(packet) = framer.parse(&message)? else {
+            panic!("expected an enhanced packet");
+        };
+        assert_eq!(packet.content, &[1, 2, 3][..]);
+        assert_eq!(packet.block_length as usize, message.len());
+
+        // A big endian section switches the byte order of the following blocks
+        let mut section = Vec::new();
+        SectionHeaderBlock::new(Endianness::BigEndian)
+            .write(&mut section, Endianness::BigEndian)?;
+        assert!(matches!(
+            framer.parse(&section)?,
+            PcapNgBlock::SectionHeader(_)
+        ));
+        assert_eq!(framer.byte_order(), Endianness::BigEndian);
+        let message = enhanced_packet(Endianness::BigEndian)?;
+        assert!(matches!(
+            framer.parse(&message)?,
+            PcapNgBlock::EnhancedPacket(_)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn reject_trailing_bytes() -> anyhow::Result<()> {
+        let mut framer = BlockFramer::new(Endianness::LittleEndian);
+        let mut message = enhanced_packet(Endianness::LittleEndian)?;
+        let block_length = message.len();
+        message.extend_from_slice(&[0xFF; 4]);
+        let err = framer.parse(&message).expect_err("trailing bytes");
+        let PcapNgParseError::UnexpectedSize(size) = err else {
+            panic!("unexpected error {err:?}");
+        };
+        assert_eq!(size.expected, block_length);
+        assert_eq!(size.got, block_length + 4);
+
+        let err = framer
+            .parse(&message[..block_length - 4])
+            .expect_err("truncated block");
+        assert!(
+            matches!(&err, PcapNgParseError::IO(io) if io.kind() == std::io::ErrorKind::UnexpectedEof),
+            "unexpected error {err:?}"
+        );
+        Ok(())
+    }
+}
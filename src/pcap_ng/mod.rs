@@ -3,12 +3,26 @@
 //! [Source](https://www.ietf.org/archive/id/draft-tuexen-opsawg-pcapng-03.html)
 //!
 //! Currently, only supports reading files from beginning to end and does not support reverse reading.
+//!
+//! The readers and writers require the `std` feature. [`blocks`], [`options`] and [`BlockFramer`] only need
+//! the `alloc` feature, so blocks can be parsed from byte slices with the `parse_from_slice` functions without `std`.
+#[cfg(feature = "alloc")]
 use thiserror::Error;
 
-use crate::{byte_order::Endianness, link_type::InvalidLinkType};
+#[cfg(feature = "alloc")]
+use crate::{byte_order::Endianness, link_type::InvalidLinkType, utils::HexBytes};
+#[cfg(feature = "alloc")]
 pub mod blocks;
+#[cfg(feature = "alloc")]
+mod framer;
+#[cfg(feature = "alloc")]
+pub use framer::BlockFramer;
+#[cfg(feature = "alloc")]
 pub mod options;
+pub mod padding;
+#[cfg(feature = "std")]
 mod sync;
+#[cfg(feature = "std")]
 pub use sync::*;
 #[cfg(feature = "tokio-async")]
 mod tokio_impl;
@@ -19,11 +33,17 @@ pub use tokio_impl::*;
 /// All pcap-ng files should start with this magic number
 pub const PCAP_NG_MAGIC: [u8; 4] = [0x0A, 0x0D, 0x0D, 0x0A];
 /// Errors returned while parsing a pcap-ng file.
+#[cfg(feature = "alloc")]
 #[derive(Debug, Error)]
 pub enum PcapNgParseError {
     /// The block ID read from the file did not match the expected ID for the
     /// block type being parsed (in either endianness).
-    #[error("Invalid block ID: expected {expected_be:?} or {expected_le:?}, got {got:?}")]
+    #[error(
+        "Invalid block ID: expected {} or {}, got {}",
+        HexBytes(.expected_be),
+        HexBytes(.expected_le),
+        HexBytes(.got)
+    )]
     UnexpectedBlockId {
         /// Expected block ID encoded as big-endian.
         expected_be: [u8; 4],
@@ -33,17 +53,23 @@ pub enum PcapNgParseError {
         got: [u8; 4],
     },
     /// The endianness magic in the section header was not recognized.
-    #[error("Invalid endianness: got {got:?}")]
+    #[error("Invalid endianness: got {}", HexBytes(.got))]
     InvalidEndianness {
         /// Bytes that were actually read.
         got: [u8; 4],
     },
     /// This should never happen. But preventing panics
     #[error(transparent)]
-    TryFromSliceError(#[from] std::array::TryFromSliceError),
+    TryFromSliceError(#[from] core::array::TryFromSliceError),
     /// An underlying I/O error occurred.
     #[error(transparent)]
-    IO(#[from] std::io::Error),
+    IO(#[from] crate::io::Error),
+    /// The file ended inside a block header
+    #[error("File ended after {read} of the 8 bytes of a block header")]
+    TruncatedBlock {
+        /// Bytes of the block header that were read.
+        read: usize,
+    },
     /// The block's declared length was smaller than the minimum required for
     /// its type.
     #[error("Minimum size for this block is {0} bytes, but got {1} bytes")]
@@ -61,8 +87,63 @@ pub enum PcapNgParseError {
     /// inferred from the block ID.
     #[error(transparent)]
     UndeterminedByteOrder(#[from] crate::byte_order::UndertminedByteOrder),
+    /// A packet referenced an interface id that has not been declared by an
+    /// interface description block in the current section.
+    #[error(
+        "Packet references undeclared interface {interface_id} ({declared} interfaces declared)"
+    )]
+    UndeclaredInterface {
+        /// Interface id referenced by the packet.
+        interface_id: u32,
+        /// Number of interfaces declared in the current section.
+        declared: usize,
+    },
+    /// An enhanced packet captured more bytes than its interface's snap length.
+    ///
+    /// Only returned when [`SyncPcapNgReader::strict_snaplen`] is enabled.
+    #[error(
+        "Invalid packet length: snap length {snap_length} of interface {interface_id} is less than captured length {captured_length}"
+    )]
+    SnapLengthExceeded {
+        /// Interface id referenced by the packet.
+        interface_id: u32,
+        /// Snap length of the interface.
+        snap_length: u32,
+        /// Captured length of the packet.
+        captured_length: u32,
+    },
+    /// The trailing block length did not match the block length in the block
+    /// header, so the block body was not parsed to exactly its end.
+    #[error(
+        "Trailing block length {} does not match block length {}",
+        HexBytes(.trailing_length),
+        HexBytes(.block_length)
+    )]
+    TrailingLengthMismatch {
+        /// Raw block length from the block header.
+        block_length: [u8; 4],
+        /// Raw trailing block length read after the block body.
+        trailing_length: [u8; 4],
+    },
+    /// The section header's block length is only valid in the opposite byte
+    /// order to the byte-order magic in its body.
+    #[error(
+        "Section header byte order {byte_order:?} conflicts with its block length {block_length}"
+    )]
+    SectionByteOrderMismatch {
+        /// Byte order declared by the byte-order magic.
+        byte_order: Endianness,
+        /// Block length decoded in that byte order.
+        block_length: u32,
+    },
+    /// A section header has a major version this crate does not read.
+    ///
+    /// Only returned when [`SyncPcapNgReader::strict_version`] is enabled.
+    #[error("Unsupported section version {}.{}", .0.major, .0.minor)]
+    InvalidVersion(crate::Version),
 }
 
+#[cfg(feature = "alloc")]
 impl Endianness {
     /// Parses the section header byte-order magic to recover the file's
     /// [`Endianness`].
@@ -75,27 +156,21 @@ impl Endianness {
     }
 }
 
-/// Pads the length to the next multiple of 4 bytes (32-bit alignment)
-pub(crate) fn pad_length_to_32_bytes(length: usize) -> usize {
-    if length.is_multiple_of(4) {
-        length
-    } else {
-        length + (4 - (length % 4))
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     #[test]
-    fn test_pad_length_to_32_bytes() {
-        assert_eq!(pad_length_to_32_bytes(0), 0);
-        assert_eq!(pad_length_to_32_bytes(1), 4);
-        assert_eq!(pad_length_to_32_bytes(3), 4);
-        assert_eq!(pad_length_to_32_bytes(4), 4);
-        assert_eq!(pad_length_to_32_bytes(5), 8);
-        assert_eq!(pad_length_to_32_bytes(7), 8);
-        assert_eq!(pad_length_to_32_bytes(8), 8);
-        assert_eq!(pad_length_to_32_bytes(9), 12);
+    fn error_messages_use_hex() {
+        let err = PcapNgParseError::UnexpectedBlockId {
+            expected_be: [0x00, 0x00, 0x00, 0x06],
+            expected_le: [0x06, 0x00, 0x00, 0x00],
+            got: *b"GIF8",
+        };
+        assert_eq!(
+            err.to_string(),
+            "Invalid block ID: expected 0x00000006 or 0x06000000, got 0x47494638 (\"GIF8\")"
+        );
+        let err = Endianness::from_pcap_ng_bytes(&[0xDE, 0xAD, 0xBE, 0xEF]).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid endianness: got 0xDEADBEEF");
     }
 }
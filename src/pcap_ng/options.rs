@@ -1,11 +1,13 @@
 //! Block Options for pcap-ng files
 //!
 //! See [3.5 Options](https://www.ietf.org/archive/id/draft-tuexen-opsawg-pcapng-03.html#name-options) for more details
+use alloc::{borrow::Cow, string::String, vec, vec::Vec};
+
 use crate::{
     byte_order::{ByteOrder, ReadExt, WriteExt},
-    pcap_ng::pad_length_to_32_bytes,
+    io::{Read, Write},
+    pcap_ng::padding::{pad_to_32, padding_bytes},
 };
-use std::io::{Read, Write};
 use thiserror::Error;
 
 macro_rules! define_options_enum {
@@ -114,7 +116,8 @@ pub enum InvalidOption {
 impl BlockOption {
     /// Creates a new BlockOption
     ///
-    /// `pen` may only be set for custom-option codes (2988, 2989, 19372, 19373).
+    /// `pen` must be set for custom-option codes (2988, 2989, 19372, 19373)
+    /// and may not be set for any other code.
     /// When `pen` is set, the wire-format option length is `4 + value.len()`
     /// because the pcapng spec includes the PEN in the option length
     /// (see RFC pcapng §3.5.2).
@@ -126,8 +129,10 @@ impl BlockOption {
         let is_custom_code = StandardOptions::try_from(option_code)
             .map(|o| o.is_custom())
             .unwrap_or(false);
-        if pen.is_some() && !is_custom_code {
-            return Err(InvalidOption::UnexpectedPen(option_code));
+        match (pen.is_some(), is_custom_code) {
+            (true, false) => return Err(InvalidOption::UnexpectedPen(option_code)),
+            (false, true) => return Err(InvalidOption::CustomRequiresPen),
+            _ => {}
         }
         let option_value = option_value.into();
         let option_length = if pen.is_some() {
@@ -143,10 +148,107 @@ impl BlockOption {
         };
         Ok(result)
     }
+    /// Builds an option from its padded body as read from a file
+    ///
+    /// Custom options take their PEN from the first 4 bytes of the body.
+    fn from_body<B: ByteOrder>(code: u16, length: u16, mut body: Vec<u8>, byte_order: B) -> Self {
+        let is_custom = StandardOptions::try_from(code)
+            .map(|o| o.is_custom())
+            .unwrap_or(false);
+        let (pen, value) = if is_custom && length >= 4 {
+            let pen = byte_order.u32_from_bytes([body[0], body[1], body[2], body[3]]);
+            (Some(pen), body[4..length as usize].to_vec())
+        } else {
+            body.truncate(length as usize);
+            (None, body)
+        };
+        Self {
+            code,
+            length,
+            pen,
+            value,
+        }
+    }
+    /// Decodes the value as a u32 in `byte_order`
+    ///
+    /// Returns `None` if the value is not exactly 4 bytes
+    pub fn as_u32<B: ByteOrder>(&self, byte_order: B) -> Option<u32> {
+        Some(byte_order.u32_from_bytes(self.value.as_slice().try_into().ok()?))
+    }
+    /// Decodes the value as a u64 in `byte_order`
+    ///
+    /// Returns `None` if the value is not exactly 8 bytes
+    pub fn as_u64<B: ByteOrder>(&self, byte_order: B) -> Option<u64> {
+        Some(byte_order.u64_from_bytes(self.value.as_slice().try_into().ok()?))
+    }
+    /// Decodes the value as an i64 in `byte_order`
+    ///
+    /// Returns `None` if the value is not exactly 8 bytes
+    pub fn as_i64<B: ByteOrder>(&self, byte_order: B) -> Option<i64> {
+        self.as_u64(byte_order).map(|value| value as i64)
+    }
     /// Returns the number of padding bytes required to align the option to
     /// a 4-byte (32-bit) boundary.
     pub fn padding_length(&self) -> usize {
-        pad_length_to_32_bytes(self.length as usize) - self.length as usize
+        padding_bytes(self.length as usize)
+    }
+    /// Serialized size of the option: the 4-byte code and length, the PEN of custom
+    /// options, the value and its padding
+    pub fn byte_len(&self) -> usize {
+        4 + self.length as usize + self.padding_length()
+    }
+}
+/// Builds a [`BlockOptions`] for writing
+///
+/// Each option is validated with [`BlockOption::new`]; the first error is
+/// returned from [`OptionsBuilder::build`].
+#[derive(Debug, Clone, Default)]
+pub struct OptionsBuilder {
+    options: Vec<BlockOption>,
+    error: Option<InvalidOption>,
+}
+impl OptionsBuilder {
+    /// Creates an empty builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Adds an `opt_comment` option
+    pub fn comment(self, comment: &str) -> Self {
+        self.raw(StandardOptions::Comment as u16, comment)
+    }
+    /// Adds a custom UTF-8 option that may be copied to new files
+    pub fn custom_utf8(self, pen: u32, value: &str) -> Self {
+        self.custom(StandardOptions::CustomUTF8Copied as u16, pen, value)
+    }
+    /// Adds a custom binary option that may be copied to new files
+    pub fn custom_binary(self, pen: u32, value: &[u8]) -> Self {
+        self.custom(StandardOptions::CustomBinaryCopied as u16, pen, value)
+    }
+    /// Adds a custom option with the given code and Private Enterprise Number (PEN)
+    pub fn custom(self, code: u16, pen: u32, value: impl Into<Vec<u8>>) -> Self {
+        self.push(BlockOption::new(code, Some(pen), value))
+    }
+    /// Adds an option with the given code and value
+    ///
+    /// Custom option codes require a PEN; use [`OptionsBuilder::custom`] for those.
+    pub fn raw(self, code: u16, value: impl Into<Vec<u8>>) -> Self {
+        self.push(BlockOption::new(code, None, value))
+    }
+    fn push(mut self, option: Result<BlockOption, InvalidOption>) -> Self {
+        match option {
+            Ok(option) => self.options.push(option),
+            Err(err) => {
+                self.error.get_or_insert(err);
+            }
+        }
+        self
+    }
+    /// Returns the options or the first error encountered while adding them
+    pub fn build(self) -> Result<BlockOptions, InvalidOption> {
+        match self.error {
+            Some(err) => Err(err),
+            None => Ok(BlockOptions(self.options)),
+        }
     }
 }
 /// Errors returned while parsing a block's options list.
@@ -154,10 +256,39 @@ impl BlockOption {
 pub enum OptionParseError {
     /// An underlying I/O error occurred.
     #[error(transparent)]
-    IO(#[from] std::io::Error),
+    IO(#[from] crate::io::Error),
     /// A fixed-size field had the wrong number of bytes.
     #[error(transparent)]
     UnexpectedSize(#[from] crate::byte_order::UnexpectedSize),
+    /// The options did not end within their byte budget.
+    #[error("Options exceed their budget of {max_bytes} bytes")]
+    BudgetExceeded {
+        /// The byte budget for the options
+        max_bytes: usize,
+    },
+    /// More options were present than the parser allows.
+    #[error("More than {max_options} options")]
+    TooManyOptions {
+        /// The maximum number of options
+        max_options: usize,
+    },
+    /// An option that must be UTF-8 was not valid UTF-8.
+    #[error("Option {code} is not valid UTF-8")]
+    InvalidUtf8 {
+        /// The option code
+        code: u16,
+    },
+}
+/// Adds `length` to the bytes `consumed` so far, failing if the result is over `max_bytes`
+fn check_budget(
+    consumed: usize,
+    length: usize,
+    max_bytes: usize,
+) -> Result<usize, OptionParseError> {
+    consumed
+        .checked_add(length)
+        .filter(|consumed| *consumed <= max_bytes)
+        .ok_or(OptionParseError::BudgetExceeded { max_bytes })
 }
 /// Represents a collection of options for a block
 ///
@@ -165,6 +296,45 @@ pub enum OptionParseError {
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct BlockOptions(pub Vec<BlockOption>);
 impl BlockOptions {
+    /// Maximum number of options read by the unbounded readers such as [`read_in`](Self::read_in)
+    pub const DEFAULT_MAX_OPTIONS: usize = 1024;
+    /// Returns the first option with `code`
+    pub fn get(&self, code: u16) -> Option<&BlockOption> {
+        self.0.iter().find(|option| option.code == code)
+    }
+    /// Returns every option with `code` in the order they were written
+    ///
+    /// Some options such as comments may appear more than once
+    pub fn get_all(&self, code: u16) -> impl Iterator<Item = &BlockOption> {
+        self.0.iter().filter(move |option| option.code == code)
+    }
+    /// Checks that every option that must be a UTF-8 string is valid UTF-8
+    ///
+    /// Comments and custom UTF-8 options are always checked along with any option whose code is in `utf8_codes`.
+    /// The meaning of codes other than the standard options depends on the block type.
+    pub fn validate_utf8(&self, utf8_codes: &[u16]) -> Result<(), OptionParseError> {
+        const ALWAYS_UTF8: [StandardOptions; 3] = [
+            StandardOptions::Comment,
+            StandardOptions::CustomUTF8Copied,
+            StandardOptions::CustomUTF8NotCopied,
+        ];
+        let must_be_utf8 = |code: u16| {
+            utf8_codes.contains(&code) || ALWAYS_UTF8.iter().any(|option| *option as u16 == code)
+        };
+        match self.0.iter().find(|option| {
+            must_be_utf8(option.code) && core::str::from_utf8(&option.value).is_err()
+        }) {
+            Some(option) => Err(OptionParseError::InvalidUtf8 { code: option.code }),
+            None => Ok(()),
+        }
+    }
+    /// Returns the value of the first option with `code` as a string
+    ///
+    /// Invalid UTF-8 is replaced
+    pub fn get_utf8(&self, code: u16) -> Option<Cow<'_, str>> {
+        self.get(code)
+            .map(|option| String::from_utf8_lossy(&option.value))
+    }
     /// Decodes a single TLV option from `bytes`, starting at `pos`.
     ///
     /// Returns the parsed option and the new cursor position, or `Ok(None)`
@@ -187,7 +357,7 @@ impl BlockOptions {
             return Ok(None);
         }
         let body_start = pos + 4;
-        let padded_length = pad_length_to_32_bytes(option_length as usize);
+        let padded_length = pad_to_32(option_length as usize);
         if body_start.saturating_add(padded_length) > bytes.len() {
             return Ok(None);
         }
@@ -269,70 +439,97 @@ impl BlockOptions {
     /// Prefer [`read_bounded`](Self::read_bounded) when the caller knows the
     /// block-length: this unbounded variant will read past the current block
     /// if the file omits the end-of-options marker.
+    ///
+    /// At most [`DEFAULT_MAX_OPTIONS`](Self::DEFAULT_MAX_OPTIONS) options are read so a stream
+    /// without a terminator can not be read forever.
+    ///
+    /// Returns the number of bytes consumed including padding and the end-of-options marker
     pub fn read_in<R: Read, B: ByteOrder>(
         &mut self,
         reader: &mut R,
         byte_order: B,
-    ) -> Result<(), OptionParseError> {
+    ) -> Result<usize, OptionParseError> {
+        self.read_in_with_limits(reader, byte_order, usize::MAX, Self::DEFAULT_MAX_OPTIONS)
+    }
+    /// Like [`read_in`](Self::read_in) but stops with an error instead of trusting the terminator
+    ///
+    /// `max_bytes` is the byte budget for the options including the end-of-options marker, normally the
+    /// remaining length of the enclosing block. An option that would end past the budget returns
+    /// [`OptionParseError::BudgetExceeded`] before its value is read. Reading more than `max_options` options
+    /// returns [`OptionParseError::TooManyOptions`].
+    ///
+    /// Returns the number of bytes consumed including padding and the end-of-options marker
+    pub fn read_in_with_limits<R: Read, B: ByteOrder>(
+        &mut self,
+        reader: &mut R,
+        byte_order: B,
+        max_bytes: usize,
+        max_options: usize,
+    ) -> Result<usize, OptionParseError> {
+        let mut consumed: usize = 0;
+        let mut read = 0;
         loop {
+            consumed = check_budget(consumed, 4, max_bytes)?;
             let option_code = reader.read_u16(byte_order)?;
             let option_length = reader.read_u16(byte_order)?;
             if option_code == 0 && option_length == 0 {
-                return Ok(());
+                return Ok(consumed);
             }
-            let padded_length = pad_length_to_32_bytes(option_length as usize);
+            if read == max_options {
+                return Err(OptionParseError::TooManyOptions { max_options });
+            }
+            let padded_length = pad_to_32(option_length as usize);
+            consumed = check_budget(consumed, padded_length, max_bytes)?;
             let mut body = vec![0u8; padded_length];
             reader.read_exact(&mut body)?;
-            let is_custom = StandardOptions::try_from(option_code)
-                .map(|o| o.is_custom())
-                .unwrap_or(false);
-            let (pen, value) = if is_custom && option_length >= 4 {
-                let pen = byte_order.u32_from_bytes([body[0], body[1], body[2], body[3]]);
-                let mut value = body[4..option_length as usize].to_vec();
-                value.truncate(option_length as usize - 4);
-                (Some(pen), value)
-            } else {
-                body.truncate(option_length as usize);
-                (None, body)
-            };
-            self.0.push(BlockOption {
-                code: option_code,
-                length: option_length,
-                pen,
-                value,
-            });
+            self.0.push(BlockOption::from_body(
+                option_code,
+                option_length,
+                body,
+                byte_order,
+            ));
+            read += 1;
         }
     }
     /// Reads a complete options list from `reader` (unbounded).
+    ///
+    /// Returns the options and the number of bytes consumed. See [`read_in`](Self::read_in)
     pub fn read<R: Read, B: ByteOrder>(
         reader: &mut R,
         byte_order: B,
-    ) -> Result<Self, OptionParseError> {
+    ) -> Result<(Self, usize), OptionParseError> {
         let mut options = Self::default();
-        options.read_in(reader, byte_order)?;
-        Ok(options)
+        let consumed = options.read_in(reader, byte_order)?;
+        Ok((options, consumed))
     }
 
     /// Reads an options list and returns `None` if it was empty (unbounded).
+    ///
+    /// Returns the options and the number of bytes consumed. See [`read_in`](Self::read_in)
     pub fn read_option<R: Read, B: ByteOrder>(
         reader: &mut R,
         byte_order: B,
-    ) -> Result<Option<Self>, OptionParseError> {
-        let mut options = Self::default();
-        options.read_in(reader, byte_order)?;
-        if options.0.is_empty() {
-            return Ok(None);
-        }
-        Ok(Some(options))
+    ) -> Result<(Option<Self>, usize), OptionParseError> {
+        let (options, consumed) = Self::read(reader, byte_order)?;
+        Ok(((!options.0.is_empty()).then_some(options), consumed))
     }
 
+    /// Number of bytes [`Self::write`] writes, including padding and the
+    /// end-of-options marker
+    ///
+    /// Lets block writers compute their block length without writing the options first
+    pub fn byte_len(&self) -> usize {
+        self.0.iter().map(BlockOption::byte_len).sum::<usize>() + 4
+    }
     /// Writes all options to `writer`, including padding and the
     /// end-of-options marker.
+    ///
+    /// Returns the number of bytes written, which is always [`Self::byte_len`]
     pub fn write<W: Write>(
         &self,
         writer: &mut W,
         byte_order: impl ByteOrder,
-    ) -> Result<(), std::io::Error> {
+    ) -> Result<usize, crate::io::Error> {
         for option in &self.0 {
             writer.write_u16(option.code, byte_order)?;
             writer.write_u16(option.length, byte_order)?;
@@ -349,7 +546,7 @@ impl BlockOptions {
         }
         writer.write_u16(0, byte_order)?; // End of options
         writer.write_u16(0, byte_order)?; // End of options length
-        Ok(())
+        Ok(self.byte_len())
     }
 }
 #[cfg(feature = "tokio-async")]
@@ -359,56 +556,55 @@ mod tokio_async {
     use crate::{
         byte_order::{ByteOrder, tokio_async::AsyncReadExt as InternalAsyncReadExt},
         pcap_ng::{
-            options::{BlockOption, BlockOptions, OptionParseError, StandardOptions},
-            pad_length_to_32_bytes,
+            options::{BlockOption, BlockOptions, OptionParseError},
+            padding::pad_to_32,
         },
     };
 
     impl BlockOptions {
         /// Async counterpart to [`BlockOptions::read_in`] (unbounded).
+        ///
+        /// Like the sync version at most [`BlockOptions::DEFAULT_MAX_OPTIONS`] options are read.
         pub async fn read_async_in<R: AsyncRead + Unpin, B: ByteOrder>(
             &mut self,
             reader: &mut R,
             byte_order: B,
-        ) -> Result<(), OptionParseError> {
+        ) -> Result<usize, OptionParseError> {
+            let mut read = 0;
+            let mut consumed = 4;
             loop {
                 let option_code = <R as InternalAsyncReadExt>::read_u16(reader, byte_order).await?;
                 let option_length =
                     <R as InternalAsyncReadExt>::read_u16(reader, byte_order).await?;
                 if option_code == 0 && option_length == 0 {
-                    return Ok(());
+                    return Ok(consumed);
+                }
+                if read == Self::DEFAULT_MAX_OPTIONS {
+                    return Err(OptionParseError::TooManyOptions {
+                        max_options: Self::DEFAULT_MAX_OPTIONS,
+                    });
                 }
-                let padded_length = pad_length_to_32_bytes(option_length as usize);
+                let padded_length = pad_to_32(option_length as usize);
                 let mut body = vec![0u8; padded_length];
                 reader.read_exact(&mut body).await?;
-                let is_custom = StandardOptions::try_from(option_code)
-                    .map(|o| o.is_custom())
-                    .unwrap_or(false);
-                let (pen, value) = if is_custom && option_length >= 4 {
-                    let pen = byte_order.u32_from_bytes([body[0], body[1], body[2], body[3]]);
-                    let mut value = body[4..option_length as usize].to_vec();
-                    value.truncate(option_length as usize - 4);
-                    (Some(pen), value)
-                } else {
-                    body.truncate(option_length as usize);
-                    (None, body)
-                };
-                self.0.push(BlockOption {
-                    code: option_code,
-                    length: option_length,
-                    pen,
-                    value,
-                });
+                consumed += 4 + padded_length;
+                self.0.push(BlockOption::from_body(
+                    option_code,
+                    option_length,
+                    body,
+                    byte_order,
+                ));
+                read += 1;
             }
         }
         /// Async counterpart to [`BlockOptions::read`] (unbounded).
         pub async fn read_async<R: AsyncRead + Unpin, B: ByteOrder>(
             reader: &mut R,
             byte_order: B,
-        ) -> Result<Self, OptionParseError> {
+        ) -> Result<(Self, usize), OptionParseError> {
             let mut options = Self::default();
-            options.read_async_in(reader, byte_order).await?;
-            Ok(options)
+            let consumed = options.read_async_in(reader, byte_order).await?;
+            Ok((options, consumed))
         }
         /// Async counterpart to [`BlockOptions::read_bounded`].
         ///
@@ -455,6 +651,53 @@ mod tests {
     use super::*;
     use crate::byte_order::LittleEndian;
 
+    #[test]
+    fn numeric_values() -> anyhow::Result<()> {
+        use crate::byte_order::BigEndian;
+
+        let value = 0x0102_0304_0506_0708u64;
+        let little = BlockOption::new(8, None, value.to_le_bytes())?;
+        assert_eq!(little.as_u64(LittleEndian), Some(value));
+        let big = BlockOption::new(8, None, value.to_be_bytes())?;
+        assert_eq!(big.as_u64(BigEndian), Some(value));
+        assert_eq!(big.as_u64(LittleEndian), Some(value.swap_bytes()));
+        assert_eq!(big.as_u32(BigEndian), None);
+
+        let negative = BlockOption::new(14, None, (-3600i64).to_be_bytes())?;
+        assert_eq!(negative.as_i64(BigEndian), Some(-3600));
+        let short = BlockOption::new(2, None, 7u32.to_le_bytes())?;
+        assert_eq!(short.as_u32(LittleEndian), Some(7));
+        assert_eq!(short.as_u64(LittleEndian), None);
+        assert_eq!(short.as_i64(LittleEndian), None);
+        Ok(())
+    }
+
+    #[test]
+    fn get_by_code() -> anyhow::Result<()> {
+        let options = OptionsBuilder::new()
+            .comment("first")
+            .raw(2, b"name".to_vec())
+            .comment("second")
+            .build()?;
+        let comment = StandardOptions::Comment as u16;
+        assert_eq!(
+            options.get(comment).map(|option| option.value.as_slice()),
+            Some(&b"first"[..])
+        );
+        assert_eq!(
+            options
+                .get_all(comment)
+                .map(|option| option.value.as_slice())
+                .collect::<Vec<_>>(),
+            [&b"first"[..], &b"second"[..]]
+        );
+        assert_eq!(options.get_utf8(comment).as_deref(), Some("first"));
+        assert_eq!(options.get_utf8(2).as_deref(), Some("name"));
+        assert!(options.get(3).is_none());
+        assert_eq!(options.get_all(3).count(), 0);
+        Ok(())
+    }
+
     #[test]
     fn test_block_options_read_write() {
         let option_one = BlockOption::new(1, None, b"Test comment").unwrap();
@@ -474,14 +717,206 @@ mod tests {
         let options = BlockOptions(vec![option_one, option_two]);
 
         let mut buffer = Vec::new();
-        options.write(&mut buffer, LittleEndian).unwrap();
+        let written = options.write(&mut buffer, LittleEndian).unwrap();
         let expected_result = [
             1, 0, 12, 0, 84, 101, 115, 116, 32, 99, 111, 109, 109, 101, 110, 116, 2, 0, 11, 0, 67,
             117, 115, 116, 111, 109, 32, 100, 97, 116, 97, 0, 0, 0, 0, 0,
         ];
         assert_eq!(buffer, expected_result);
+        assert_eq!(written, buffer.len());
+        assert_eq!(options.byte_len(), buffer.len());
 
-        let read_options = BlockOptions::read(&mut buffer.as_slice(), LittleEndian).unwrap();
+        let (read_options, consumed) =
+            BlockOptions::read(&mut buffer.as_slice(), LittleEndian).unwrap();
         assert_eq!(options, read_options);
+        assert_eq!(consumed, buffer.len());
+    }
+
+    #[test]
+    fn test_block_option_pen_validation() {
+        assert_eq!(
+            BlockOption::new(2988, None, b"value"),
+            Err(InvalidOption::CustomRequiresPen)
+        );
+        assert_eq!(
+            BlockOption::new(1, Some(32473), b"value"),
+            Err(InvalidOption::UnexpectedPen(1))
+        );
+    }
+
+    #[test]
+    fn test_options_builder_round_trip() -> anyhow::Result<()> {
+        let options = OptionsBuilder::new()
+            .comment("Hello")
+            .custom_utf8(32473, "custom value")
+            .build()?;
+        assert_eq!(options.0.len(), 2);
+        assert_eq!(options.0[0].code, StandardOptions::Comment as u16);
+        assert_eq!(options.0[1].code, StandardOptions::CustomUTF8Copied as u16);
+        assert_eq!(options.0[1].pen, Some(32473));
+
+        let mut buffer = Vec::new();
+        options.write(&mut buffer, LittleEndian)?;
+        let (read_options, consumed) = BlockOptions::read(&mut buffer.as_slice(), LittleEndian)?;
+        assert_eq!(options, read_options);
+        assert_eq!(consumed, buffer.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_options_builder_invalid() {
+        let result = OptionsBuilder::new()
+            .comment("Hello")
+            .raw(
+                StandardOptions::CustomBinaryCopied as u16,
+                b"missing pen".to_vec(),
+            )
+            .build();
+        assert_eq!(result, Err(InvalidOption::CustomRequiresPen));
+    }
+
+    #[test]
+    fn test_read_in_without_terminator() {
+        // Every option header read from the stream is code 0x0101 with a length of 257
+        let mut options = BlockOptions::default();
+        let err = options
+            .read_in_with_limits(&mut std::io::repeat(1), LittleEndian, 1024, 16)
+            .expect_err("the budget must stop the parser");
+        assert!(matches!(
+            err,
+            OptionParseError::BudgetExceeded { max_bytes: 1024 }
+        ));
+        assert_eq!(options.0.len(), 3);
+
+        let mut options = BlockOptions::default();
+        let err = options
+            .read_in(&mut std::io::repeat(1), LittleEndian)
+            .expect_err("the option count must stop the parser");
+        assert!(matches!(
+            err,
+            OptionParseError::TooManyOptions {
+                max_options: BlockOptions::DEFAULT_MAX_OPTIONS
+            }
+        ));
+        assert_eq!(options.0.len(), BlockOptions::DEFAULT_MAX_OPTIONS);
+
+        // A terminated list within the budget is read normally
+        let mut buffer = Vec::new();
+        OptionsBuilder::new()
+            .comment("Hello")
+            .build()
+            .unwrap()
+            .write(&mut buffer, LittleEndian)
+            .unwrap();
+        let mut options = BlockOptions::default();
+        let consumed = options
+            .read_in_with_limits(&mut buffer.as_slice(), LittleEndian, buffer.len(), 1)
+            .unwrap();
+        assert_eq!(options.0.len(), 1);
+        assert_eq!(consumed, buffer.len());
+    }
+
+    #[test]
+    fn byte_len_matches_write() -> anyhow::Result<()> {
+        let options = OptionsBuilder::new()
+            .comment("odd")
+            .comment("four")
+            .custom_utf8(32473, "pen")
+            .custom_binary(32473, &[1, 2, 3, 4])
+            .raw(2, Vec::new())
+            .build()?;
+        let mut buffer = Vec::new();
+        options.write(&mut buffer, LittleEndian)?;
+        assert_eq!(options.byte_len(), buffer.len());
+        assert_eq!(
+            options
+                .0
+                .iter()
+                .map(BlockOption::byte_len)
+                .collect::<Vec<_>>(),
+            [8, 8, 12, 12, 4]
+        );
+        assert_eq!(BlockOptions::default().byte_len(), 4);
+        Ok(())
+    }
+
+    /// Writes `options` and checks the bytes against `expected` then reads them back
+    fn assert_golden(options: &BlockOptions, expected: &[u8]) -> anyhow::Result<()> {
+        let mut buffer = Vec::new();
+        options.write(&mut buffer, LittleEndian)?;
+        assert_eq!(buffer, expected);
+        assert!(buffer.len().is_multiple_of(4));
+        let (read_options, consumed) = BlockOptions::read(&mut buffer.as_slice(), LittleEndian)?;
+        assert_eq!(&read_options, options);
+        assert_eq!(consumed, buffer.len());
+        Ok(())
+    }
+
+    #[test]
+    fn padding_two_and_three_byte_values() -> anyhow::Result<()> {
+        let two = BlockOption::new(1, None, b"hi")?;
+        assert_eq!(two.padding_length(), 2);
+        assert_golden(
+            &BlockOptions(vec![two]),
+            &[
+                1, 0, 2, 0, b'h', b'i', 0, 0, // opt_comment, 2 bytes of padding
+                0, 0, 0, 0, // opt_endofopt
+            ],
+        )?;
+
+        let three = BlockOption::new(1, None, b"abc")?;
+        assert_eq!(three.padding_length(), 1);
+        assert_golden(
+            &BlockOptions(vec![three]),
+            &[
+                1, 0, 3, 0, b'a', b'b', b'c', 0, // opt_comment, 1 byte of padding
+                0, 0, 0, 0, // opt_endofopt
+            ],
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn padding_custom_option_with_pen() -> anyhow::Result<()> {
+        let custom = BlockOption::new(2988, Some(32473), b"ab")?;
+        // The PEN counts towards the length and the padding
+        assert_eq!(custom.length, 6);
+        assert_eq!(custom.padding_length(), 2);
+        assert_golden(
+            &BlockOptions(vec![custom]),
+            &[
+                0xAC, 0x0B, 6, 0, // custom string option, length 6
+                0xD9, 0x7E, 0, 0, // PEN 32473
+                b'a', b'b', 0, 0, // value, 2 bytes of padding
+                0, 0, 0, 0, // opt_endofopt
+            ],
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn padding_between_options() -> anyhow::Result<()> {
+        let options = BlockOptions(vec![
+            BlockOption::new(1, None, b"abc")?,
+            BlockOption::new(2, None, b"x")?,
+            BlockOption::new(3, None, b"hi")?,
+            BlockOption::new(2989, Some(32473), [1, 2, 3])?,
+            BlockOption::new(4, None, b"four")?,
+            BlockOption::new(5, None, Vec::new())?,
+        ]);
+        assert_golden(
+            &options,
+            &[
+                1, 0, 3, 0, b'a', b'b', b'c', 0, // 1 byte of padding
+                2, 0, 1, 0, b'x', 0, 0, 0, // 3 bytes of padding
+                3, 0, 2, 0, b'h', b'i', 0, 0, // 2 bytes of padding
+                0xAD, 0x0B, 7, 0, 0xD9, 0x7E, 0, 0, 1, 2, 3,
+                0, // PEN and value, 1 byte of padding
+                4, 0, 4, 0, b'f', b'o', b'u', b'r', // no padding
+                5, 0, 0, 0, // empty value
+                0, 0, 0, 0, // opt_endofopt
+            ],
+        )?;
+        Ok(())
     }
 }
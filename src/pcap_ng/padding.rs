@@ -0,0 +1,37 @@
+//! Padding of pcap-ng block bodies and options
+//!
+//! Block bodies and option values are padded with zeros to a multiple of 4 bytes (32 bits).
+//! The padding is not included in option lengths but is included in block lengths.
+
+/// Pads `length` to the next multiple of 4 bytes (32-bit alignment)
+pub fn pad_to_32(length: usize) -> usize {
+    length + padding_bytes(length)
+}
+/// Returns the number of padding bytes needed after `length` bytes to reach 32-bit alignment
+pub fn padding_bytes(length: usize) -> usize {
+    (4 - length % 4) % 4
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_pad_to_32() {
+        assert_eq!(pad_to_32(0), 0);
+        assert_eq!(pad_to_32(1), 4);
+        assert_eq!(pad_to_32(3), 4);
+        assert_eq!(pad_to_32(4), 4);
+        assert_eq!(pad_to_32(5), 8);
+        assert_eq!(pad_to_32(7), 8);
+        assert_eq!(pad_to_32(8), 8);
+        assert_eq!(pad_to_32(9), 12);
+    }
+    #[test]
+    fn test_padding_bytes() {
+        assert_eq!(padding_bytes(0), 0);
+        assert_eq!(padding_bytes(1), 3);
+        assert_eq!(padding_bytes(4), 0);
+        assert_eq!(padding_bytes(5), 3);
+        assert_eq!(padding_bytes(7), 1);
+    }
+}
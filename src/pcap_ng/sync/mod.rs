@@ -1,15 +1,23 @@
-//! Synchronous pcap-ng reader
-use std::io::Read;
+//! Synchronous pcap-ng reader and writer
+use std::{collections::HashMap, io::Read, net::IpAddr};
+pub mod writer;
 
 use crate::{
-    Version,
+    PcapFileType, Version,
     any_reader::AnyPacketHeader,
+    byte_order::Endianness,
     pcap_ng::{
         PcapNgParseError,
-        blocks::{BlockHeader, InterfaceDescriptionBlock, PcapNgBlock, SectionHeaderBlock},
+        blocks::{
+            Block, BlockHeader, DecryptionSecretsBlock, EnhancedPacket, InterfaceDescriptionBlock,
+            PcapNgBlock, SectionHeaderBlock, SimplePacket,
+        },
+        padding::pad_to_32,
     },
 };
 
+/// A packet header and its data along with the interface it was captured on
+pub type InterfacePacket<'a> = (AnyPacketHeader, &'a InterfaceDescriptionBlock, &'a [u8]);
 /// A synchronous reader for PCAP-NG files
 #[derive(Debug)]
 pub struct SyncPcapNgReader<R: Read> {
@@ -22,8 +30,55 @@ pub struct SyncPcapNgReader<R: Read> {
     interfaces: Vec<InterfaceDescriptionBlock>,
     /// Reusable scratch buffer for packet contents.
     buffer: Vec<u8>,
+    /// Byte offset of the next block header from the start of the stream
+    position: u64,
+    /// Byte offset of the first block after the current section header
+    section_start: u64,
+    /// Reject enhanced packets larger than their interface's snap length
+    strict_snaplen: bool,
+    /// Reject section headers with unknown major versions
+    strict_version: bool,
+    /// Byte order used for every section instead of the byte-order magic. See [`SyncPcapNgReader::new_with_forced_byte_order`]
+    forced_byte_order: Option<Endianness>,
+    /// Names collected from every name resolution block read
+    names: HashMap<IpAddr, Vec<String>>,
+    /// Decryption secrets blocks read and not yet taken with [`Self::take_secrets`]
+    secrets: Vec<DecryptionSecretsBlock>,
+    /// Block header read by [`Self::peek_block_header`] that has not been consumed yet
+    peeked_header: Option<BlockHeader>,
+    /// Reject blocks with options that must be UTF-8 but are not
+    validate_utf8_options: bool,
+    /// Length `buffer` is grown to before reading the next block, from the snap lengths of the interfaces read
+    presized_buffer: usize,
+}
+/// Clones the source and the reader state
+///
+/// The clone gets its own packet buffer so packets read from either reader never share data.
+impl<R: Read + Clone> Clone for SyncPcapNgReader<R> {
+    fn clone(&self) -> Self {
+        Self {
+            reader: self.reader.clone(),
+            current_section: self.current_section.clone(),
+            interfaces: self.interfaces.clone(),
+            buffer: vec![0u8; self.buffer.len()],
+            position: self.position,
+            section_start: self.section_start,
+            strict_snaplen: self.strict_snaplen,
+            strict_version: self.strict_version,
+            forced_byte_order: self.forced_byte_order,
+            names: self.names.clone(),
+            secrets: self.secrets.clone(),
+            peeked_header: self.peeked_header,
+            validate_utf8_options: self.validate_utf8_options,
+            presized_buffer: self.presized_buffer,
+        }
+    }
 }
 impl<R: Read> SyncPcapNgReader<R> {
+    /// The largest snap length the packet buffer is grown to when an interface is read (256 KiB)
+    ///
+    /// Matches `MAXIMUM_SNAPLEN` in libpcap. Larger packets still grow the buffer when they are read
+    const MAX_PRESIZED_BUFFER: usize = 262_144;
     /// Creates a new `SyncPcapReader` from a reader
     /// Returns `Ok(Self)` on success, or `Err` if there was an error
     /// reading the file header
@@ -35,19 +90,102 @@ impl<R: Read> SyncPcapNgReader<R> {
         let buffer = vec![0u8; 65536];
         Ok(Self {
             reader,
+            position: current_section.block_length as u64,
+            section_start: current_section.block_length as u64,
             current_section,
             interfaces: Vec::with_capacity(1),
             buffer,
+            strict_snaplen: false,
+            strict_version: false,
+            forced_byte_order: None,
+            names: HashMap::new(),
+            secrets: Vec::new(),
+            peeked_header: None,
+            validate_utf8_options: false,
+            presized_buffer: 0,
         })
     }
+    /// Creates a reader that reads every block in `byte_order`, ignoring the byte-order magic of the section headers
+    ///
+    /// A recovery escape hatch for files whose section header magic is damaged but whose byte order is known.
+    /// The magic is not validated, so a wrong `byte_order` surfaces as garbage values or unrelated parse errors
+    /// instead of [`PcapNgParseError::InvalidEndianness`]. Prefer [`Self::new`] for intact files.
+    pub fn new_with_forced_byte_order(
+        mut reader: R,
+        byte_order: Endianness,
+    ) -> Result<Self, PcapNgParseError> {
+        let header = BlockHeader::read(&mut reader)?;
+        let current_section =
+            SectionHeaderBlock::read_with_forced_byte_order(&mut reader, &header, byte_order)?;
+        let mut reader = Self::new_with_section(reader, current_section);
+        reader.forced_byte_order = Some(byte_order);
+        Ok(reader)
+    }
+    /// Creates a new reader like [`Self::new`] with room for `capacity` interfaces
+    ///
+    /// The allocation is reused when a new section clears the interfaces
+    pub fn with_interface_capacity(reader: R, capacity: usize) -> Result<Self, PcapNgParseError> {
+        let mut reader = Self::new(reader)?;
+        reader.interfaces.reserve(capacity);
+        Ok(reader)
+    }
     pub(crate) fn new_with_section(reader: R, current_section: SectionHeaderBlock) -> Self {
         Self {
             reader,
+            position: current_section.block_length as u64,
+            section_start: current_section.block_length as u64,
             current_section,
             interfaces: Vec::with_capacity(1),
             buffer: vec![0u8; 65536], // Default buffer size
+            strict_snaplen: false,
+            strict_version: false,
+            forced_byte_order: None,
+            names: HashMap::new(),
+            secrets: Vec::new(),
+            peeked_header: None,
+            validate_utf8_options: false,
+            presized_buffer: 0,
         }
     }
+    /// Enables or disables snap length validation
+    ///
+    /// When enabled, an enhanced packet whose `captured_length` is greater than the snap length of its interface
+    /// returns [`PcapNgParseError::SnapLengthExceeded`]. A snap length of 0 means unlimited.
+    ///
+    /// Disabled by default
+    pub fn strict_snaplen(mut self, strict_snaplen: bool) -> Self {
+        self.strict_snaplen = strict_snaplen;
+        self
+    }
+    /// Enables or disables section version validation
+    ///
+    /// When enabled, a section header whose major version is not supported (see [`Version::is_supported`])
+    /// returns [`PcapNgParseError::InvalidVersion`]. The current section is checked when enabling.
+    ///
+    /// Disabled by default, any version is read as if it were 1.0
+    pub fn strict_version(mut self, strict_version: bool) -> Result<Self, PcapNgParseError> {
+        self.strict_version = strict_version;
+        check_version(strict_version, &self.current_section)?;
+        Ok(self)
+    }
+    /// Enables or disables UTF-8 validation of string options
+    ///
+    /// When enabled, reading a block whose comment or other string option is not valid UTF-8 returns
+    /// [`OptionParseError::InvalidUtf8`](crate::pcap_ng::options::OptionParseError::InvalidUtf8).
+    /// See [`PcapNgBlock::validate_utf8_options`] for the options checked.
+    /// The first section header is read by [`Self::new`] before this is set and is not checked.
+    ///
+    /// Disabled by default
+    pub fn validate_utf8_options(mut self, validate_utf8_options: bool) -> Self {
+        self.validate_utf8_options = validate_utf8_options;
+        self
+    }
+    /// Returns the snap length of the interface with `interface_id` in the current section
+    pub fn snap_length(&self, interface_id: u32) -> Option<u32> {
+        self.interfaces
+            .get(interface_id as usize)
+            .map(|interface| interface.snap_length)
+    }
     /// Returns the file header of the pcap file
     pub fn current_section(&self) -> &SectionHeaderBlock {
         &self.current_section
@@ -64,11 +202,109 @@ impl<R: Read> SyncPcapNgReader<R> {
     pub fn interfaces(&self) -> &[InterfaceDescriptionBlock] {
         &self.interfaces
     }
+    /// Byte offset of the next block from the start of the stream
+    ///
+    /// Assumes the reader was positioned at the start of the stream when the reader was created
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+    /// Number of bytes left in the current section
+    ///
+    /// Computed from the section length declared by the section header and [`Self::position`].
+    /// Returns `None` if the section does not declare a length.
+    pub fn section_bytes_remaining(&self) -> Option<u64> {
+        self.current_section
+            .section_length
+            .map(|length| length.saturating_sub(self.position - self.section_start))
+    }
+    /// Returns the names for `addr` collected from the name resolution blocks read so far
+    ///
+    /// Names are kept across sections. A name listed by several blocks is only returned once
+    pub fn resolve(&self, addr: IpAddr) -> Option<&[String]> {
+        self.names.get(&addr).map(Vec::as_slice)
+    }
+    /// Returns the decryption secrets blocks read so far, in file order
+    ///
+    /// Secrets are kept across sections until they are taken with [`Self::take_secrets`].
+    /// Decrypting packets with them is left to the caller
+    pub fn secrets(&self) -> &[DecryptionSecretsBlock] {
+        &self.secrets
+    }
+    /// Removes and returns the decryption secrets blocks read since the last call
+    ///
+    /// Calling this after each block hands the secrets to a decryptor as they are encountered
+    pub fn take_secrets(&mut self) -> Vec<DecryptionSecretsBlock> {
+        std::mem::take(&mut self.secrets)
+    }
+    /// Returns a mutable reference to the underlying source
+    ///
+    /// Reading from the source directly leaves the reader out of sync with it.
+    /// After seeking the source to a section header call [`Self::reinit_section`].
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+    /// Consumes the reader and returns the underlying source
+    ///
+    /// The source is positioned after the last block read. A header read by [`Self::peek_block_header`]
+    /// has already been consumed from the source and is lost
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+    /// Reads a section header block from the current position of the source and starts a new section
+    ///
+    /// Intended for use after seeking the source to the start of a section with [`Self::get_mut`].
+    /// The interfaces of the previous section are cleared. [`Self::position`] does not know about the seek
+    /// and keeps counting from its previous value.
+    pub fn reinit_section(&mut self) -> Result<SectionHeaderBlock, PcapNgParseError> {
+        let section = match self.forced_byte_order {
+            Some(byte_order) => {
+                let header = BlockHeader::read(&mut self.reader)?;
+                SectionHeaderBlock::read_with_forced_byte_order(
+                    &mut self.reader,
+                    &header,
+                    byte_order,
+                )?
+            }
+            None => SectionHeaderBlock::read_from_reader(&mut self.reader, &mut self.buffer)?,
+        };
+        check_version(self.strict_version, &section)?;
+        self.peeked_header = None;
+        self.interfaces.clear();
+        self.current_section = section.clone();
+        self.position += section.block_length as u64;
+        self.section_start = self.position;
+        Ok(section)
+    }
     /// Reads the next pcap-ng block, transparently tracking section headers
     /// and interface description blocks as they pass.
     ///
     /// Returns `Ok(None)` at end of file.
     pub fn next_block(&mut self) -> Result<Option<PcapNgBlock<'_>>, PcapNgParseError> {
+        let Some(header) = self.read_block_header()? else {
+            return Ok(None);
+        };
+        self.read_block_body(&header).map(Some)
+    }
+    /// Returns the header of the next block without consuming it
+    ///
+    /// The header is read from the source and kept until the next call that reads a block, which uses it instead of
+    /// reading it again. [`Self::position`] still points at the start of the peeked block.
+    /// Byte order dependent fields must be decoded with the byte order of the [`Self::current_section`].
+    ///
+    /// Returns `Ok(None)` at end of file.
+    pub fn peek_block_header(&mut self) -> Result<Option<BlockHeader>, PcapNgParseError> {
+        if self.peeked_header.is_none() {
+            self.peeked_header = self.read_block_header()?;
+        }
+        Ok(self.peeked_header)
+    }
+    /// Reads the next block header, or takes the one read by [`Self::peek_block_header`]
+    ///
+    /// Returns `Ok(None)` at end of file.
+    fn read_block_header(&mut self) -> Result<Option<BlockHeader>, PcapNgParseError> {
+        if let Some(header) = self.peeked_header.take() {
+            return Ok(Some(header));
+        }
         let mut header_bytes = [0u8; 8];
         match self.reader.read_exact(&mut header_bytes) {
             Ok(_) => {}
@@ -77,25 +313,87 @@ impl<R: Read> SyncPcapNgReader<R> {
             }
             Err(err) => return Err(PcapNgParseError::IO(err)),
         }
-        let header = BlockHeader::parse_from_bytes(&header_bytes)?;
-
-        let result = PcapNgBlock::read(
-            &mut self.reader,
-            &header,
-            self.current_section.byte_order,
-            &mut self.buffer,
-        )?;
+        BlockHeader::parse_from_bytes(&header_bytes).map(Some)
+    }
+    /// Reads the block following `header`, tracking section headers and interfaces
+    fn read_block_body(
+        &mut self,
+        header: &BlockHeader,
+    ) -> Result<PcapNgBlock<'_>, PcapNgParseError> {
+        // Size the buffer for the largest packet the interfaces declare so it is not grown packet by packet.
+        // The block read last may borrow the buffer, so this happens before the next block is read
+        if self.buffer.len() < self.presized_buffer {
+            self.buffer.resize(self.presized_buffer, 0);
+        }
+        let result = match self.forced_byte_order {
+            Some(byte_order)
+                if header.block_id_as_u32(byte_order) == SectionHeaderBlock::block_id() =>
+            {
+                PcapNgBlock::SectionHeader(SectionHeaderBlock::read_with_forced_byte_order(
+                    &mut self.reader,
+                    header,
+                    byte_order,
+                )?)
+            }
+            _ => PcapNgBlock::read(
+                &mut self.reader,
+                header,
+                self.current_section.byte_order,
+                &mut self.buffer,
+            )?,
+        };
+        if self.validate_utf8_options {
+            result.validate_utf8_options()?;
+        }
         match &result {
             PcapNgBlock::InterfaceDescription(interface_block) => {
+                let snap_length =
+                    (interface_block.snap_length as usize).min(Self::MAX_PRESIZED_BUFFER);
+                self.presized_buffer = self.presized_buffer.max(pad_to_32(snap_length));
                 self.interfaces.push(interface_block.clone());
             }
             PcapNgBlock::SectionHeader(section_header) => {
+                check_version(self.strict_version, section_header)?;
                 self.interfaces.clear();
                 self.current_section = section_header.clone();
             }
+            PcapNgBlock::NameResolution(name_resolution) => {
+                for resolved in name_resolution.records.iter() {
+                    let names = self.names.entry(resolved.address).or_default();
+                    for name in resolved.names {
+                        if !names.contains(&name) {
+                            names.push(name);
+                        }
+                    }
+                }
+            }
+            PcapNgBlock::DecryptionSecrets(secrets) => {
+                self.secrets.push(secrets.clone());
+            }
+            PcapNgBlock::EnhancedPacket(packet) if self.strict_snaplen => {
+                check_snap_length(
+                    &self.interfaces,
+                    packet.interface_id,
+                    packet.captured_length,
+                )?;
+            }
             _ => {}
         }
-        Ok(Some(result))
+        // The section header may have changed the byte order so the length is read after
+        self.position += header.block_length_as_u32(self.current_section.byte_order) as u64;
+        if matches!(result, PcapNgBlock::SectionHeader(_)) {
+            self.section_start = self.position;
+        }
+        Ok(result)
+    }
+    /// Reads the next block along with the byte offset of its block header
+    ///
+    /// See [`Self::position`]
+    pub fn next_block_with_offset(
+        &mut self,
+    ) -> Result<Option<(u64, PcapNgBlock<'_>)>, PcapNgParseError> {
+        let offset = self.position;
+        Ok(self.next_block()?.map(|block| (offset, block)))
     }
     /// Reads the next packet from the pcapng file
     ///
@@ -107,23 +405,12 @@ impl<R: Read> SyncPcapNgReader<R> {
             match block {
                 PcapNgBlock::EnhancedPacket(enhanced_packet) => {
                     let packet_length = enhanced_packet.content.len();
-                    let header = AnyPacketHeader::PcapNgEnhanced {
-                        block_length: enhanced_packet.block_length,
-                        original_length: enhanced_packet.original_length,
-                        interface_id: enhanced_packet.interface_id,
-                        timestamp_high: enhanced_packet.timestamp_high,
-                        timestamp_low: enhanced_packet.timestamp_low,
-                        captured_length: enhanced_packet.captured_length,
-                        options: enhanced_packet.options,
-                    };
+                    let header = AnyPacketHeader::from(enhanced_packet);
                     return Ok(Some((header, &self.buffer[..packet_length])));
                 }
                 PcapNgBlock::SimplePacket(simple_packet) => {
                     let packet_length = simple_packet.content.len();
-                    let header = AnyPacketHeader::PcapNgSimple {
-                        block_length: simple_packet.block_length,
-                        original_length: simple_packet.original_length,
-                    };
+                    let header = AnyPacketHeader::from(simple_packet);
                     return Ok(Some((header, &self.buffer[..packet_length])));
                 }
                 _ => {
@@ -134,14 +421,419 @@ impl<R: Read> SyncPcapNgReader<R> {
         }
         Ok(None)
     }
+    /// Reads the next packet header and skips the packet data without copying it into the packet buffer
+    ///
+    /// Returns the header along with the length of the skipped packet data.
+    /// Other block types are read as normal so sections and interfaces are still tracked.
+    ///
+    /// When Ok(None) is returned, it indicates the end of the file has been reached
+    pub fn skip_packet(&mut self) -> Result<Option<(AnyPacketHeader, usize)>, PcapNgParseError> {
+        while let Some(header) = self.read_block_header()? {
+            let byte_order = self.current_section.byte_order;
+            let packet = match header.block_id_as_u32(byte_order) {
+                6 => {
+                    header.check_minimum_size::<EnhancedPacket>(byte_order)?;
+                    let packet = EnhancedPacket::read_skipping_content(
+                        &mut self.reader,
+                        &header,
+                        byte_order,
+                    )?;
+                    if self.strict_snaplen {
+                        check_snap_length(
+                            &self.interfaces,
+                            packet.interface_id,
+                            packet.captured_length,
+                        )?;
+                    }
+                    if self.validate_utf8_options
+                        && let Some(options) = &packet.options
+                    {
+                        options.validate_utf8(&[])?;
+                    }
+                    let captured_length = packet.captured_length as usize;
+                    (AnyPacketHeader::from(packet), captured_length)
+                }
+                3 => {
+                    header.check_minimum_size::<SimplePacket>(byte_order)?;
+                    let (packet, captured_length) =
+                        SimplePacket::read_skipping_content(&mut self.reader, &header, byte_order)?;
+                    (AnyPacketHeader::from(packet), captured_length)
+                }
+                _ => {
+                    self.read_block_body(&header)?;
+                    continue;
+                }
+            };
+            self.position += header.block_length_as_u32(byte_order) as u64;
+            return Ok(Some(packet));
+        }
+        Ok(None)
+    }
+    /// Reads the next packet along with the interface it was captured on
+    ///
+    /// Enhanced packets are resolved via their `interface_id`. Simple packets
+    /// always belong to the first interface of the section.
+    ///
+    /// Returns [`PcapNgParseError::UndeclaredInterface`] if the packet references
+    /// an interface that has not been declared in the current section.
+    pub fn next_packet_with_interface(
+        &mut self,
+    ) -> Result<Option<InterfacePacket<'_>>, PcapNgParseError> {
+        let Some((header, data)) = self.next_packet()? else {
+            return Ok(None);
+        };
+        let data_length = data.len();
+        let interface_id = header.interface_id();
+        let interface = self.interfaces.get(interface_id as usize).ok_or(
+            PcapNgParseError::UndeclaredInterface {
+                interface_id,
+                declared: self.interfaces.len(),
+            },
+        )?;
+        Ok(Some((header, interface, &self.buffer[..data_length])))
+    }
+    /// Reads every remaining block into a vector
+    ///
+    /// The section header read by [`Self::new`] is not included. Intended for small files as every packet is copied.
+    pub fn read_all(self) -> Result<Vec<PcapNgBlock<'static>>, PcapNgParseError> {
+        self.read_all_with_capacity(0)
+    }
+    /// Like [`Self::read_all`] but reserves space for `capacity` blocks up front
+    pub fn read_all_with_capacity(
+        mut self,
+        capacity: usize,
+    ) -> Result<Vec<PcapNgBlock<'static>>, PcapNgParseError> {
+        let mut blocks = Vec::with_capacity(capacity);
+        while let Some(block) = self.next_block()? {
+            blocks.push(block.into_owned());
+        }
+        Ok(blocks)
+    }
+    /// Reads every remaining packet into a vector
+    ///
+    /// Intended for small files as every packet is copied.
+    pub fn read_all_packets(mut self) -> Result<Vec<(AnyPacketHeader, Vec<u8>)>, PcapNgParseError> {
+        let mut packets = Vec::new();
+        while let Some((header, data)) = self.next_packet()? {
+            packets.push((header, data.to_vec()));
+        }
+        Ok(packets)
+    }
+}
+/// Returns [`PcapNgParseError::SnapLengthExceeded`] if the packet is larger than its interface's snap length
+fn check_snap_length(
+    interfaces: &[InterfaceDescriptionBlock],
+    interface_id: u32,
+    captured_length: u32,
+) -> Result<(), PcapNgParseError> {
+    if let Some(interface) = interfaces.get(interface_id as usize)
+        && interface.snap_length != 0
+        && captured_length > interface.snap_length
+    {
+        return Err(PcapNgParseError::SnapLengthExceeded {
+            interface_id,
+            snap_length: interface.snap_length,
+            captured_length,
+        });
+    }
+    Ok(())
+}
+/// Returns [`PcapNgParseError::InvalidVersion`] if `strict_version` is set and the section version is not supported
+fn check_version(
+    strict_version: bool,
+    section: &SectionHeaderBlock,
+) -> Result<(), PcapNgParseError> {
+    if strict_version && !section.version.is_supported(PcapFileType::PcapNg) {
+        return Err(PcapNgParseError::InvalidVersion(section.version));
+    }
+    Ok(())
 }
 #[cfg(test)]
 mod tests {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
     use etherparse::{NetSlice, SlicedPacket};
 
-    use crate::byte_order::Endianness;
+    use crate::{
+        byte_order::{ByteOrder, Endianness},
+        link_type::LinkType,
+        pcap_ng::{
+            PCAP_NG_MAGIC,
+            blocks::{Block, EnhancedPacket, NameResolutionBlock, Record, Records},
+            options::{OptionParseError, OptionsBuilder},
+        },
+    };
 
     use super::*;
+    use crate::pcap_ng::writer::SyncPcapNgWriter;
+    /// Section header (no options) followed by one IDB per link type
+    fn section_with_interfaces(link_types: &[LinkType]) -> Vec<u8> {
+        let mut file = vec![
+            0x0A, 0x0D, 0x0D, 0x0A, 28, 0, 0, 0, 0x4D, 0x3C, 0x2B, 0x1A, 1, 0, 0, 0, 0xFF, 0xFF,
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 28, 0, 0, 0,
+        ];
+        for link_type in link_types {
+            let [a, b] = (*link_type as u16).to_le_bytes();
+            file.extend_from_slice(&[1, 0, 0, 0, 20, 0, 0, 0, a, b, 0, 0, 0, 0, 0, 0, 20, 0, 0, 0]);
+        }
+        file
+    }
+    fn write_packet(file: &mut Vec<u8>, interface_id: u32, content: &[u8]) -> anyhow::Result<()> {
+        EnhancedPacket {
+            block_length: 0,
+            interface_id,
+            timestamp_high: 0,
+            timestamp_low: 0,
+            captured_length: content.len() as u32,
+            original_length: content.len() as u32,
+            content: content.into(),
+            options: None,
+        }
+        .write(file, Endianness::LittleEndian)?;
+        Ok(())
+    }
+    #[test]
+    fn next_block_with_offset() -> anyhow::Result<()> {
+        for file_name in [
+            "test_data/ng/test001_le.pcapng",
+            "test_data/ng/test001_be.pcapng",
+        ] {
+            let file = std::fs::read(file_name)?;
+            // The first section header is read by `new` and is always at offset 0
+            assert_eq!(file[..4], PCAP_NG_MAGIC);
+            let mut reader = SyncPcapNgReader::new(file.as_slice())?;
+            assert_eq!(
+                reader.position(),
+                reader.current_section().block_length as u64
+            );
+            let mut previous = 0;
+            while let Some((offset, _)) = reader.next_block_with_offset()? {
+                assert!(offset > previous, "offsets must increase");
+                // The leading and trailing block lengths must match if the offset is the start of a block
+                let start = offset as usize;
+                let length = reader
+                    .current_section()
+                    .byte_order
+                    .u32_from_bytes(file[start + 4..start + 8].try_into()?)
+                    as usize;
+                assert_eq!(
+                    file[start + 4..start + 8],
+                    file[start + length - 4..start + length]
+                );
+                previous = offset;
+            }
+            assert_eq!(reader.position(), file.len() as u64);
+        }
+        Ok(())
+    }
+    #[test]
+    fn strict_snaplen() -> anyhow::Result<()> {
+        let mut writer = writer::SyncPcapNgWriter::new(
+            Vec::new(),
+            SectionHeaderBlock::new(Endianness::LittleEndian),
+        )?;
+        writer.add_interface(InterfaceDescriptionBlock::new(LinkType::Ethernet, 4))?;
+        writer.write_packet(0, 0, &[1, 2, 3, 4], None)?;
+        writer.write_packet(0, 0, &[1, 2, 3, 4, 5, 6, 7, 8], None)?;
+        let file = writer.into_inner();
+
+        // Lenient by default
+        let mut reader = SyncPcapNgReader::new(file.as_slice())?;
+        assert!(reader.next_packet()?.is_some());
+        assert_eq!(reader.snap_length(0), Some(4));
+        assert_eq!(reader.snap_length(1), None);
+        assert!(reader.next_packet()?.is_some());
+
+        let mut reader = SyncPcapNgReader::new(file.as_slice())?.strict_snaplen(true);
+        assert!(reader.next_packet()?.is_some());
+        let err = reader
+            .next_packet()
+            .expect_err("packet is larger than the snap length");
+        assert!(matches!(
+            err,
+            PcapNgParseError::SnapLengthExceeded {
+                interface_id: 0,
+                snap_length: 4,
+                captured_length: 8
+            }
+        ));
+        Ok(())
+    }
+    #[test]
+    fn read_all() -> anyhow::Result<()> {
+        let file = std::fs::read("test_data/ng/test001_le.pcapng")?;
+        let blocks = SyncPcapNgReader::new(file.as_slice())?.read_all()?;
+        assert!(!blocks.is_empty());
+        assert!(
+            blocks
+                .iter()
+                .any(|block| matches!(block, PcapNgBlock::InterfaceDescription(_)))
+        );
+
+        let packets = SyncPcapNgReader::new(file.as_slice())?.read_all_packets()?;
+        let packet_blocks: Vec<_> = blocks.iter().filter(|block| block.is_packet()).collect();
+        assert_eq!(packets.len(), packet_blocks.len());
+        for ((_, data), block) in packets.iter().zip(packet_blocks) {
+            let PcapNgBlock::EnhancedPacket(packet) = block else {
+                panic!("test001 only has enhanced packets");
+            };
+            assert_eq!(*packet.content, data[..]);
+        }
+        Ok(())
+    }
+    #[test]
+    fn skip_packet_matches_next_packet() -> anyhow::Result<()> {
+        for file_name in [
+            "test_data/ng/test001_le.pcapng",
+            "test_data/ng/test001_be.pcapng",
+        ] {
+            let file = std::fs::read(file_name)?;
+            let mut reader = SyncPcapNgReader::new(file.as_slice())?;
+            let mut skipping = SyncPcapNgReader::new(file.as_slice())?;
+            let mut packets = 0;
+            while let Some((header, data)) = reader.next_packet()? {
+                let (skipped, length) = skipping.skip_packet()?.expect("same number of packets");
+                assert_eq!(skipped, header);
+                assert_eq!(length, data.len());
+                assert_eq!(skipping.position(), reader.position());
+                packets += 1;
+            }
+            assert!(packets > 0);
+            assert!(skipping.skip_packet()?.is_none());
+            assert_eq!(skipping.interfaces(), reader.interfaces());
+        }
+        Ok(())
+    }
+    #[test]
+    fn clone_mid_stream() -> anyhow::Result<()> {
+        let file = std::fs::read("test_data/ng/test001_le.pcapng")?;
+        let mut reader = SyncPcapNgReader::new(std::io::Cursor::new(file.as_slice()))?;
+        reader.next_packet()?;
+        let mut cloned = reader.clone();
+        assert_eq!(cloned.interfaces(), reader.interfaces());
+        while let Some((header, data)) = reader.next_packet()? {
+            let (cloned_header, cloned_data) = cloned.next_packet()?.expect("same packets");
+            assert_eq!(cloned_header, header);
+            assert_eq!(cloned_data, data);
+        }
+        assert!(cloned.next_packet()?.is_none());
+        assert_eq!(cloned.position(), reader.position());
+        Ok(())
+    }
+    #[test]
+    fn section_bytes_remaining() -> anyhow::Result<()> {
+        let mut section = Vec::new();
+        InterfaceDescriptionBlock::new(LinkType::Ethernet, 0)
+            .write(&mut section, Endianness::LittleEndian)?;
+        let interface_length = section.len() as u64;
+        write_packet(&mut section, 0, &[1, 2, 3, 4])?;
+        let packet_length = section.len() as u64 - interface_length;
+
+        let mut header = SectionHeaderBlock::new(Endianness::LittleEndian);
+        header.section_length = Some(section.len() as u64);
+        let mut file = Vec::new();
+        header.write(&mut file, Endianness::LittleEndian)?;
+        file.extend_from_slice(&section);
+        // A second section without a declared length
+        SectionHeaderBlock::new(Endianness::LittleEndian)
+            .write(&mut file, Endianness::LittleEndian)?;
+
+        let mut reader = SyncPcapNgReader::new(file.as_slice())?;
+        assert_eq!(
+            reader.section_bytes_remaining(),
+            Some(interface_length + packet_length)
+        );
+        reader.next_block()?;
+        assert_eq!(reader.section_bytes_remaining(), Some(packet_length));
+        reader.next_block()?;
+        assert_eq!(reader.section_bytes_remaining(), Some(0));
+        reader.next_block()?;
+        assert_eq!(reader.section_bytes_remaining(), None);
+        Ok(())
+    }
+    #[test]
+    fn skip_simple_packet() -> anyhow::Result<()> {
+        let mut file = section_with_interfaces(&[LinkType::Ethernet]);
+        SimplePacket {
+            block_length: 0,
+            original_length: 6,
+            content: (&[1, 2, 3, 4, 5, 6][..]).into(),
+        }
+        .write(&mut file, Endianness::LittleEndian)?;
+        let mut reader = SyncPcapNgReader::new(file.as_slice())?;
+        let (header, length) = reader.skip_packet()?.expect("simple packet");
+        assert_eq!(length, 6);
+        assert_eq!(header.original_length(), 6);
+        assert!(reader.skip_packet()?.is_none());
+        Ok(())
+    }
+    #[test]
+    fn skip_packet_below_minimum_size() -> anyhow::Result<()> {
+        let mut file = section_with_interfaces(&[LinkType::Ethernet]);
+        // Enhanced packet claiming 20 bytes, below its 32 byte minimum
+        file.extend_from_slice(&[6, 0, 0, 0, 20, 0, 0, 0]);
+        file.extend_from_slice(&[0; 12]);
+
+        let mut reader = SyncPcapNgReader::new(file.as_slice())?;
+        let err = reader.next_packet().expect_err("short enhanced packet");
+        assert!(
+            matches!(err, PcapNgParseError::MinimumSizeNotMet(32, 20)),
+            "unexpected error {err:?}"
+        );
+        let mut reader = SyncPcapNgReader::new(file.as_slice())?;
+        let err = reader.skip_packet().expect_err("short enhanced packet");
+        assert!(
+            matches!(err, PcapNgParseError::MinimumSizeNotMet(32, 20)),
+            "unexpected error {err:?}"
+        );
+        Ok(())
+    }
+    #[test]
+    fn read_all_with_capacity() -> anyhow::Result<()> {
+        let file = std::fs::read("test_data/ng/test001_le.pcapng")?;
+        let blocks = SyncPcapNgReader::new(file.as_slice())?.read_all()?;
+        let with_capacity = SyncPcapNgReader::new(file.as_slice())?.read_all_with_capacity(32)?;
+        assert!(with_capacity.capacity() >= 32);
+        assert_eq!(with_capacity, blocks);
+        Ok(())
+    }
+    #[test]
+    fn next_packet_with_interface_routes_packets() -> anyhow::Result<()> {
+        let mut file = section_with_interfaces(&[LinkType::Ethernet, LinkType::Raw]);
+        write_packet(&mut file, 1, &[1, 2, 3])?;
+        write_packet(&mut file, 0, &[4, 5, 6, 7])?;
+
+        let mut reader = SyncPcapNgReader::new(file.as_slice())?;
+        let (_, interface, data) = reader.next_packet_with_interface()?.expect("first packet");
+        assert_eq!(interface.link_type, LinkType::Raw);
+        assert_eq!(data, &[1, 2, 3]);
+        let (_, interface, data) = reader.next_packet_with_interface()?.expect("second packet");
+        assert_eq!(interface.link_type, LinkType::Ethernet);
+        assert_eq!(data, &[4, 5, 6, 7]);
+        assert!(reader.next_packet_with_interface()?.is_none());
+        Ok(())
+    }
+    #[test]
+    fn next_packet_with_interface_undeclared() -> anyhow::Result<()> {
+        let mut file = section_with_interfaces(&[LinkType::Ethernet]);
+        write_packet(&mut file, 3, &[1, 2, 3])?;
+
+        let mut reader = SyncPcapNgReader::new(file.as_slice())?;
+        let err = reader
+            .next_packet_with_interface()
+            .expect_err("interface 3 is not declared");
+        assert!(
+            matches!(
+                err,
+                PcapNgParseError::UndeclaredInterface {
+                    interface_id: 3,
+                    declared: 1
+                }
+            ),
+            "unexpected error {err:?}"
+        );
+        Ok(())
+    }
     #[test]
     fn read_packets_from_file() -> anyhow::Result<()> {
         let file = std::fs::File::open("test_data/ng/test001_le.pcapng")?;
@@ -172,7 +864,7 @@ mod tests {
             };
             println!("---- Packet: (Block Length {}) ----", packet.block_length);
             let parse =
-                SlicedPacket::from_ethernet(packet.content).expect("Failed to parse packet");
+                SlicedPacket::from_ethernet(&packet.content).expect("Failed to parse packet");
             let Some(net_slice) = parse.net else {
                 panic!("Expected a network layer slice, got: {:?}", parse);
             };
@@ -194,4 +886,322 @@ mod tests {
         }
         Ok(())
     }
+    #[test]
+    fn reinit_section() -> anyhow::Result<()> {
+        let mut first = writer::SyncPcapNgWriter::new(
+            Vec::new(),
+            SectionHeaderBlock::new(Endianness::LittleEndian),
+        )?;
+        first.add_interface(InterfaceDescriptionBlock::new(LinkType::Ethernet, 0))?;
+        first.add_interface(InterfaceDescriptionBlock::new(LinkType::Raw, 0))?;
+        first.write_packet(1, 0, &[1, 2, 3], None)?;
+        let mut file = first.into_inner();
+        let second_section = file.len() as u64;
+
+        let mut second = writer::SyncPcapNgWriter::new(
+            Vec::new(),
+            SectionHeaderBlock::new(Endianness::BigEndian),
+        )?;
+        second.add_interface(InterfaceDescriptionBlock::new(LinkType::LinuxSll, 0))?;
+        second.write_packet(0, 0, &[4, 5], None)?;
+        file.extend_from_slice(&second.into_inner());
+
+        let mut reader = SyncPcapNgReader::new(std::io::Cursor::new(file))?;
+        while reader.next_packet()?.is_some() {}
+        assert_eq!(reader.interfaces().len(), 1);
+
+        // Go back to the first section
+        reader.get_mut().set_position(0);
+        let section = reader.reinit_section()?;
+        assert_eq!(section.byte_order, Endianness::LittleEndian);
+        assert!(reader.interfaces().is_empty());
+        let (_, interface, data) = reader
+            .next_packet_with_interface()?
+            .expect("packet in the first section");
+        assert_eq!(interface.link_type, LinkType::Raw);
+        assert_eq!(data, &[1, 2, 3]);
+        assert_eq!(reader.interfaces().len(), 2);
+
+        // Jump straight to the second section
+        reader.get_mut().set_position(second_section);
+        let section = reader.reinit_section()?;
+        assert_eq!(section.byte_order, Endianness::BigEndian);
+        assert_eq!(reader.current_section().byte_order, Endianness::BigEndian);
+        assert!(reader.interfaces().is_empty());
+        let (_, interface, data) = reader
+            .next_packet_with_interface()?
+            .expect("packet in the second section");
+        assert_eq!(interface.link_type, LinkType::LinuxSll);
+        assert_eq!(data, &[4, 5]);
+        assert_eq!(reader.interfaces().len(), 1);
+        Ok(())
+    }
+    #[test]
+    fn collect_secrets() -> anyhow::Result<()> {
+        let mut file = section_with_interfaces(&[LinkType::Ethernet]);
+        let secrets = DecryptionSecretsBlock {
+            block_length: 0,
+            secrets_type: 0x544c_534b,
+            secrets_length: 0,
+            secrets_data: b"CLIENT_RANDOM 00 11\n".to_vec(),
+            options: None,
+        };
+        write_packet(&mut file, 0, &[1, 2, 3])?;
+        secrets.write(&mut file, Endianness::LittleEndian)?;
+        write_packet(&mut file, 0, &[4, 5, 6])?;
+
+        let mut reader = SyncPcapNgReader::new(file.as_slice())?;
+        assert!(reader.next_packet()?.is_some());
+        assert!(reader.secrets().is_empty());
+        assert!(reader.next_packet()?.is_some());
+        let taken = reader.take_secrets();
+        assert_eq!(taken.len(), 1);
+        assert_eq!(taken[0].secrets_type, 0x544c_534b);
+        assert_eq!(taken[0].secrets_data, secrets.secrets_data);
+        assert!(reader.secrets().is_empty());
+        assert!(reader.next_packet()?.is_none());
+        assert!(reader.take_secrets().is_empty());
+        Ok(())
+    }
+    #[test]
+    fn resolve_names() -> anyhow::Result<()> {
+        let mut file = section_with_interfaces(&[LinkType::Ethernet]);
+        NameResolutionBlock {
+            block_length: 0,
+            records: Records(vec![
+                Record {
+                    record_type: Record::IPV4,
+                    record_length: 18,
+                    record_data: b"\x0a\0\0\x01gateway\0router\0".to_vec(),
+                },
+                Record {
+                    record_type: Record::IPV6,
+                    record_length: 26,
+                    record_data: [&Ipv6Addr::LOCALHOST.octets()[..], b"localhost\0"].concat(),
+                },
+            ]),
+            options: None,
+        }
+        .write(&mut file, Endianness::LittleEndian)?;
+        write_packet(&mut file, 0, &[1, 2, 3])?;
+        NameResolutionBlock {
+            block_length: 0,
+            records: Records(vec![Record {
+                record_type: Record::IPV4,
+                record_length: 16,
+                record_data: b"\x0a\0\0\x01router\0dns\0".to_vec(),
+            }]),
+            options: None,
+        }
+        .write(&mut file, Endianness::LittleEndian)?;
+
+        let mut reader = SyncPcapNgReader::new(file.as_slice())?;
+        let gateway = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        assert_eq!(reader.resolve(gateway), None);
+        reader.next_packet()?.expect("one packet");
+        assert_eq!(
+            reader.resolve(gateway),
+            Some(&["gateway".to_owned(), "router".to_owned()][..])
+        );
+        assert_eq!(
+            reader.resolve(IpAddr::V6(Ipv6Addr::LOCALHOST)),
+            Some(&["localhost".to_owned()][..])
+        );
+        assert!(reader.next_packet()?.is_none());
+        assert_eq!(
+            reader.resolve(gateway),
+            Some(&["gateway".to_owned(), "router".to_owned(), "dns".to_owned()][..])
+        );
+        assert_eq!(reader.resolve(IpAddr::V4(Ipv4Addr::LOCALHOST)), None);
+        Ok(())
+    }
+    #[test]
+    fn peek_block_header() -> anyhow::Result<()> {
+        let mut file = section_with_interfaces(&[LinkType::Ethernet]);
+        write_packet(&mut file, 0, &[1, 2, 3])?;
+        write_packet(&mut file, 0, &[4, 5, 6, 7])?;
+
+        let mut reader = SyncPcapNgReader::new(file.as_slice())?;
+        let byte_order = reader.current_section().byte_order;
+        let header = reader.peek_block_header()?.expect("interface block");
+        assert_eq!(header.block_id_as_u32(byte_order), 1);
+        // Peeking again returns the same header without reading
+        assert_eq!(reader.peek_block_header()?, Some(header));
+        let position = reader.position();
+        let block = reader.next_block()?.expect("interface block");
+        assert!(matches!(block, PcapNgBlock::InterfaceDescription(_)));
+        assert_eq!(
+            reader.position(),
+            position + header.block_length_as_u32(byte_order) as u64
+        );
+
+        let header = reader.peek_block_header()?.expect("first packet");
+        assert_eq!(header.block_id_as_u32(byte_order), 6);
+        let (_, data) = reader.next_packet()?.expect("first packet");
+        assert_eq!(data, &[1, 2, 3]);
+        // skip_packet also uses the peeked header
+        reader.peek_block_header()?;
+        let (_, length) = reader.skip_packet()?.expect("second packet");
+        assert_eq!(length, 4);
+        assert_eq!(reader.peek_block_header()?, None);
+        assert!(reader.next_block()?.is_none());
+        Ok(())
+    }
+    #[test]
+    fn validate_utf8_options() -> anyhow::Result<()> {
+        let invalid_comment = || OptionsBuilder::new().raw(1, vec![b'o', b'k', 0xFF]).build();
+        let mut file = section_with_interfaces(&[LinkType::Ethernet]);
+        EnhancedPacket {
+            block_length: 0,
+            interface_id: 0,
+            timestamp_high: 0,
+            timestamp_low: 0,
+            captured_length: 3,
+            original_length: 3,
+            content: (&[1, 2, 3][..]).into(),
+            options: Some(invalid_comment()?),
+        }
+        .write(&mut file, Endianness::LittleEndian)?;
+
+        // Lenient by default
+        let mut reader = SyncPcapNgReader::new(file.as_slice())?;
+        assert!(reader.next_packet()?.is_some());
+
+        let is_invalid_comment = |err: &PcapNgParseError| {
+            matches!(
+                err,
+                PcapNgParseError::OptionParseError(OptionParseError::InvalidUtf8 { code: 1 })
+            )
+        };
+        let mut reader = SyncPcapNgReader::new(file.as_slice())?.validate_utf8_options(true);
+        let err = reader.next_packet().expect_err("invalid comment");
+        assert!(is_invalid_comment(&err), "unexpected error {err:?}");
+        let mut reader = SyncPcapNgReader::new(file.as_slice())?.validate_utf8_options(true);
+        let err = reader.skip_packet().expect_err("invalid comment");
+        assert!(is_invalid_comment(&err), "unexpected error {err:?}");
+
+        // Interface names must be UTF-8 too
+        let mut file = section_with_interfaces(&[]);
+        let mut interface = InterfaceDescriptionBlock::new(LinkType::Ethernet, 0);
+        interface.options = Some(OptionsBuilder::new().raw(2, vec![0xC0]).build()?);
+        interface.write(&mut file, Endianness::LittleEndian)?;
+        let mut reader = SyncPcapNgReader::new(file.as_slice())?.validate_utf8_options(true);
+        let err = reader.next_block().expect_err("invalid if_name");
+        assert!(
+            matches!(
+                err,
+                PcapNgParseError::OptionParseError(OptionParseError::InvalidUtf8 { code: 2 })
+            ),
+            "unexpected error {err:?}"
+        );
+        Ok(())
+    }
+    #[test]
+    fn with_interface_capacity() -> anyhow::Result<()> {
+        let mut file = section_with_interfaces(&[LinkType::Ethernet; 10]);
+        write_packet(&mut file, 9, &[1, 2, 3])?;
+        let mut reader = SyncPcapNgReader::with_interface_capacity(file.as_slice(), 10)?;
+        assert!(reader.interfaces.capacity() >= 10);
+        let allocation = reader.interfaces.as_ptr();
+        while reader.next_block()?.is_some() {}
+        assert_eq!(reader.interfaces().len(), 10);
+        assert_eq!(reader.interfaces.as_ptr(), allocation);
+        Ok(())
+    }
+    #[test]
+    fn buffer_sized_to_snap_length() -> anyhow::Result<()> {
+        let mut writer = SyncPcapNgWriter::new(
+            Vec::new(),
+            SectionHeaderBlock::new(Endianness::LittleEndian),
+        )?;
+        writer.add_interface(InterfaceDescriptionBlock::new(LinkType::Ethernet, 1500))?;
+        writer.add_interface(InterfaceDescriptionBlock::new(LinkType::Ethernet, 200_000))?;
+        writer.add_interface(InterfaceDescriptionBlock::new(LinkType::Ethernet, 0))?;
+        writer.write_packet(0, 0, &[1, 2, 3], None)?;
+        let file = writer.into_inner();
+
+        let mut reader = SyncPcapNgReader::new(file.as_slice())?;
+        assert_eq!(reader.buffer.len(), 65536);
+        assert_eq!(reader.next_packet()?.map(|(_, data)| data.len()), Some(3));
+        assert_eq!(reader.interfaces().len(), 3);
+        assert!(reader.buffer.len() >= 200_000);
+
+        // Huge snap lengths do not allocate more than the cap
+        let mut writer = SyncPcapNgWriter::new(
+            Vec::new(),
+            SectionHeaderBlock::new(Endianness::LittleEndian),
+        )?;
+        writer.add_interface(InterfaceDescriptionBlock::new(LinkType::Ethernet, u32::MAX))?;
+        writer.write_packet(0, 0, &[1, 2, 3], None)?;
+        let file = writer.into_inner();
+        let mut reader = SyncPcapNgReader::new(file.as_slice())?;
+        assert!(reader.next_packet()?.is_some());
+        assert_eq!(
+            reader.buffer.len(),
+            SyncPcapNgReader::<&[u8]>::MAX_PRESIZED_BUFFER
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn strict_version() -> anyhow::Result<()> {
+        let mut file = section_with_interfaces(&[LinkType::Ethernet]);
+        let mut reader = SyncPcapNgReader::new(file.as_slice())?.strict_version(true)?;
+        assert_eq!(
+            reader.current_section().version,
+            Version::PCAP_NG_VERSION_1_0
+        );
+        assert!(reader.next_block()?.is_some());
+
+        // A second section with version 2.0
+        let mut section = SectionHeaderBlock::new(Endianness::LittleEndian);
+        section.version = Version { major: 2, minor: 0 };
+        section.write(&mut file, Endianness::LittleEndian)?;
+        let mut reader = SyncPcapNgReader::new(file.as_slice())?;
+        while reader.next_block()?.is_some() {}
+
+        let mut reader = SyncPcapNgReader::new(file.as_slice())?.strict_version(true)?;
+        reader.next_block()?;
+        let err = reader.next_block().expect_err("section version 2.0");
+        assert!(
+            matches!(
+                err,
+                PcapNgParseError::InvalidVersion(Version { major: 2, minor: 0 })
+            ),
+            "unexpected error {err:?}"
+        );
+
+        let err = SyncPcapNgReader::new(&file[file.len() - 28..])?
+            .strict_version(true)
+            .expect_err("first section version 2.0");
+        assert!(matches!(err, PcapNgParseError::InvalidVersion(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn forced_byte_order() -> anyhow::Result<()> {
+        let file = std::fs::read("test_data/test.pcapng")?;
+        let expected = SyncPcapNgReader::new(file.as_slice())?.read_all_packets()?;
+
+        let reader = SyncPcapNgReader::new_with_forced_byte_order(
+            file.as_slice(),
+            Endianness::LittleEndian,
+        )?;
+        assert_eq!(
+            reader.current_section().byte_order,
+            Endianness::LittleEndian
+        );
+        assert_eq!(reader.read_all_packets()?, expected);
+
+        // A damaged byte-order magic is only readable with a forced byte order
+        let mut damaged = file.clone();
+        damaged[8..12].copy_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+        assert!(SyncPcapNgReader::new(damaged.as_slice()).is_err());
+        let reader = SyncPcapNgReader::new_with_forced_byte_order(
+            damaged.as_slice(),
+            Endianness::LittleEndian,
+        )?;
+        assert_eq!(reader.read_all_packets()?.len(), expected.len());
+        Ok(())
+    }
 }
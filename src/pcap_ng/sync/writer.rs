@@ -0,0 +1,285 @@
+//! Synchronous pcap-ng writer
+use std::{
+    borrow::Cow,
+    io::{self, Write},
+};
+
+use crate::{
+    byte_order::Endianness,
+    pcap_ng::blocks::{
+        Block, EnhancedPacket, InterfaceDescriptionBlock, SectionHeaderBlock, SimplePacket,
+    },
+};
+
+/// A Sync Pcap-NG Writer
+///
+/// Writes a single section. The section header is written on creation and
+/// interfaces must be added with [`Self::add_interface`] before packets
+/// referencing them are written.
+pub struct SyncPcapNgWriter<W: Write> {
+    target: W,
+    section: SectionHeaderBlock,
+    interfaces: Vec<InterfaceDescriptionBlock>,
+    /// Total bytes written including the section header
+    bytes_written: u64,
+    packets_written: u64,
+}
+impl<W: Write> SyncPcapNgWriter<W> {
+    /// Creates a new writer and immediately writes the section header block to `target`
+    ///
+    /// The byte order of the section header is used for every block written.
+    pub fn new(mut target: W, section: SectionHeaderBlock) -> Result<Self, io::Error> {
+        let mut writer = CountingWriter::new(&mut target);
+        section.write(&mut writer, section.byte_order)?;
+        let bytes_written = writer.count;
+        Ok(Self {
+            target,
+            section,
+            interfaces: Vec::with_capacity(1),
+            bytes_written,
+            packets_written: 0,
+        })
+    }
+    /// The byte order every block is written in
+    pub fn byte_order(&self) -> Endianness {
+        self.section.byte_order
+    }
+    /// The section header that was written
+    pub fn section(&self) -> &SectionHeaderBlock {
+        &self.section
+    }
+    /// The interfaces that have been written
+    pub fn interfaces(&self) -> &[InterfaceDescriptionBlock] {
+        &self.interfaces
+    }
+    /// Writes an interface description block and returns its interface id
+    pub fn add_interface(
+        &mut self,
+        interface: InterfaceDescriptionBlock,
+    ) -> Result<u32, io::Error> {
+        let mut writer = CountingWriter::new(&mut self.target);
+        interface.write(&mut writer, self.section.byte_order)?;
+        self.bytes_written += writer.count;
+        self.interfaces.push(interface);
+        Ok((self.interfaces.len() - 1) as u32)
+    }
+    /// Writes an enhanced packet block
+    ///
+    /// Returns an [`io::ErrorKind::InvalidInput`] error if the packet references an interface that has not been added
+    pub fn write_enhanced_packet(&mut self, packet: &EnhancedPacket<'_>) -> Result<(), io::Error> {
+        if packet.interface_id as usize >= self.interfaces.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Packet references undeclared interface {}",
+                    packet.interface_id
+                ),
+            ));
+        }
+        let mut writer = CountingWriter::new(&mut self.target);
+        packet.write(&mut writer, self.section.byte_order)?;
+        self.bytes_written += writer.count;
+        self.packets_written += 1;
+        Ok(())
+    }
+    /// Writes an enhanced packet block for `content` on `interface_id`
+    ///
+    /// `timestamp` is in units of the interface's timestamp resolution.
+    /// `original_length` defaults to the length of `content`
+    pub fn write_packet(
+        &mut self,
+        interface_id: u32,
+        timestamp: u64,
+        content: &[u8],
+        original_length: Option<u32>,
+    ) -> Result<(), io::Error> {
+        self.write_enhanced_packet(&EnhancedPacket {
+            block_length: 0,
+            interface_id,
+            timestamp_high: (timestamp >> 32) as u32,
+            timestamp_low: timestamp as u32,
+            captured_length: content.len() as u32,
+            original_length: original_length.unwrap_or(content.len() as u32),
+            content: Cow::Borrowed(content),
+            options: None,
+        })
+    }
+    /// Writes a simple packet block
+    ///
+    /// Simple packets belong to the first interface so one must have been added
+    pub fn write_simple_packet(&mut self, packet: &SimplePacket<'_>) -> Result<(), io::Error> {
+        if self.interfaces.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Simple packets require an interface to be declared",
+            ));
+        }
+        let mut writer = CountingWriter::new(&mut self.target);
+        packet.write(&mut writer, self.section.byte_order)?;
+        self.bytes_written += writer.count;
+        self.packets_written += 1;
+        Ok(())
+    }
+    /// Total number of bytes written to the target, including the section header
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+    /// Number of packets written to the target
+    pub fn packets_written(&self) -> u64 {
+        self.packets_written
+    }
+    /// Flushes the target writer.
+    pub fn finish(mut self) -> Result<(), io::Error> {
+        self.target.flush()?;
+        Ok(())
+    }
+    /// Consumes the writer and returns the underlying target
+    pub fn into_inner(self) -> W {
+        self.target
+    }
+}
+/// Counts the bytes written through it
+struct CountingWriter<'w, W: Write> {
+    inner: &'w mut W,
+    count: u64,
+}
+impl<'w, W: Write> CountingWriter<'w, W> {
+    fn new(inner: &'w mut W) -> Self {
+        Self { inner, count: 0 }
+    }
+}
+impl<W: Write> Write for CountingWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written as u64;
+        Ok(written)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    use crate::{
+        any_reader::AnyPacketHeader, link_type::LinkType, pcap_ng::SyncPcapNgReader,
+        pcap_ng::blocks::PcapNgBlock, pcap_ng::options::OptionsBuilder,
+    };
+
+    #[test]
+    fn write_read_round_trip() -> anyhow::Result<()> {
+        for byte_order in [Endianness::LittleEndian, Endianness::BigEndian] {
+            let mut writer =
+                SyncPcapNgWriter::new(Vec::new(), SectionHeaderBlock::new(byte_order))?;
+            assert_eq!(
+                writer.add_interface(InterfaceDescriptionBlock::new(LinkType::Ethernet, 0))?,
+                0
+            );
+            assert_eq!(
+                writer.add_interface(InterfaceDescriptionBlock::new(LinkType::Raw, 1500))?,
+                1
+            );
+            writer.write_packet(1, 1_000_001, &[1, 2, 3], Some(10))?;
+            writer.write_simple_packet(&SimplePacket {
+                block_length: 0,
+                original_length: 5,
+                content: Cow::Borrowed(&[4, 5, 6, 7, 8]),
+            })?;
+            assert_eq!(writer.packets_written(), 2);
+            let bytes_written = writer.bytes_written();
+            let file = writer.into_inner();
+            assert_eq!(bytes_written, file.len() as u64);
+
+            let mut reader = SyncPcapNgReader::new(file.as_slice())?;
+            assert_eq!(reader.current_section().byte_order, byte_order);
+            let (header, interface, data) = reader
+                .next_packet_with_interface()?
+                .expect("enhanced packet");
+            assert_eq!(interface.link_type, LinkType::Raw);
+            assert_eq!(interface.snap_length, 1500);
+            assert_eq!(data, &[1, 2, 3]);
+            let AnyPacketHeader::PcapNgEnhanced {
+                timestamp_high,
+                timestamp_low,
+                original_length,
+                ..
+            } = header
+            else {
+                panic!("expected an enhanced packet header, got {header:?}");
+            };
+            assert_eq!((timestamp_high, timestamp_low), (0, 1_000_001));
+            assert_eq!(original_length, 10);
+
+            let Some(PcapNgBlock::SimplePacket(packet)) = reader.next_block()? else {
+                panic!("expected a simple packet");
+            };
+            assert_eq!(*packet.content, [4, 5, 6, 7, 8]);
+            assert!(reader.next_block()?.is_none());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn undeclared_interface() -> anyhow::Result<()> {
+        let mut writer = SyncPcapNgWriter::new(
+            Vec::new(),
+            SectionHeaderBlock::new(Endianness::LittleEndian),
+        )?;
+        let err = writer
+            .write_packet(0, 0, &[1], None)
+            .expect_err("no interface was added");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        Ok(())
+    }
+
+    /// Writes a deterministic capture and compares it against the checked in fixture
+    ///
+    /// The odd content and comment lengths exercise the padding of packet data and options
+    fn write_fixture(byte_order: Endianness, name: &str) -> anyhow::Result<()> {
+        let (actual, expected) = crate::test_helpers::test_files(name)?;
+        let mut section = SectionHeaderBlock::new(byte_order);
+        section.options = Some(OptionsBuilder::new().comment("fixture").build()?);
+        let mut writer = SyncPcapNgWriter::new(File::create(&actual)?, section)?;
+        writer.add_interface(InterfaceDescriptionBlock::new(LinkType::Ethernet, 0xFFFF))?;
+        writer.write_packet(0, 0x0005_E0A1_B2C3_D4E5, &[1, 2, 3], None)?;
+        writer.write_enhanced_packet(&EnhancedPacket {
+            block_length: 0,
+            interface_id: 0,
+            timestamp_high: 0x0005_E0A1,
+            timestamp_low: 0xB2C3_D4E6,
+            captured_length: 4,
+            original_length: 60,
+            content: Cow::Borrowed(&[4, 5, 6, 7]),
+            options: Some(OptionsBuilder::new().comment("odd").build()?),
+        })?;
+        writer.write_packet(0, 0x0005_E0A1_B2C3_D4E7, &[0xAB; 0x41], None)?;
+        writer.finish()?;
+
+        let mut reader = SyncPcapNgReader::new(File::open(&actual)?)?;
+        assert_eq!(reader.current_section().byte_order, byte_order);
+        let mut packets = 0;
+        while reader.next_packet()?.is_some() {
+            packets += 1;
+        }
+        assert_eq!(packets, 3);
+
+        crate::test_helpers::do_files_match(actual, expected)?;
+        Ok(())
+    }
+
+    #[test]
+    fn fixture_little_endian() -> anyhow::Result<()> {
+        write_fixture(
+            Endianness::LittleEndian,
+            "sync_ng_writer_little_endian.pcapng",
+        )
+    }
+
+    #[test]
+    fn fixture_big_endian() -> anyhow::Result<()> {
+        write_fixture(Endianness::BigEndian, "sync_ng_writer_big_endian.pcapng")
+    }
+}
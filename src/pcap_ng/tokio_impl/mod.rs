@@ -1,12 +1,39 @@
 //! Asynchronous PCAP io
 //!
 //! Requires the `tokio-async` feature
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::pcap_ng::{PcapNgParseError, blocks::BlockHeader};
 mod reader;
 pub use reader::AsyncPcapNgReader;
 
+mod writer;
+pub use writer::AsyncPcapNgWriter;
+
 mod pooled_reader;
 pub use pooled_reader::{AsyncPooledPcapNgReader, DEFAULT_BUFFER_SIZE, PooledNgPacket};
 
 // Re-exported for discoverability: the pooled pcap-ng reader shares the same
 // lock-free buffer pool as the pcap one.
 pub use crate::buffer_pool::{BufferPool, PooledPacket};
+
+/// Reads the 8 byte header of the next block
+///
+/// Returns `Ok(None)` if the source ends before the header and [`PcapNgParseError::TruncatedBlock`]
+/// if it ends inside the header.
+pub(crate) async fn read_block_header<R: AsyncRead + Unpin>(
+    reader: &mut R,
+) -> Result<Option<BlockHeader>, PcapNgParseError> {
+    let mut header_bytes = [0u8; 8];
+    let mut read = 0;
+    while read < header_bytes.len() {
+        match reader.read(&mut header_bytes[read..]).await {
+            Ok(0) if read == 0 => return Ok(None), // No more blocks
+            Ok(0) => return Err(PcapNgParseError::TruncatedBlock { read }),
+            Ok(n) => read += n,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(PcapNgParseError::IO(err)),
+        }
+    }
+    BlockHeader::parse_from_bytes(&header_bytes).map(Some)
+}
@@ -15,7 +15,8 @@ use crate::{
             TokioAsyncBlock,
         },
         options::BlockOptions,
-        pad_length_to_32_bytes,
+        padding::pad_to_32,
+        tokio_impl::read_block_header,
     },
 };
 
@@ -130,6 +131,12 @@ impl<R: AsyncRead + Unpin> AsyncPooledPcapNgReader<R> {
     pub fn interfaces(&self) -> &[InterfaceDescriptionBlock] {
         &self.interfaces
     }
+    /// Consumes the reader and returns the underlying source
+    ///
+    /// The source is positioned after the last block read
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
 
     /// Returns a reference to the buffer pool (e.g. to `recycle` batches).
     pub fn pool(&self) -> &BufferPool {
@@ -146,13 +153,9 @@ impl<R: AsyncRead + Unpin> AsyncPooledPcapNgReader<R> {
     /// If all pool buffers are in use, this awaits until one is returned.
     pub async fn next_packet(&mut self) -> Result<Option<PooledNgPacket>, PcapNgParseError> {
         loop {
-            let mut header_bytes = [0u8; 8];
-            match self.reader.read_exact(&mut header_bytes).await {
-                Ok(_) => {}
-                Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
-                Err(err) => return Err(PcapNgParseError::IO(err)),
-            }
-            let header = BlockHeader::parse_from_bytes(&header_bytes)?;
+            let Some(header) = read_block_header(&mut self.reader).await? else {
+                return Ok(None);
+            };
             let byte_order = self.current_section.byte_order;
 
             match header.block_id_as_u32(byte_order) {
@@ -211,7 +214,7 @@ impl<R: AsyncRead + Unpin> AsyncPooledPcapNgReader<R> {
 
         let block_length = header.block_length_as_u32(byte_order);
         let captured = captured_length as usize;
-        let padded = pad_length_to_32_bytes(captured);
+        let padded = pad_to_32(captured);
 
         // EPB minimum_size = 32 = 8 (BlockHeader) + 20 (fixed) + 4 (footer).
         // Guard against a corrupt block length that would desync the stream.
@@ -237,6 +240,7 @@ impl<R: AsyncRead + Unpin> AsyncPooledPcapNgReader<R> {
             // Footer (trailing block length).
             let mut footer = [0u8; 4];
             self.reader.read_exact(&mut footer).await?;
+            header.check_trailing_length(footer)?;
             Ok::<_, PcapNgParseError>(options)
         }
         .await;
@@ -299,6 +303,7 @@ impl<R: AsyncRead + Unpin> AsyncPooledPcapNgReader<R> {
             // Footer (trailing block length).
             let mut footer = [0u8; 4];
             self.reader.read_exact(&mut footer).await?;
+            header.check_trailing_length(footer)?;
             Ok::<_, PcapNgParseError>(())
         }
         .await;
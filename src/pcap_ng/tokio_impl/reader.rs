@@ -9,17 +9,21 @@ use crate::{
             BlockHeader, InterfaceDescriptionBlock, PcapNgBlock, SectionHeaderBlock,
             TokioAsyncBlock,
         },
+        tokio_impl::read_block_header,
     },
 };
-use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::io::AsyncRead;
 /// Asynchronous PCAP-NG reader
 ///
 /// Requires the `tokio-async` feature
 ///
 /// ## Notes
 ///
-/// It is recommended to use a buffered reader (e.g., [`tokio::io::BufReader`]) for better performance
-/// Due to the nature of this parser it involves many small reads, which can be inefficient on unbuffered readers
+/// Each block is read with two reads: the 8 byte block header, then the rest of the block in one chunk
+/// which is parsed from memory. Section header blocks and custom blocks are read field by field.
+///
+/// On unbuffered sources with many small blocks a buffered reader (e.g., [`tokio::io::BufReader`]) still reduces the
+/// number of reads made on the source.
 #[derive(Debug)]
 pub struct AsyncPcapNgReader<R: AsyncRead + Unpin> {
     reader: R,
@@ -31,6 +35,8 @@ pub struct AsyncPcapNgReader<R: AsyncRead + Unpin> {
     interfaces: Vec<InterfaceDescriptionBlock>,
     /// Reusable scratch buffer for packet contents.
     buffer: Vec<u8>,
+    /// Reject blocks with options that must be UTF-8 but are not
+    validate_utf8_options: bool,
 }
 impl<R: AsyncRead + Unpin> AsyncPcapNgReader<R> {
     /// Creates a new `AsyncPcapNgReader` from a reader
@@ -51,6 +57,7 @@ impl<R: AsyncRead + Unpin> AsyncPcapNgReader<R> {
             current_section,
             interfaces: Vec::with_capacity(1),
             buffer,
+            validate_utf8_options: false,
         })
     }
     pub(crate) fn new_with_section(reader: R, current_section: SectionHeaderBlock) -> Self {
@@ -59,8 +66,16 @@ impl<R: AsyncRead + Unpin> AsyncPcapNgReader<R> {
             current_section,
             interfaces: Vec::with_capacity(1),
             buffer: Vec::with_capacity(65536), // Default buffer size
+            validate_utf8_options: false,
         }
     }
+    /// Enables or disables UTF-8 validation of string options
+    ///
+    /// See [`SyncPcapNgReader::validate_utf8_options`](crate::pcap_ng::SyncPcapNgReader::validate_utf8_options)
+    pub fn validate_utf8_options(mut self, validate_utf8_options: bool) -> Self {
+        self.validate_utf8_options = validate_utf8_options;
+        self
+    }
     /// Returns the file header of the pcap file
     pub fn current_section(&self) -> &SectionHeaderBlock {
         &self.current_section
@@ -77,21 +92,22 @@ impl<R: AsyncRead + Unpin> AsyncPcapNgReader<R> {
     pub fn interfaces(&self) -> &[InterfaceDescriptionBlock] {
         &self.interfaces
     }
+    /// Consumes the reader and returns the underlying source
+    ///
+    /// The source is positioned after the last block read
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
     /// Reads the next block from the pcapng file
     ///
     /// This starts by reading 8 bytes for the block header, then reads the rest of the block
     ///
-    /// When Ok(None) is returned, it indicates the end of the file has been reached
+    /// When Ok(None) is returned, it indicates the end of the file has been reached.
+    /// A file ending inside a block header returns [`PcapNgParseError::TruncatedBlock`]
     pub async fn next_block<'b>(&'b mut self) -> Result<Option<PcapNgBlock<'b>>, PcapNgParseError> {
-        let mut header_bytes = [0u8; 8];
-        match self.reader.read_exact(&mut header_bytes).await {
-            Ok(_) => {}
-            Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => {
-                return Ok(None); // No more blocks
-            }
-            Err(err) => return Err(PcapNgParseError::IO(err)),
-        }
-        let header = BlockHeader::parse_from_bytes(&header_bytes)?;
+        let Some(header) = read_block_header(&mut self.reader).await? else {
+            return Ok(None);
+        };
 
         let result = PcapNgBlock::<'b>::read_async(
             &mut self.reader,
@@ -100,6 +116,9 @@ impl<R: AsyncRead + Unpin> AsyncPcapNgReader<R> {
             &mut self.buffer,
         )
         .await?;
+        if self.validate_utf8_options {
+            result.validate_utf8_options()?;
+        }
         match &result {
             PcapNgBlock::InterfaceDescription(interface_block) => {
                 self.interfaces.push(interface_block.clone());
@@ -157,9 +176,67 @@ impl<R: AsyncRead + Unpin> AsyncPcapNgReader<R> {
 mod tests {
     use etherparse::{NetSlice, SlicedPacket};
 
-    use crate::byte_order::Endianness;
+    use std::{
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use tokio::io::ReadBuf;
+
+    use crate::{
+        byte_order::Endianness,
+        pcap_ng::{SyncPcapNgReader, blocks::Block},
+    };
 
     use super::*;
+    /// Counts the reads made on the inner reader
+    struct CountingReader<'a> {
+        inner: &'a [u8],
+        reads: usize,
+    }
+    impl AsyncRead for CountingReader<'_> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            self.reads += 1;
+            Pin::new(&mut self.inner).poll_read(cx, buf)
+        }
+    }
+    #[tokio::test]
+    async fn blocks_are_read_in_one_chunk() -> anyhow::Result<()> {
+        for file_name in [
+            "test_data/ng/test001_le.pcapng",
+            "test_data/ng/test001_be.pcapng",
+        ] {
+            let file = std::fs::read(file_name)?;
+            let counting = CountingReader {
+                inner: &file,
+                reads: 0,
+            };
+            let mut reader = AsyncPcapNgReader::new(counting).await?;
+            let mut blocks = Vec::new();
+            loop {
+                let reads_before = reader.reader.reads;
+                let Some(block) = reader.next_block().await? else {
+                    break;
+                };
+                let block = block.into_owned();
+                if !matches!(block, PcapNgBlock::SectionHeader(_)) {
+                    assert_eq!(
+                        reader.reader.reads - reads_before,
+                        2,
+                        "the block header and body should be read with one read each {block:?}"
+                    );
+                }
+                blocks.push(block);
+            }
+            // The chunked path parses the same blocks as the sync reader
+            assert_eq!(blocks, SyncPcapNgReader::new(file.as_slice())?.read_all()?);
+        }
+        Ok(())
+    }
     #[tokio::test]
     async fn read_packets_from_file() -> anyhow::Result<()> {
         let file = tokio::fs::File::open("test_data/ng/test001_le.pcapng").await?;
@@ -190,7 +267,7 @@ mod tests {
             };
             println!("---- Packet: (Block Length {}) ----", packet.block_length);
             let parse =
-                SlicedPacket::from_ethernet(packet.content).expect("Failed to parse packet");
+                SlicedPacket::from_ethernet(&packet.content).expect("Failed to parse packet");
             let Some(net_slice) = parse.net else {
                 panic!("Expected a network layer slice, got: {:?}", parse);
             };
@@ -212,4 +289,58 @@ mod tests {
         }
         Ok(())
     }
+
+    #[tokio::test]
+    async fn truncated_block_header() -> anyhow::Result<()> {
+        let file = std::fs::read("test_data/ng/test001_le.pcapng")?;
+        let mut reader = AsyncPcapNgReader::new(file.as_slice()).await?;
+        let mut blocks = 0;
+        while reader.next_block().await?.is_some() {
+            blocks += 1;
+        }
+        assert!(blocks > 0);
+
+        // The file ends 3 bytes into the header of another block
+        let mut truncated = file.clone();
+        truncated.extend_from_slice(&file[..3]);
+        let mut reader = AsyncPcapNgReader::new(truncated.as_slice()).await?;
+        for _ in 0..blocks {
+            assert!(reader.next_block().await?.is_some());
+        }
+        let err = reader
+            .next_block()
+            .await
+            .expect_err("the last block header is truncated");
+        assert!(
+            matches!(err, PcapNgParseError::TruncatedBlock { read: 3 }),
+            "unexpected error {err:?}"
+        );
+        Ok(())
+    }
+    #[tokio::test]
+    async fn block_below_minimum_size() -> anyhow::Result<()> {
+        let mut file = Vec::new();
+        SectionHeaderBlock::new(Endianness::LittleEndian)
+            .write(&mut file, Endianness::LittleEndian)?;
+        // Enhanced packet claiming 20 bytes, below its 32 byte minimum
+        let mut short_packet = file.clone();
+        short_packet.extend_from_slice(&[6, 0, 0, 0, 20, 0, 0, 0]);
+        short_packet.extend_from_slice(&[0; 12]);
+        // Interface description claiming less than its own block header
+        let mut short_interface = file;
+        short_interface.extend_from_slice(&[1, 0, 0, 0, 4, 0, 0, 0]);
+
+        for (file, expected) in [(short_packet, (32, 20)), (short_interface, (20, 4))] {
+            let mut reader = AsyncPcapNgReader::new(file.as_slice()).await?;
+            let err = reader
+                .next_block()
+                .await
+                .expect_err("block is below its minimum size");
+            assert!(
+                matches!(err, PcapNgParseError::MinimumSizeNotMet(minimum, got) if (minimum, got) == expected),
+                "unexpected error {err:?}"
+            );
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,208 @@
+//! Asynchronous pcap-ng writer
+use std::{borrow::Cow, io};
+
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::{
+    byte_order::Endianness,
+    pcap_ng::blocks::{
+        Block, EnhancedPacket, InterfaceDescriptionBlock, SectionHeaderBlock, SimplePacket,
+    },
+};
+
+/// An Async Pcap-NG Writer
+///
+/// The async counterpart to [`SyncPcapNgWriter`](crate::pcap_ng::writer::SyncPcapNgWriter).
+/// Each block is serialized into a reused buffer before being written to the target.
+pub struct AsyncPcapNgWriter<W: AsyncWrite + Unpin> {
+    target: W,
+    section: SectionHeaderBlock,
+    interfaces: Vec<InterfaceDescriptionBlock>,
+    /// Reused buffer for serializing blocks
+    buffer: Vec<u8>,
+    /// Total bytes written including the section header
+    bytes_written: u64,
+    packets_written: u64,
+}
+impl<W: AsyncWrite + Unpin> AsyncPcapNgWriter<W> {
+    /// Creates a new writer and immediately writes the section header block to `target`
+    ///
+    /// The byte order of the section header is used for every block written.
+    pub async fn new(target: W, section: SectionHeaderBlock) -> Result<Self, io::Error> {
+        let mut writer = Self {
+            target,
+            section,
+            interfaces: Vec::with_capacity(1),
+            buffer: Vec::new(),
+            bytes_written: 0,
+            packets_written: 0,
+        };
+        writer
+            .section
+            .write(&mut writer.buffer, writer.section.byte_order)?;
+        writer.flush_buffer().await?;
+        Ok(writer)
+    }
+    /// Writes the serialized block in `buffer` to the target
+    async fn flush_buffer(&mut self) -> Result<(), io::Error> {
+        self.target.write_all(&self.buffer).await?;
+        self.bytes_written += self.buffer.len() as u64;
+        self.buffer.clear();
+        Ok(())
+    }
+    /// The byte order every block is written in
+    pub fn byte_order(&self) -> Endianness {
+        self.section.byte_order
+    }
+    /// The section header that was written
+    pub fn section(&self) -> &SectionHeaderBlock {
+        &self.section
+    }
+    /// The interfaces that have been written
+    pub fn interfaces(&self) -> &[InterfaceDescriptionBlock] {
+        &self.interfaces
+    }
+    /// Writes an interface description block and returns its interface id
+    pub async fn add_interface(
+        &mut self,
+        interface: InterfaceDescriptionBlock,
+    ) -> Result<u32, io::Error> {
+        interface.write(&mut self.buffer, self.section.byte_order)?;
+        self.flush_buffer().await?;
+        self.interfaces.push(interface);
+        Ok((self.interfaces.len() - 1) as u32)
+    }
+    /// Writes an enhanced packet block
+    ///
+    /// Returns an [`io::ErrorKind::InvalidInput`] error if the packet references an interface that has not been added
+    pub async fn write_enhanced_packet(
+        &mut self,
+        packet: &EnhancedPacket<'_>,
+    ) -> Result<(), io::Error> {
+        if packet.interface_id as usize >= self.interfaces.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Packet references undeclared interface {}",
+                    packet.interface_id
+                ),
+            ));
+        }
+        packet.write(&mut self.buffer, self.section.byte_order)?;
+        self.flush_buffer().await?;
+        self.packets_written += 1;
+        Ok(())
+    }
+    /// Writes an enhanced packet block for `content` on `interface_id`
+    ///
+    /// `timestamp` is in units of the interface's timestamp resolution.
+    /// `original_length` defaults to the length of `content`
+    pub async fn write_packet(
+        &mut self,
+        interface_id: u32,
+        timestamp: u64,
+        content: &[u8],
+        original_length: Option<u32>,
+    ) -> Result<(), io::Error> {
+        self.write_enhanced_packet(&EnhancedPacket {
+            block_length: 0,
+            interface_id,
+            timestamp_high: (timestamp >> 32) as u32,
+            timestamp_low: timestamp as u32,
+            captured_length: content.len() as u32,
+            original_length: original_length.unwrap_or(content.len() as u32),
+            content: Cow::Borrowed(content),
+            options: None,
+        })
+        .await
+    }
+    /// Writes a simple packet block
+    ///
+    /// Simple packets belong to the first interface so one must have been added
+    pub async fn write_simple_packet(
+        &mut self,
+        packet: &SimplePacket<'_>,
+    ) -> Result<(), io::Error> {
+        if self.interfaces.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Simple packets require an interface to be declared",
+            ));
+        }
+        packet.write(&mut self.buffer, self.section.byte_order)?;
+        self.flush_buffer().await?;
+        self.packets_written += 1;
+        Ok(())
+    }
+    /// Total number of bytes written to the target, including the section header
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+    /// Number of packets written to the target
+    pub fn packets_written(&self) -> u64 {
+        self.packets_written
+    }
+    /// Flushes the target writer.
+    pub async fn finish(mut self) -> Result<(), io::Error> {
+        self.target.flush().await?;
+        Ok(())
+    }
+    /// Consumes the writer and returns the underlying target
+    pub fn into_inner(self) -> W {
+        self.target
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{any_reader::AnyPacketHeader, link_type::LinkType, pcap_ng::AsyncPcapNgReader};
+
+    #[tokio::test]
+    async fn write_read_round_trip() -> anyhow::Result<()> {
+        for byte_order in [Endianness::LittleEndian, Endianness::BigEndian] {
+            let mut writer =
+                AsyncPcapNgWriter::new(Vec::new(), SectionHeaderBlock::new(byte_order)).await?;
+            writer
+                .add_interface(InterfaceDescriptionBlock::new(LinkType::Ethernet, 0))
+                .await?;
+            writer
+                .write_packet(0, 1_000_001, &[1, 2, 3], Some(10))
+                .await?;
+            writer
+                .write_simple_packet(&SimplePacket {
+                    block_length: 0,
+                    original_length: 2,
+                    content: Cow::Borrowed(&[4, 5]),
+                })
+                .await?;
+            let err = writer
+                .write_packet(1, 0, &[1], None)
+                .await
+                .expect_err("interface 1 was not added");
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+            assert_eq!(writer.packets_written(), 2);
+            let bytes_written = writer.bytes_written();
+            let file = writer.into_inner();
+            assert_eq!(bytes_written, file.len() as u64);
+
+            let mut reader = AsyncPcapNgReader::new(file.as_slice()).await?;
+            assert_eq!(reader.current_section().byte_order, byte_order);
+            let (header, data) = reader.next_packet().await?.expect("enhanced packet");
+            assert_eq!(data, &[1, 2, 3]);
+            assert!(matches!(
+                header,
+                AnyPacketHeader::PcapNgEnhanced {
+                    timestamp_low: 1_000_001,
+                    original_length: 10,
+                    ..
+                }
+            ));
+            let (header, data) = reader.next_packet().await?.expect("simple packet");
+            assert_eq!(data, &[4, 5]);
+            assert!(matches!(header, AnyPacketHeader::PcapNgSimple { .. }));
+            assert!(reader.next_packet().await?.is_none());
+        }
+        Ok(())
+    }
+}
@@ -1,9 +1,40 @@
+use core::fmt;
+#[cfg(feature = "std")]
 use std::io::Read;
+/// Displays bytes from a file in error messages
+///
+/// Renders as hex (`0x0A0D0D0A`) followed by the ASCII text when every byte is printable
+pub(crate) struct HexBytes<'a>(pub &'a [u8]);
+impl fmt::Display for HexBytes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "no bytes");
+        }
+        write!(f, "0x")?;
+        for byte in self.0 {
+            write!(f, "{byte:02X}")?;
+        }
+        if self
+            .0
+            .iter()
+            .all(|byte| byte.is_ascii_graphic() || *byte == b' ')
+        {
+            write!(f, " (\"")?;
+            for byte in self.0 {
+                write!(f, "{}", *byte as char)?;
+            }
+            write!(f, "\")")?;
+        }
+        Ok(())
+    }
+}
+#[cfg(feature = "std")]
 /// A reader that allows peeking into the first N bytes without consuming them
 pub struct PeakableReader<R: Read> {
     inner: R,
     peeked: Option<Vec<u8>>,
 }
+#[cfg(feature = "std")]
 impl<R: Read> PeakableReader<R> {
     /// Creates a new `PeakableReader` that reads the first `peek_size` bytes
     pub fn new(mut inner: R, peek_size: usize) -> std::io::Result<Self> {
@@ -20,6 +51,7 @@ impl<R: Read> PeakableReader<R> {
         self.peeked.as_deref()
     }
 }
+#[cfg(feature = "std")]
 impl<R: Read> Read for PeakableReader<R> {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         if let Some(peeked) = &mut self.peeked {
@@ -93,7 +125,17 @@ pub mod tokio_impl {
 mod tests {
     use std::io::Read;
 
-    use crate::utils::PeakableReader;
+    use crate::utils::{HexBytes, PeakableReader};
+
+    #[test]
+    fn test_hex_bytes() {
+        assert_eq!(
+            HexBytes(&[0x0A, 0x0D, 0x0D, 0x0A]).to_string(),
+            "0x0A0D0D0A"
+        );
+        assert_eq!(HexBytes(b"PCAP").to_string(), "0x50434150 (\"PCAP\")");
+        assert_eq!(HexBytes(&[]).to_string(), "no bytes");
+    }
 
     #[test]
     fn test_peak_reader() {